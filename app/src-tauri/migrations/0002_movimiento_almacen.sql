@@ -0,0 +1,3 @@
+-- Almacén afectado por cada movimiento. Los movimientos existentes quedan sin
+-- almacén y no se reflejan en stock_almacen.
+ALTER TABLE movimiento ADD COLUMN id_almacen INTEGER REFERENCES almacen(id_almacen);
@@ -0,0 +1,31 @@
+-- Lotes PEPS abiertos por las entradas y consumos registrados por las salidas
+CREATE TABLE lote (
+    id_lote INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_movimiento INTEGER NOT NULL,
+    id_prod_prov INTEGER NOT NULL,
+    id_presentacion INTEGER NOT NULL,
+    id_almacen INTEGER NOT NULL,
+    numero_lote TEXT,
+    fecha_entrada TEXT NOT NULL,
+    fecha_venc TEXT,
+    cantidad_inicial REAL NOT NULL,
+    cantidad_actual REAL NOT NULL,
+    costo_unitario REAL NOT NULL DEFAULT 0,
+    FOREIGN KEY (id_movimiento) REFERENCES movimiento(id_movimiento),
+    FOREIGN KEY (id_prod_prov) REFERENCES producto_proveedor(id_prod_prov),
+    FOREIGN KEY (id_presentacion) REFERENCES presentacion(id_presentacion),
+    FOREIGN KEY (id_almacen) REFERENCES almacen(id_almacen)
+);
+
+CREATE TABLE lote_consumo (
+    id_consumo INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_movimiento INTEGER NOT NULL,
+    id_lote INTEGER NOT NULL,
+    cantidad REAL NOT NULL,
+    costo_unitario REAL NOT NULL,
+    FOREIGN KEY (id_movimiento) REFERENCES movimiento(id_movimiento),
+    FOREIGN KEY (id_lote) REFERENCES lote(id_lote)
+);
+
+CREATE INDEX idx_lote_ubicacion ON lote (id_prod_prov, id_presentacion, id_almacen);
+CREATE INDEX idx_lote_consumo_movimiento ON lote_consumo (id_movimiento);
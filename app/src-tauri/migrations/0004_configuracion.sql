@@ -0,0 +1,8 @@
+-- Parámetros globales (id_almacen nulo) o por almacén
+CREATE TABLE configuracion (
+    id_configuracion INTEGER PRIMARY KEY AUTOINCREMENT,
+    clave TEXT NOT NULL,
+    id_almacen INTEGER,
+    valor TEXT NOT NULL,
+    FOREIGN KEY (id_almacen) REFERENCES almacen(id_almacen)
+);
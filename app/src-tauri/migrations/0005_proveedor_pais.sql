@@ -0,0 +1,2 @@
+-- País del proveedor (ISO 3166-1 alfa-2), define cómo se valida ruc_ci
+ALTER TABLE proveedor ADD COLUMN pais TEXT NOT NULL DEFAULT 'NI';
@@ -0,0 +1,13 @@
+-- Archivado (borrado lógico) de productos, proveedores y almacenes
+ALTER TABLE producto ADD COLUMN eliminado_en TEXT;
+ALTER TABLE producto ADD COLUMN eliminado_por TEXT;
+ALTER TABLE proveedor ADD COLUMN eliminado_en TEXT;
+ALTER TABLE proveedor ADD COLUMN eliminado_por TEXT;
+ALTER TABLE almacen ADD COLUMN eliminado_en TEXT;
+ALTER TABLE almacen ADD COLUMN eliminado_por TEXT;
+
+-- Usuario que opera la aplicación; una sola fila
+CREATE TABLE sesion (
+    id_sesion INTEGER PRIMARY KEY CHECK (id_sesion = 1),
+    usuario TEXT NOT NULL
+);
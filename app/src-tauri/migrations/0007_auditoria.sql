@@ -0,0 +1,586 @@
+-- Historial de cambios. Cada tabla auditada tiene disparadores que registran
+-- el estado anterior y posterior de la fila en la misma transacción que el
+-- cambio. Una migración que agregue columnas a una tabla auditada debe volver
+-- a crear sus disparadores para incluirlas.
+CREATE TABLE auditoria (
+    id_auditoria INTEGER PRIMARY KEY AUTOINCREMENT,
+    entidad TEXT NOT NULL,
+    id_registro INTEGER NOT NULL,
+    operacion TEXT NOT NULL CHECK (operacion IN ('INSERT', 'UPDATE', 'DELETE')),
+    antes TEXT,
+    despues TEXT,
+    fecha TEXT NOT NULL DEFAULT (datetime('now')),
+    usuario TEXT
+);
+
+CREATE INDEX idx_auditoria_registro ON auditoria (entidad, id_registro);
+CREATE INDEX idx_auditoria_fecha ON auditoria (fecha);
+
+-- producto
+CREATE TRIGGER auditoria_producto_insert AFTER INSERT ON producto
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('producto', NEW.id_producto, 'INSERT', NULL, json_object(
+        'id_producto', NEW.id_producto,
+        'codigo_interno', NEW.codigo_interno,
+        'descripcion', NEW.descripcion,
+        'categoria', NEW.categoria,
+        'subcategoria', NEW.subcategoria,
+        'estado', NEW.estado,
+        'eliminado_en', NEW.eliminado_en,
+        'eliminado_por', NEW.eliminado_por
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_producto_update AFTER UPDATE ON producto
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('producto', NEW.id_producto, 'UPDATE', json_object(
+        'id_producto', OLD.id_producto,
+        'codigo_interno', OLD.codigo_interno,
+        'descripcion', OLD.descripcion,
+        'categoria', OLD.categoria,
+        'subcategoria', OLD.subcategoria,
+        'estado', OLD.estado,
+        'eliminado_en', OLD.eliminado_en,
+        'eliminado_por', OLD.eliminado_por
+    ), json_object(
+        'id_producto', NEW.id_producto,
+        'codigo_interno', NEW.codigo_interno,
+        'descripcion', NEW.descripcion,
+        'categoria', NEW.categoria,
+        'subcategoria', NEW.subcategoria,
+        'estado', NEW.estado,
+        'eliminado_en', NEW.eliminado_en,
+        'eliminado_por', NEW.eliminado_por
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_producto_delete AFTER DELETE ON producto
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('producto', OLD.id_producto, 'DELETE', json_object(
+        'id_producto', OLD.id_producto,
+        'codigo_interno', OLD.codigo_interno,
+        'descripcion', OLD.descripcion,
+        'categoria', OLD.categoria,
+        'subcategoria', OLD.subcategoria,
+        'estado', OLD.estado,
+        'eliminado_en', OLD.eliminado_en,
+        'eliminado_por', OLD.eliminado_por
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+-- proveedor
+CREATE TRIGGER auditoria_proveedor_insert AFTER INSERT ON proveedor
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('proveedor', NEW.id_proveedor, 'INSERT', NULL, json_object(
+        'id_proveedor', NEW.id_proveedor,
+        'ruc_ci', NEW.ruc_ci,
+        'nombre', NEW.nombre,
+        'contacto', NEW.contacto,
+        'telefono', NEW.telefono,
+        'email', NEW.email,
+        'estado', NEW.estado,
+        'pais', NEW.pais,
+        'eliminado_en', NEW.eliminado_en,
+        'eliminado_por', NEW.eliminado_por
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_proveedor_update AFTER UPDATE ON proveedor
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('proveedor', NEW.id_proveedor, 'UPDATE', json_object(
+        'id_proveedor', OLD.id_proveedor,
+        'ruc_ci', OLD.ruc_ci,
+        'nombre', OLD.nombre,
+        'contacto', OLD.contacto,
+        'telefono', OLD.telefono,
+        'email', OLD.email,
+        'estado', OLD.estado,
+        'pais', OLD.pais,
+        'eliminado_en', OLD.eliminado_en,
+        'eliminado_por', OLD.eliminado_por
+    ), json_object(
+        'id_proveedor', NEW.id_proveedor,
+        'ruc_ci', NEW.ruc_ci,
+        'nombre', NEW.nombre,
+        'contacto', NEW.contacto,
+        'telefono', NEW.telefono,
+        'email', NEW.email,
+        'estado', NEW.estado,
+        'pais', NEW.pais,
+        'eliminado_en', NEW.eliminado_en,
+        'eliminado_por', NEW.eliminado_por
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_proveedor_delete AFTER DELETE ON proveedor
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('proveedor', OLD.id_proveedor, 'DELETE', json_object(
+        'id_proveedor', OLD.id_proveedor,
+        'ruc_ci', OLD.ruc_ci,
+        'nombre', OLD.nombre,
+        'contacto', OLD.contacto,
+        'telefono', OLD.telefono,
+        'email', OLD.email,
+        'estado', OLD.estado,
+        'pais', OLD.pais,
+        'eliminado_en', OLD.eliminado_en,
+        'eliminado_por', OLD.eliminado_por
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+-- almacen
+CREATE TRIGGER auditoria_almacen_insert AFTER INSERT ON almacen
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('almacen', NEW.id_almacen, 'INSERT', NULL, json_object(
+        'id_almacen', NEW.id_almacen,
+        'nombre', NEW.nombre,
+        'ubicacion', NEW.ubicacion,
+        'responsable', NEW.responsable,
+        'eliminado_en', NEW.eliminado_en,
+        'eliminado_por', NEW.eliminado_por
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_almacen_update AFTER UPDATE ON almacen
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('almacen', NEW.id_almacen, 'UPDATE', json_object(
+        'id_almacen', OLD.id_almacen,
+        'nombre', OLD.nombre,
+        'ubicacion', OLD.ubicacion,
+        'responsable', OLD.responsable,
+        'eliminado_en', OLD.eliminado_en,
+        'eliminado_por', OLD.eliminado_por
+    ), json_object(
+        'id_almacen', NEW.id_almacen,
+        'nombre', NEW.nombre,
+        'ubicacion', NEW.ubicacion,
+        'responsable', NEW.responsable,
+        'eliminado_en', NEW.eliminado_en,
+        'eliminado_por', NEW.eliminado_por
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_almacen_delete AFTER DELETE ON almacen
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('almacen', OLD.id_almacen, 'DELETE', json_object(
+        'id_almacen', OLD.id_almacen,
+        'nombre', OLD.nombre,
+        'ubicacion', OLD.ubicacion,
+        'responsable', OLD.responsable,
+        'eliminado_en', OLD.eliminado_en,
+        'eliminado_por', OLD.eliminado_por
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+-- presentacion
+CREATE TRIGGER auditoria_presentacion_insert AFTER INSERT ON presentacion
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('presentacion', NEW.id_presentacion, 'INSERT', NULL, json_object(
+        'id_presentacion', NEW.id_presentacion,
+        'id_producto', NEW.id_producto,
+        'unidad', NEW.unidad,
+        'cantidad', NEW.cantidad,
+        'descripcion', NEW.descripcion
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_presentacion_update AFTER UPDATE ON presentacion
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('presentacion', NEW.id_presentacion, 'UPDATE', json_object(
+        'id_presentacion', OLD.id_presentacion,
+        'id_producto', OLD.id_producto,
+        'unidad', OLD.unidad,
+        'cantidad', OLD.cantidad,
+        'descripcion', OLD.descripcion
+    ), json_object(
+        'id_presentacion', NEW.id_presentacion,
+        'id_producto', NEW.id_producto,
+        'unidad', NEW.unidad,
+        'cantidad', NEW.cantidad,
+        'descripcion', NEW.descripcion
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_presentacion_delete AFTER DELETE ON presentacion
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('presentacion', OLD.id_presentacion, 'DELETE', json_object(
+        'id_presentacion', OLD.id_presentacion,
+        'id_producto', OLD.id_producto,
+        'unidad', OLD.unidad,
+        'cantidad', OLD.cantidad,
+        'descripcion', OLD.descripcion
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+-- producto_proveedor
+CREATE TRIGGER auditoria_producto_proveedor_insert AFTER INSERT ON producto_proveedor
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('producto_proveedor', NEW.id_prod_prov, 'INSERT', NULL, json_object(
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_producto', NEW.id_producto,
+        'id_proveedor', NEW.id_proveedor,
+        'codigo_proveedor', NEW.codigo_proveedor,
+        'estado', NEW.estado
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_producto_proveedor_update AFTER UPDATE ON producto_proveedor
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('producto_proveedor', NEW.id_prod_prov, 'UPDATE', json_object(
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_producto', OLD.id_producto,
+        'id_proveedor', OLD.id_proveedor,
+        'codigo_proveedor', OLD.codigo_proveedor,
+        'estado', OLD.estado
+    ), json_object(
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_producto', NEW.id_producto,
+        'id_proveedor', NEW.id_proveedor,
+        'codigo_proveedor', NEW.codigo_proveedor,
+        'estado', NEW.estado
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_producto_proveedor_delete AFTER DELETE ON producto_proveedor
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('producto_proveedor', OLD.id_prod_prov, 'DELETE', json_object(
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_producto', OLD.id_producto,
+        'id_proveedor', OLD.id_proveedor,
+        'codigo_proveedor', OLD.codigo_proveedor,
+        'estado', OLD.estado
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+-- factura
+CREATE TRIGGER auditoria_factura_insert AFTER INSERT ON factura
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('factura', NEW.id_factura, 'INSERT', NULL, json_object(
+        'id_factura', NEW.id_factura,
+        'numero', NEW.numero,
+        'fecha', NEW.fecha,
+        'id_proveedor', NEW.id_proveedor,
+        'total', NEW.total,
+        'estado', NEW.estado
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_factura_update AFTER UPDATE ON factura
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('factura', NEW.id_factura, 'UPDATE', json_object(
+        'id_factura', OLD.id_factura,
+        'numero', OLD.numero,
+        'fecha', OLD.fecha,
+        'id_proveedor', OLD.id_proveedor,
+        'total', OLD.total,
+        'estado', OLD.estado
+    ), json_object(
+        'id_factura', NEW.id_factura,
+        'numero', NEW.numero,
+        'fecha', NEW.fecha,
+        'id_proveedor', NEW.id_proveedor,
+        'total', NEW.total,
+        'estado', NEW.estado
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_factura_delete AFTER DELETE ON factura
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('factura', OLD.id_factura, 'DELETE', json_object(
+        'id_factura', OLD.id_factura,
+        'numero', OLD.numero,
+        'fecha', OLD.fecha,
+        'id_proveedor', OLD.id_proveedor,
+        'total', OLD.total,
+        'estado', OLD.estado
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+-- movimiento
+CREATE TRIGGER auditoria_movimiento_insert AFTER INSERT ON movimiento
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('movimiento', NEW.id_movimiento, 'INSERT', NULL, json_object(
+        'id_movimiento', NEW.id_movimiento,
+        'fecha', NEW.fecha,
+        'tipo', NEW.tipo,
+        'subtipo', NEW.subtipo,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'cantidad', NEW.cantidad,
+        'precio_unit', NEW.precio_unit,
+        'monto_total', NEW.monto_total,
+        'lote', NEW.lote,
+        'fecha_venc', NEW.fecha_venc,
+        'obs', NEW.obs,
+        'id_factura', NEW.id_factura,
+        'id_almacen', NEW.id_almacen
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_movimiento_update AFTER UPDATE ON movimiento
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('movimiento', NEW.id_movimiento, 'UPDATE', json_object(
+        'id_movimiento', OLD.id_movimiento,
+        'fecha', OLD.fecha,
+        'tipo', OLD.tipo,
+        'subtipo', OLD.subtipo,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'cantidad', OLD.cantidad,
+        'precio_unit', OLD.precio_unit,
+        'monto_total', OLD.monto_total,
+        'lote', OLD.lote,
+        'fecha_venc', OLD.fecha_venc,
+        'obs', OLD.obs,
+        'id_factura', OLD.id_factura,
+        'id_almacen', OLD.id_almacen
+    ), json_object(
+        'id_movimiento', NEW.id_movimiento,
+        'fecha', NEW.fecha,
+        'tipo', NEW.tipo,
+        'subtipo', NEW.subtipo,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'cantidad', NEW.cantidad,
+        'precio_unit', NEW.precio_unit,
+        'monto_total', NEW.monto_total,
+        'lote', NEW.lote,
+        'fecha_venc', NEW.fecha_venc,
+        'obs', NEW.obs,
+        'id_factura', NEW.id_factura,
+        'id_almacen', NEW.id_almacen
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_movimiento_delete AFTER DELETE ON movimiento
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('movimiento', OLD.id_movimiento, 'DELETE', json_object(
+        'id_movimiento', OLD.id_movimiento,
+        'fecha', OLD.fecha,
+        'tipo', OLD.tipo,
+        'subtipo', OLD.subtipo,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'cantidad', OLD.cantidad,
+        'precio_unit', OLD.precio_unit,
+        'monto_total', OLD.monto_total,
+        'lote', OLD.lote,
+        'fecha_venc', OLD.fecha_venc,
+        'obs', OLD.obs,
+        'id_factura', OLD.id_factura,
+        'id_almacen', OLD.id_almacen
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+-- stock_almacen
+CREATE TRIGGER auditoria_stock_almacen_insert AFTER INSERT ON stock_almacen
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('stock_almacen', NEW.id_stock, 'INSERT', NULL, json_object(
+        'id_stock', NEW.id_stock,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'id_almacen', NEW.id_almacen,
+        'stock_actual', NEW.stock_actual
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_stock_almacen_update AFTER UPDATE ON stock_almacen
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('stock_almacen', NEW.id_stock, 'UPDATE', json_object(
+        'id_stock', OLD.id_stock,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'id_almacen', OLD.id_almacen,
+        'stock_actual', OLD.stock_actual
+    ), json_object(
+        'id_stock', NEW.id_stock,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'id_almacen', NEW.id_almacen,
+        'stock_actual', NEW.stock_actual
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_stock_almacen_delete AFTER DELETE ON stock_almacen
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('stock_almacen', OLD.id_stock, 'DELETE', json_object(
+        'id_stock', OLD.id_stock,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'id_almacen', OLD.id_almacen,
+        'stock_actual', OLD.stock_actual
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+-- lote
+CREATE TRIGGER auditoria_lote_insert AFTER INSERT ON lote
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('lote', NEW.id_lote, 'INSERT', NULL, json_object(
+        'id_lote', NEW.id_lote,
+        'id_movimiento', NEW.id_movimiento,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'id_almacen', NEW.id_almacen,
+        'numero_lote', NEW.numero_lote,
+        'fecha_entrada', NEW.fecha_entrada,
+        'fecha_venc', NEW.fecha_venc,
+        'cantidad_inicial', NEW.cantidad_inicial,
+        'cantidad_actual', NEW.cantidad_actual,
+        'costo_unitario', NEW.costo_unitario
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_lote_update AFTER UPDATE ON lote
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('lote', NEW.id_lote, 'UPDATE', json_object(
+        'id_lote', OLD.id_lote,
+        'id_movimiento', OLD.id_movimiento,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'id_almacen', OLD.id_almacen,
+        'numero_lote', OLD.numero_lote,
+        'fecha_entrada', OLD.fecha_entrada,
+        'fecha_venc', OLD.fecha_venc,
+        'cantidad_inicial', OLD.cantidad_inicial,
+        'cantidad_actual', OLD.cantidad_actual,
+        'costo_unitario', OLD.costo_unitario
+    ), json_object(
+        'id_lote', NEW.id_lote,
+        'id_movimiento', NEW.id_movimiento,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'id_almacen', NEW.id_almacen,
+        'numero_lote', NEW.numero_lote,
+        'fecha_entrada', NEW.fecha_entrada,
+        'fecha_venc', NEW.fecha_venc,
+        'cantidad_inicial', NEW.cantidad_inicial,
+        'cantidad_actual', NEW.cantidad_actual,
+        'costo_unitario', NEW.costo_unitario
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_lote_delete AFTER DELETE ON lote
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('lote', OLD.id_lote, 'DELETE', json_object(
+        'id_lote', OLD.id_lote,
+        'id_movimiento', OLD.id_movimiento,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'id_almacen', OLD.id_almacen,
+        'numero_lote', OLD.numero_lote,
+        'fecha_entrada', OLD.fecha_entrada,
+        'fecha_venc', OLD.fecha_venc,
+        'cantidad_inicial', OLD.cantidad_inicial,
+        'cantidad_actual', OLD.cantidad_actual,
+        'costo_unitario', OLD.costo_unitario
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+-- lote_consumo
+CREATE TRIGGER auditoria_lote_consumo_insert AFTER INSERT ON lote_consumo
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('lote_consumo', NEW.id_consumo, 'INSERT', NULL, json_object(
+        'id_consumo', NEW.id_consumo,
+        'id_movimiento', NEW.id_movimiento,
+        'id_lote', NEW.id_lote,
+        'cantidad', NEW.cantidad,
+        'costo_unitario', NEW.costo_unitario
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_lote_consumo_update AFTER UPDATE ON lote_consumo
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('lote_consumo', NEW.id_consumo, 'UPDATE', json_object(
+        'id_consumo', OLD.id_consumo,
+        'id_movimiento', OLD.id_movimiento,
+        'id_lote', OLD.id_lote,
+        'cantidad', OLD.cantidad,
+        'costo_unitario', OLD.costo_unitario
+    ), json_object(
+        'id_consumo', NEW.id_consumo,
+        'id_movimiento', NEW.id_movimiento,
+        'id_lote', NEW.id_lote,
+        'cantidad', NEW.cantidad,
+        'costo_unitario', NEW.costo_unitario
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_lote_consumo_delete AFTER DELETE ON lote_consumo
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('lote_consumo', OLD.id_consumo, 'DELETE', json_object(
+        'id_consumo', OLD.id_consumo,
+        'id_movimiento', OLD.id_movimiento,
+        'id_lote', OLD.id_lote,
+        'cantidad', OLD.cantidad,
+        'costo_unitario', OLD.costo_unitario
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+-- configuracion
+CREATE TRIGGER auditoria_configuracion_insert AFTER INSERT ON configuracion
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('configuracion', NEW.id_configuracion, 'INSERT', NULL, json_object(
+        'id_configuracion', NEW.id_configuracion,
+        'clave', NEW.clave,
+        'id_almacen', NEW.id_almacen,
+        'valor', NEW.valor
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_configuracion_update AFTER UPDATE ON configuracion
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('configuracion', NEW.id_configuracion, 'UPDATE', json_object(
+        'id_configuracion', OLD.id_configuracion,
+        'clave', OLD.clave,
+        'id_almacen', OLD.id_almacen,
+        'valor', OLD.valor
+    ), json_object(
+        'id_configuracion', NEW.id_configuracion,
+        'clave', NEW.clave,
+        'id_almacen', NEW.id_almacen,
+        'valor', NEW.valor
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_configuracion_delete AFTER DELETE ON configuracion
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('configuracion', OLD.id_configuracion, 'DELETE', json_object(
+        'id_configuracion', OLD.id_configuracion,
+        'clave', OLD.clave,
+        'id_almacen', OLD.id_almacen,
+        'valor', OLD.valor
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
@@ -0,0 +1,91 @@
+-- Documento que agrupa movimientos relacionados, como la salida y la entrada
+-- de una transferencia entre almacenes
+ALTER TABLE movimiento ADD COLUMN documento TEXT;
+
+CREATE INDEX idx_movimiento_documento ON movimiento (documento);
+
+DROP TRIGGER auditoria_movimiento_insert;
+DROP TRIGGER auditoria_movimiento_update;
+DROP TRIGGER auditoria_movimiento_delete;
+
+CREATE TRIGGER auditoria_movimiento_insert AFTER INSERT ON movimiento
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('movimiento', NEW.id_movimiento, 'INSERT', NULL, json_object(
+        'id_movimiento', NEW.id_movimiento,
+        'fecha', NEW.fecha,
+        'tipo', NEW.tipo,
+        'subtipo', NEW.subtipo,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'cantidad', NEW.cantidad,
+        'precio_unit', NEW.precio_unit,
+        'monto_total', NEW.monto_total,
+        'lote', NEW.lote,
+        'fecha_venc', NEW.fecha_venc,
+        'obs', NEW.obs,
+        'id_factura', NEW.id_factura,
+        'id_almacen', NEW.id_almacen,
+        'documento', NEW.documento
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_movimiento_update AFTER UPDATE ON movimiento
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('movimiento', NEW.id_movimiento, 'UPDATE', json_object(
+        'id_movimiento', OLD.id_movimiento,
+        'fecha', OLD.fecha,
+        'tipo', OLD.tipo,
+        'subtipo', OLD.subtipo,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'cantidad', OLD.cantidad,
+        'precio_unit', OLD.precio_unit,
+        'monto_total', OLD.monto_total,
+        'lote', OLD.lote,
+        'fecha_venc', OLD.fecha_venc,
+        'obs', OLD.obs,
+        'id_factura', OLD.id_factura,
+        'id_almacen', OLD.id_almacen,
+        'documento', OLD.documento
+    ), json_object(
+        'id_movimiento', NEW.id_movimiento,
+        'fecha', NEW.fecha,
+        'tipo', NEW.tipo,
+        'subtipo', NEW.subtipo,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'cantidad', NEW.cantidad,
+        'precio_unit', NEW.precio_unit,
+        'monto_total', NEW.monto_total,
+        'lote', NEW.lote,
+        'fecha_venc', NEW.fecha_venc,
+        'obs', NEW.obs,
+        'id_factura', NEW.id_factura,
+        'id_almacen', NEW.id_almacen,
+        'documento', NEW.documento
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_movimiento_delete AFTER DELETE ON movimiento
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('movimiento', OLD.id_movimiento, 'DELETE', json_object(
+        'id_movimiento', OLD.id_movimiento,
+        'fecha', OLD.fecha,
+        'tipo', OLD.tipo,
+        'subtipo', OLD.subtipo,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'cantidad', OLD.cantidad,
+        'precio_unit', OLD.precio_unit,
+        'monto_total', OLD.monto_total,
+        'lote', OLD.lote,
+        'fecha_venc', OLD.fecha_venc,
+        'obs', OLD.obs,
+        'id_factura', OLD.id_factura,
+        'id_almacen', OLD.id_almacen,
+        'documento', OLD.documento
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
@@ -0,0 +1,121 @@
+-- Conteos físicos de inventario por almacén. Al crearse, cada conteo guarda
+-- el stock esperado y el costo unitario de cada ubicación del almacén.
+CREATE TABLE conteo (
+    id_conteo INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_almacen INTEGER NOT NULL,
+    fecha TEXT NOT NULL,
+    estado TEXT NOT NULL DEFAULT 'Abierto'
+        CHECK (estado IN ('Abierto', 'En conteo', 'Aprobado', 'Cancelado')),
+    obs TEXT,
+    FOREIGN KEY (id_almacen) REFERENCES almacen(id_almacen)
+);
+
+-- Un solo conteo en curso por almacén
+CREATE UNIQUE INDEX idx_conteo_en_curso ON conteo (id_almacen)
+    WHERE estado IN ('Abierto', 'En conteo');
+
+CREATE TABLE conteo_detalle (
+    id_detalle INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_conteo INTEGER NOT NULL,
+    id_prod_prov INTEGER NOT NULL,
+    id_presentacion INTEGER NOT NULL,
+    cantidad_esperada REAL NOT NULL,
+    cantidad_contada REAL,
+    costo_unitario REAL NOT NULL DEFAULT 0,
+    UNIQUE (id_conteo, id_prod_prov, id_presentacion),
+    FOREIGN KEY (id_conteo) REFERENCES conteo(id_conteo),
+    FOREIGN KEY (id_prod_prov) REFERENCES producto_proveedor(id_prod_prov),
+    FOREIGN KEY (id_presentacion) REFERENCES presentacion(id_presentacion)
+);
+
+CREATE TRIGGER auditoria_conteo_insert AFTER INSERT ON conteo
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('conteo', NEW.id_conteo, 'INSERT', NULL, json_object(
+        'id_conteo', NEW.id_conteo,
+        'id_almacen', NEW.id_almacen,
+        'fecha', NEW.fecha,
+        'estado', NEW.estado,
+        'obs', NEW.obs
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_conteo_update AFTER UPDATE ON conteo
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('conteo', NEW.id_conteo, 'UPDATE', json_object(
+        'id_conteo', OLD.id_conteo,
+        'id_almacen', OLD.id_almacen,
+        'fecha', OLD.fecha,
+        'estado', OLD.estado,
+        'obs', OLD.obs
+    ), json_object(
+        'id_conteo', NEW.id_conteo,
+        'id_almacen', NEW.id_almacen,
+        'fecha', NEW.fecha,
+        'estado', NEW.estado,
+        'obs', NEW.obs
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_conteo_delete AFTER DELETE ON conteo
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('conteo', OLD.id_conteo, 'DELETE', json_object(
+        'id_conteo', OLD.id_conteo,
+        'id_almacen', OLD.id_almacen,
+        'fecha', OLD.fecha,
+        'estado', OLD.estado,
+        'obs', OLD.obs
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_conteo_detalle_insert AFTER INSERT ON conteo_detalle
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('conteo_detalle', NEW.id_detalle, 'INSERT', NULL, json_object(
+        'id_detalle', NEW.id_detalle,
+        'id_conteo', NEW.id_conteo,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'cantidad_esperada', NEW.cantidad_esperada,
+        'cantidad_contada', NEW.cantidad_contada,
+        'costo_unitario', NEW.costo_unitario
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_conteo_detalle_update AFTER UPDATE ON conteo_detalle
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('conteo_detalle', NEW.id_detalle, 'UPDATE', json_object(
+        'id_detalle', OLD.id_detalle,
+        'id_conteo', OLD.id_conteo,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'cantidad_esperada', OLD.cantidad_esperada,
+        'cantidad_contada', OLD.cantidad_contada,
+        'costo_unitario', OLD.costo_unitario
+    ), json_object(
+        'id_detalle', NEW.id_detalle,
+        'id_conteo', NEW.id_conteo,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'cantidad_esperada', NEW.cantidad_esperada,
+        'cantidad_contada', NEW.cantidad_contada,
+        'costo_unitario', NEW.costo_unitario
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_conteo_detalle_delete AFTER DELETE ON conteo_detalle
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('conteo_detalle', OLD.id_detalle, 'DELETE', json_object(
+        'id_detalle', OLD.id_detalle,
+        'id_conteo', OLD.id_conteo,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'cantidad_esperada', OLD.cantidad_esperada,
+        'cantidad_contada', OLD.cantidad_contada,
+        'costo_unitario', OLD.costo_unitario
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
@@ -0,0 +1,62 @@
+-- Stock mínimo, máximo y punto de reorden por producto, en unidad base. Una
+-- fila sin almacén es el valor general; una fila con almacén lo sobrescribe
+-- campo por campo.
+CREATE TABLE stock_parametro (
+    id_parametro INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_producto INTEGER NOT NULL,
+    id_almacen INTEGER,
+    stock_minimo REAL CHECK (stock_minimo >= 0),
+    stock_maximo REAL CHECK (stock_maximo >= 0),
+    punto_reorden REAL CHECK (punto_reorden >= 0),
+    FOREIGN KEY (id_producto) REFERENCES producto(id_producto),
+    FOREIGN KEY (id_almacen) REFERENCES almacen(id_almacen)
+);
+
+-- Una fila general y una por almacén para cada producto
+CREATE UNIQUE INDEX idx_stock_parametro_producto ON stock_parametro (id_producto, COALESCE(id_almacen, 0));
+
+CREATE TRIGGER auditoria_stock_parametro_insert AFTER INSERT ON stock_parametro
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('stock_parametro', NEW.id_parametro, 'INSERT', NULL, json_object(
+        'id_parametro', NEW.id_parametro,
+        'id_producto', NEW.id_producto,
+        'id_almacen', NEW.id_almacen,
+        'stock_minimo', NEW.stock_minimo,
+        'stock_maximo', NEW.stock_maximo,
+        'punto_reorden', NEW.punto_reorden
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_stock_parametro_update AFTER UPDATE ON stock_parametro
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('stock_parametro', NEW.id_parametro, 'UPDATE', json_object(
+        'id_parametro', OLD.id_parametro,
+        'id_producto', OLD.id_producto,
+        'id_almacen', OLD.id_almacen,
+        'stock_minimo', OLD.stock_minimo,
+        'stock_maximo', OLD.stock_maximo,
+        'punto_reorden', OLD.punto_reorden
+    ), json_object(
+        'id_parametro', NEW.id_parametro,
+        'id_producto', NEW.id_producto,
+        'id_almacen', NEW.id_almacen,
+        'stock_minimo', NEW.stock_minimo,
+        'stock_maximo', NEW.stock_maximo,
+        'punto_reorden', NEW.punto_reorden
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_stock_parametro_delete AFTER DELETE ON stock_parametro
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('stock_parametro', OLD.id_parametro, 'DELETE', json_object(
+        'id_parametro', OLD.id_parametro,
+        'id_producto', OLD.id_producto,
+        'id_almacen', OLD.id_almacen,
+        'stock_minimo', OLD.stock_minimo,
+        'stock_maximo', OLD.stock_maximo,
+        'punto_reorden', OLD.punto_reorden
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
@@ -0,0 +1,230 @@
+-- Órdenes de compra a proveedores. Lo recibido de cada línea son las entradas
+-- registradas contra ella (movimiento.id_linea_orden).
+CREATE TABLE orden_compra (
+    id_orden INTEGER PRIMARY KEY AUTOINCREMENT,
+    numero TEXT NOT NULL UNIQUE,
+    fecha TEXT NOT NULL,
+    id_proveedor INTEGER NOT NULL,
+    id_almacen INTEGER NOT NULL,
+    estado TEXT NOT NULL DEFAULT 'Borrador'
+        CHECK (estado IN ('Borrador', 'Enviada', 'Recibida parcialmente', 'Recibida', 'Cerrada')),
+    fecha_entrega TEXT,
+    obs TEXT,
+    FOREIGN KEY (id_proveedor) REFERENCES proveedor(id_proveedor),
+    FOREIGN KEY (id_almacen) REFERENCES almacen(id_almacen)
+);
+
+CREATE TABLE orden_compra_linea (
+    id_linea INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_orden INTEGER NOT NULL,
+    id_prod_prov INTEGER NOT NULL,
+    id_presentacion INTEGER NOT NULL,
+    cantidad REAL NOT NULL CHECK (cantidad > 0),
+    precio_unit REAL NOT NULL CHECK (precio_unit >= 0),
+    FOREIGN KEY (id_orden) REFERENCES orden_compra(id_orden),
+    FOREIGN KEY (id_prod_prov) REFERENCES producto_proveedor(id_prod_prov),
+    FOREIGN KEY (id_presentacion) REFERENCES presentacion(id_presentacion)
+);
+
+CREATE INDEX idx_orden_compra_linea_orden ON orden_compra_linea (id_orden);
+
+ALTER TABLE movimiento ADD COLUMN id_linea_orden INTEGER REFERENCES orden_compra_linea(id_linea);
+
+CREATE INDEX idx_movimiento_linea_orden ON movimiento (id_linea_orden);
+
+-- Líneas con la cantidad recibida hasta ahora
+CREATE VIEW orden_compra_linea_saldo AS
+SELECT l.*,
+       COALESCE((SELECT SUM(m.cantidad) FROM movimiento m
+                 WHERE m.id_linea_orden = l.id_linea AND m.tipo = 'entrada'), 0.0) AS cantidad_recibida
+FROM orden_compra_linea l;
+
+CREATE TRIGGER auditoria_orden_compra_insert AFTER INSERT ON orden_compra
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('orden_compra', NEW.id_orden, 'INSERT', NULL, json_object(
+        'id_orden', NEW.id_orden,
+        'numero', NEW.numero,
+        'fecha', NEW.fecha,
+        'id_proveedor', NEW.id_proveedor,
+        'id_almacen', NEW.id_almacen,
+        'estado', NEW.estado,
+        'fecha_entrega', NEW.fecha_entrega,
+        'obs', NEW.obs
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_orden_compra_update AFTER UPDATE ON orden_compra
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('orden_compra', NEW.id_orden, 'UPDATE', json_object(
+        'id_orden', OLD.id_orden,
+        'numero', OLD.numero,
+        'fecha', OLD.fecha,
+        'id_proveedor', OLD.id_proveedor,
+        'id_almacen', OLD.id_almacen,
+        'estado', OLD.estado,
+        'fecha_entrega', OLD.fecha_entrega,
+        'obs', OLD.obs
+    ), json_object(
+        'id_orden', NEW.id_orden,
+        'numero', NEW.numero,
+        'fecha', NEW.fecha,
+        'id_proveedor', NEW.id_proveedor,
+        'id_almacen', NEW.id_almacen,
+        'estado', NEW.estado,
+        'fecha_entrega', NEW.fecha_entrega,
+        'obs', NEW.obs
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_orden_compra_delete AFTER DELETE ON orden_compra
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('orden_compra', OLD.id_orden, 'DELETE', json_object(
+        'id_orden', OLD.id_orden,
+        'numero', OLD.numero,
+        'fecha', OLD.fecha,
+        'id_proveedor', OLD.id_proveedor,
+        'id_almacen', OLD.id_almacen,
+        'estado', OLD.estado,
+        'fecha_entrega', OLD.fecha_entrega,
+        'obs', OLD.obs
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_orden_compra_linea_insert AFTER INSERT ON orden_compra_linea
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('orden_compra_linea', NEW.id_linea, 'INSERT', NULL, json_object(
+        'id_linea', NEW.id_linea,
+        'id_orden', NEW.id_orden,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'cantidad', NEW.cantidad,
+        'precio_unit', NEW.precio_unit
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_orden_compra_linea_update AFTER UPDATE ON orden_compra_linea
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('orden_compra_linea', NEW.id_linea, 'UPDATE', json_object(
+        'id_linea', OLD.id_linea,
+        'id_orden', OLD.id_orden,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'cantidad', OLD.cantidad,
+        'precio_unit', OLD.precio_unit
+    ), json_object(
+        'id_linea', NEW.id_linea,
+        'id_orden', NEW.id_orden,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'cantidad', NEW.cantidad,
+        'precio_unit', NEW.precio_unit
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_orden_compra_linea_delete AFTER DELETE ON orden_compra_linea
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('orden_compra_linea', OLD.id_linea, 'DELETE', json_object(
+        'id_linea', OLD.id_linea,
+        'id_orden', OLD.id_orden,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'cantidad', OLD.cantidad,
+        'precio_unit', OLD.precio_unit
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+DROP TRIGGER auditoria_movimiento_insert;
+DROP TRIGGER auditoria_movimiento_update;
+DROP TRIGGER auditoria_movimiento_delete;
+
+CREATE TRIGGER auditoria_movimiento_insert AFTER INSERT ON movimiento
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('movimiento', NEW.id_movimiento, 'INSERT', NULL, json_object(
+        'id_movimiento', NEW.id_movimiento,
+        'fecha', NEW.fecha,
+        'tipo', NEW.tipo,
+        'subtipo', NEW.subtipo,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'cantidad', NEW.cantidad,
+        'precio_unit', NEW.precio_unit,
+        'monto_total', NEW.monto_total,
+        'lote', NEW.lote,
+        'fecha_venc', NEW.fecha_venc,
+        'obs', NEW.obs,
+        'id_factura', NEW.id_factura,
+        'id_almacen', NEW.id_almacen,
+        'documento', NEW.documento,
+        'id_linea_orden', NEW.id_linea_orden
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_movimiento_update AFTER UPDATE ON movimiento
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('movimiento', NEW.id_movimiento, 'UPDATE', json_object(
+        'id_movimiento', OLD.id_movimiento,
+        'fecha', OLD.fecha,
+        'tipo', OLD.tipo,
+        'subtipo', OLD.subtipo,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'cantidad', OLD.cantidad,
+        'precio_unit', OLD.precio_unit,
+        'monto_total', OLD.monto_total,
+        'lote', OLD.lote,
+        'fecha_venc', OLD.fecha_venc,
+        'obs', OLD.obs,
+        'id_factura', OLD.id_factura,
+        'id_almacen', OLD.id_almacen,
+        'documento', OLD.documento,
+        'id_linea_orden', OLD.id_linea_orden
+    ), json_object(
+        'id_movimiento', NEW.id_movimiento,
+        'fecha', NEW.fecha,
+        'tipo', NEW.tipo,
+        'subtipo', NEW.subtipo,
+        'id_prod_prov', NEW.id_prod_prov,
+        'id_presentacion', NEW.id_presentacion,
+        'cantidad', NEW.cantidad,
+        'precio_unit', NEW.precio_unit,
+        'monto_total', NEW.monto_total,
+        'lote', NEW.lote,
+        'fecha_venc', NEW.fecha_venc,
+        'obs', NEW.obs,
+        'id_factura', NEW.id_factura,
+        'id_almacen', NEW.id_almacen,
+        'documento', NEW.documento,
+        'id_linea_orden', NEW.id_linea_orden
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_movimiento_delete AFTER DELETE ON movimiento
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('movimiento', OLD.id_movimiento, 'DELETE', json_object(
+        'id_movimiento', OLD.id_movimiento,
+        'fecha', OLD.fecha,
+        'tipo', OLD.tipo,
+        'subtipo', OLD.subtipo,
+        'id_prod_prov', OLD.id_prod_prov,
+        'id_presentacion', OLD.id_presentacion,
+        'cantidad', OLD.cantidad,
+        'precio_unit', OLD.precio_unit,
+        'monto_total', OLD.monto_total,
+        'lote', OLD.lote,
+        'fecha_venc', OLD.fecha_venc,
+        'obs', OLD.obs,
+        'id_factura', OLD.id_factura,
+        'id_almacen', OLD.id_almacen,
+        'documento', OLD.documento,
+        'id_linea_orden', OLD.id_linea_orden
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
@@ -0,0 +1,63 @@
+-- Descuento y porcentaje de impuesto de la factura. Subtotal, impuesto y total
+-- se calculan con sus entradas; `total` queda como el impreso por el proveedor.
+ALTER TABLE factura ADD COLUMN descuento REAL NOT NULL DEFAULT 0 CHECK (descuento >= 0);
+ALTER TABLE factura ADD COLUMN porcentaje_impuesto REAL NOT NULL DEFAULT 0
+    CHECK (porcentaje_impuesto >= 0 AND porcentaje_impuesto <= 100);
+
+DROP TRIGGER auditoria_factura_insert;
+DROP TRIGGER auditoria_factura_update;
+DROP TRIGGER auditoria_factura_delete;
+
+CREATE TRIGGER auditoria_factura_insert AFTER INSERT ON factura
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('factura', NEW.id_factura, 'INSERT', NULL, json_object(
+        'id_factura', NEW.id_factura,
+        'numero', NEW.numero,
+        'fecha', NEW.fecha,
+        'id_proveedor', NEW.id_proveedor,
+        'total', NEW.total,
+        'estado', NEW.estado,
+        'descuento', NEW.descuento,
+        'porcentaje_impuesto', NEW.porcentaje_impuesto
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_factura_update AFTER UPDATE ON factura
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('factura', NEW.id_factura, 'UPDATE', json_object(
+        'id_factura', OLD.id_factura,
+        'numero', OLD.numero,
+        'fecha', OLD.fecha,
+        'id_proveedor', OLD.id_proveedor,
+        'total', OLD.total,
+        'estado', OLD.estado,
+        'descuento', OLD.descuento,
+        'porcentaje_impuesto', OLD.porcentaje_impuesto
+    ), json_object(
+        'id_factura', NEW.id_factura,
+        'numero', NEW.numero,
+        'fecha', NEW.fecha,
+        'id_proveedor', NEW.id_proveedor,
+        'total', NEW.total,
+        'estado', NEW.estado,
+        'descuento', NEW.descuento,
+        'porcentaje_impuesto', NEW.porcentaje_impuesto
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_factura_delete AFTER DELETE ON factura
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('factura', OLD.id_factura, 'DELETE', json_object(
+        'id_factura', OLD.id_factura,
+        'numero', OLD.numero,
+        'fecha', OLD.fecha,
+        'id_proveedor', OLD.id_proveedor,
+        'total', OLD.total,
+        'estado', OLD.estado,
+        'descuento', OLD.descuento,
+        'porcentaje_impuesto', OLD.porcentaje_impuesto
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
@@ -0,0 +1,13 @@
+-- Estados de la factura: Borrador, Activa, Pagada y Anulada. Las pendientes
+-- de pago pasan a activas.
+UPDATE factura SET estado = 'Activa' WHERE estado IS NULL OR estado = 'Pendiente';
+
+-- Lo recibido de una línea de orden descuenta las entradas anuladas
+DROP VIEW orden_compra_linea_saldo;
+
+CREATE VIEW orden_compra_linea_saldo AS
+SELECT l.*,
+       COALESCE((SELECT SUM(CASE m.tipo WHEN 'entrada' THEN m.cantidad ELSE -m.cantidad END)
+                 FROM movimiento m
+                 WHERE m.id_linea_orden = l.id_linea AND m.tipo IN ('entrada', 'salida')), 0.0) AS cantidad_recibida
+FROM orden_compra_linea l;
@@ -0,0 +1,251 @@
+-- Condiciones de pago del proveedor y vencimiento de sus facturas
+ALTER TABLE proveedor ADD COLUMN dias_credito INTEGER NOT NULL DEFAULT 0 CHECK (dias_credito >= 0);
+ALTER TABLE factura ADD COLUMN fecha_vencimiento TEXT;
+
+UPDATE factura SET fecha_vencimiento = fecha;
+
+-- Pagos a proveedores y su aplicación a facturas. Lo no aplicado queda como
+-- saldo a favor del proveedor.
+CREATE TABLE pago (
+    id_pago INTEGER PRIMARY KEY AUTOINCREMENT,
+    fecha TEXT NOT NULL,
+    id_proveedor INTEGER NOT NULL,
+    monto REAL NOT NULL CHECK (monto > 0),
+    metodo TEXT NOT NULL,
+    referencia TEXT,
+    obs TEXT,
+    FOREIGN KEY (id_proveedor) REFERENCES proveedor(id_proveedor)
+);
+
+CREATE TABLE pago_aplicacion (
+    id_aplicacion INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_pago INTEGER NOT NULL,
+    id_factura INTEGER NOT NULL,
+    monto REAL NOT NULL CHECK (monto > 0),
+    FOREIGN KEY (id_pago) REFERENCES pago(id_pago),
+    FOREIGN KEY (id_factura) REFERENCES factura(id_factura),
+    UNIQUE (id_pago, id_factura)
+);
+
+CREATE INDEX idx_pago_proveedor ON pago (id_proveedor);
+CREATE INDEX idx_pago_aplicacion_factura ON pago_aplicacion (id_factura);
+
+DROP TRIGGER auditoria_proveedor_insert;
+DROP TRIGGER auditoria_proveedor_update;
+DROP TRIGGER auditoria_proveedor_delete;
+
+CREATE TRIGGER auditoria_proveedor_insert AFTER INSERT ON proveedor
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('proveedor', NEW.id_proveedor, 'INSERT', NULL, json_object(
+        'id_proveedor', NEW.id_proveedor,
+        'ruc_ci', NEW.ruc_ci,
+        'nombre', NEW.nombre,
+        'contacto', NEW.contacto,
+        'telefono', NEW.telefono,
+        'email', NEW.email,
+        'estado', NEW.estado,
+        'pais', NEW.pais,
+        'eliminado_en', NEW.eliminado_en,
+        'eliminado_por', NEW.eliminado_por,
+        'dias_credito', NEW.dias_credito
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_proveedor_update AFTER UPDATE ON proveedor
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('proveedor', NEW.id_proveedor, 'UPDATE', json_object(
+        'id_proveedor', OLD.id_proveedor,
+        'ruc_ci', OLD.ruc_ci,
+        'nombre', OLD.nombre,
+        'contacto', OLD.contacto,
+        'telefono', OLD.telefono,
+        'email', OLD.email,
+        'estado', OLD.estado,
+        'pais', OLD.pais,
+        'eliminado_en', OLD.eliminado_en,
+        'eliminado_por', OLD.eliminado_por,
+        'dias_credito', OLD.dias_credito
+    ), json_object(
+        'id_proveedor', NEW.id_proveedor,
+        'ruc_ci', NEW.ruc_ci,
+        'nombre', NEW.nombre,
+        'contacto', NEW.contacto,
+        'telefono', NEW.telefono,
+        'email', NEW.email,
+        'estado', NEW.estado,
+        'pais', NEW.pais,
+        'eliminado_en', NEW.eliminado_en,
+        'eliminado_por', NEW.eliminado_por,
+        'dias_credito', NEW.dias_credito
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_proveedor_delete AFTER DELETE ON proveedor
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('proveedor', OLD.id_proveedor, 'DELETE', json_object(
+        'id_proveedor', OLD.id_proveedor,
+        'ruc_ci', OLD.ruc_ci,
+        'nombre', OLD.nombre,
+        'contacto', OLD.contacto,
+        'telefono', OLD.telefono,
+        'email', OLD.email,
+        'estado', OLD.estado,
+        'pais', OLD.pais,
+        'eliminado_en', OLD.eliminado_en,
+        'eliminado_por', OLD.eliminado_por,
+        'dias_credito', OLD.dias_credito
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+DROP TRIGGER auditoria_factura_insert;
+DROP TRIGGER auditoria_factura_update;
+DROP TRIGGER auditoria_factura_delete;
+
+CREATE TRIGGER auditoria_factura_insert AFTER INSERT ON factura
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('factura', NEW.id_factura, 'INSERT', NULL, json_object(
+        'id_factura', NEW.id_factura,
+        'numero', NEW.numero,
+        'fecha', NEW.fecha,
+        'id_proveedor', NEW.id_proveedor,
+        'total', NEW.total,
+        'estado', NEW.estado,
+        'descuento', NEW.descuento,
+        'porcentaje_impuesto', NEW.porcentaje_impuesto,
+        'fecha_vencimiento', NEW.fecha_vencimiento
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_factura_update AFTER UPDATE ON factura
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('factura', NEW.id_factura, 'UPDATE', json_object(
+        'id_factura', OLD.id_factura,
+        'numero', OLD.numero,
+        'fecha', OLD.fecha,
+        'id_proveedor', OLD.id_proveedor,
+        'total', OLD.total,
+        'estado', OLD.estado,
+        'descuento', OLD.descuento,
+        'porcentaje_impuesto', OLD.porcentaje_impuesto,
+        'fecha_vencimiento', OLD.fecha_vencimiento
+    ), json_object(
+        'id_factura', NEW.id_factura,
+        'numero', NEW.numero,
+        'fecha', NEW.fecha,
+        'id_proveedor', NEW.id_proveedor,
+        'total', NEW.total,
+        'estado', NEW.estado,
+        'descuento', NEW.descuento,
+        'porcentaje_impuesto', NEW.porcentaje_impuesto,
+        'fecha_vencimiento', NEW.fecha_vencimiento
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_factura_delete AFTER DELETE ON factura
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('factura', OLD.id_factura, 'DELETE', json_object(
+        'id_factura', OLD.id_factura,
+        'numero', OLD.numero,
+        'fecha', OLD.fecha,
+        'id_proveedor', OLD.id_proveedor,
+        'total', OLD.total,
+        'estado', OLD.estado,
+        'descuento', OLD.descuento,
+        'porcentaje_impuesto', OLD.porcentaje_impuesto,
+        'fecha_vencimiento', OLD.fecha_vencimiento
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_pago_insert AFTER INSERT ON pago
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('pago', NEW.id_pago, 'INSERT', NULL, json_object(
+        'id_pago', NEW.id_pago,
+        'fecha', NEW.fecha,
+        'id_proveedor', NEW.id_proveedor,
+        'monto', NEW.monto,
+        'metodo', NEW.metodo,
+        'referencia', NEW.referencia,
+        'obs', NEW.obs
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_pago_update AFTER UPDATE ON pago
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('pago', NEW.id_pago, 'UPDATE', json_object(
+        'id_pago', OLD.id_pago,
+        'fecha', OLD.fecha,
+        'id_proveedor', OLD.id_proveedor,
+        'monto', OLD.monto,
+        'metodo', OLD.metodo,
+        'referencia', OLD.referencia,
+        'obs', OLD.obs
+    ), json_object(
+        'id_pago', NEW.id_pago,
+        'fecha', NEW.fecha,
+        'id_proveedor', NEW.id_proveedor,
+        'monto', NEW.monto,
+        'metodo', NEW.metodo,
+        'referencia', NEW.referencia,
+        'obs', NEW.obs
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_pago_delete AFTER DELETE ON pago
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('pago', OLD.id_pago, 'DELETE', json_object(
+        'id_pago', OLD.id_pago,
+        'fecha', OLD.fecha,
+        'id_proveedor', OLD.id_proveedor,
+        'monto', OLD.monto,
+        'metodo', OLD.metodo,
+        'referencia', OLD.referencia,
+        'obs', OLD.obs
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_pago_aplicacion_insert AFTER INSERT ON pago_aplicacion
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('pago_aplicacion', NEW.id_aplicacion, 'INSERT', NULL, json_object(
+        'id_aplicacion', NEW.id_aplicacion,
+        'id_pago', NEW.id_pago,
+        'id_factura', NEW.id_factura,
+        'monto', NEW.monto
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_pago_aplicacion_update AFTER UPDATE ON pago_aplicacion
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('pago_aplicacion', NEW.id_aplicacion, 'UPDATE', json_object(
+        'id_aplicacion', OLD.id_aplicacion,
+        'id_pago', OLD.id_pago,
+        'id_factura', OLD.id_factura,
+        'monto', OLD.monto
+    ), json_object(
+        'id_aplicacion', NEW.id_aplicacion,
+        'id_pago', NEW.id_pago,
+        'id_factura', NEW.id_factura,
+        'monto', NEW.monto
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_pago_aplicacion_delete AFTER DELETE ON pago_aplicacion
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('pago_aplicacion', OLD.id_aplicacion, 'DELETE', json_object(
+        'id_aplicacion', OLD.id_aplicacion,
+        'id_pago', OLD.id_pago,
+        'id_factura', OLD.id_factura,
+        'monto', OLD.monto
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
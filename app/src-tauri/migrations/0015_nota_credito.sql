@@ -0,0 +1,71 @@
+-- Notas de crédito del proveedor por mercadería devuelta. Las salidas de la
+-- devolución llevan el número de la nota como documento.
+CREATE TABLE nota_credito (
+    id_nota INTEGER PRIMARY KEY AUTOINCREMENT,
+    numero TEXT NOT NULL UNIQUE,
+    fecha TEXT NOT NULL,
+    id_proveedor INTEGER NOT NULL,
+    id_factura INTEGER NOT NULL,
+    monto REAL NOT NULL CHECK (monto >= 0),
+    motivo TEXT,
+    obs TEXT,
+    FOREIGN KEY (id_proveedor) REFERENCES proveedor(id_proveedor),
+    FOREIGN KEY (id_factura) REFERENCES factura(id_factura)
+);
+
+CREATE INDEX idx_nota_credito_proveedor ON nota_credito (id_proveedor);
+CREATE INDEX idx_nota_credito_factura ON nota_credito (id_factura);
+
+CREATE TRIGGER auditoria_nota_credito_insert AFTER INSERT ON nota_credito
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('nota_credito', NEW.id_nota, 'INSERT', NULL, json_object(
+        'id_nota', NEW.id_nota,
+        'numero', NEW.numero,
+        'fecha', NEW.fecha,
+        'id_proveedor', NEW.id_proveedor,
+        'id_factura', NEW.id_factura,
+        'monto', NEW.monto,
+        'motivo', NEW.motivo,
+        'obs', NEW.obs
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_nota_credito_update AFTER UPDATE ON nota_credito
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('nota_credito', NEW.id_nota, 'UPDATE', json_object(
+        'id_nota', OLD.id_nota,
+        'numero', OLD.numero,
+        'fecha', OLD.fecha,
+        'id_proveedor', OLD.id_proveedor,
+        'id_factura', OLD.id_factura,
+        'monto', OLD.monto,
+        'motivo', OLD.motivo,
+        'obs', OLD.obs
+    ), json_object(
+        'id_nota', NEW.id_nota,
+        'numero', NEW.numero,
+        'fecha', NEW.fecha,
+        'id_proveedor', NEW.id_proveedor,
+        'id_factura', NEW.id_factura,
+        'monto', NEW.monto,
+        'motivo', NEW.motivo,
+        'obs', NEW.obs
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_nota_credito_delete AFTER DELETE ON nota_credito
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('nota_credito', OLD.id_nota, 'DELETE', json_object(
+        'id_nota', OLD.id_nota,
+        'numero', OLD.numero,
+        'fecha', OLD.fecha,
+        'id_proveedor', OLD.id_proveedor,
+        'id_factura', OLD.id_factura,
+        'monto', OLD.monto,
+        'motivo', OLD.motivo,
+        'obs', OLD.obs
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
@@ -0,0 +1,76 @@
+-- Saldos de stock guardados a una fecha para no tener que repasar todo el
+-- historial de movimientos al consultar el stock a una fecha pasada. El
+-- detalle se deriva de los movimientos y no se audita.
+CREATE TABLE corte_stock (
+    id_corte INTEGER PRIMARY KEY AUTOINCREMENT,
+    fecha TEXT NOT NULL UNIQUE,
+    creado_en TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE corte_stock_detalle (
+    id_corte INTEGER NOT NULL,
+    id_prod_prov INTEGER NOT NULL,
+    id_presentacion INTEGER NOT NULL,
+    id_almacen INTEGER NOT NULL,
+    cantidad REAL NOT NULL,
+    PRIMARY KEY (id_corte, id_prod_prov, id_presentacion, id_almacen),
+    FOREIGN KEY (id_corte) REFERENCES corte_stock(id_corte)
+);
+
+-- Un movimiento registrado, modificado o eliminado con fecha igual o
+-- anterior a un corte lo deja desactualizado; se descarta y el stock a esas
+-- fechas se vuelve a calcular con los movimientos
+CREATE TRIGGER corte_stock_movimiento_insert AFTER INSERT ON movimiento
+BEGIN
+    DELETE FROM corte_stock_detalle
+    WHERE id_corte IN (SELECT id_corte FROM corte_stock WHERE fecha >= NEW.fecha);
+    DELETE FROM corte_stock WHERE fecha >= NEW.fecha;
+END;
+
+CREATE TRIGGER corte_stock_movimiento_update AFTER UPDATE ON movimiento
+BEGIN
+    DELETE FROM corte_stock_detalle
+    WHERE id_corte IN (SELECT id_corte FROM corte_stock WHERE fecha >= MIN(OLD.fecha, NEW.fecha));
+    DELETE FROM corte_stock WHERE fecha >= MIN(OLD.fecha, NEW.fecha);
+END;
+
+CREATE TRIGGER corte_stock_movimiento_delete AFTER DELETE ON movimiento
+BEGIN
+    DELETE FROM corte_stock_detalle
+    WHERE id_corte IN (SELECT id_corte FROM corte_stock WHERE fecha >= OLD.fecha);
+    DELETE FROM corte_stock WHERE fecha >= OLD.fecha;
+END;
+
+CREATE TRIGGER auditoria_corte_stock_insert AFTER INSERT ON corte_stock
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('corte_stock', NEW.id_corte, 'INSERT', NULL, json_object(
+        'id_corte', NEW.id_corte,
+        'fecha', NEW.fecha,
+        'creado_en', NEW.creado_en
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_corte_stock_update AFTER UPDATE ON corte_stock
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('corte_stock', NEW.id_corte, 'UPDATE', json_object(
+        'id_corte', OLD.id_corte,
+        'fecha', OLD.fecha,
+        'creado_en', OLD.creado_en
+    ), json_object(
+        'id_corte', NEW.id_corte,
+        'fecha', NEW.fecha,
+        'creado_en', NEW.creado_en
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_corte_stock_delete AFTER DELETE ON corte_stock
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('corte_stock', OLD.id_corte, 'DELETE', json_object(
+        'id_corte', OLD.id_corte,
+        'fecha', OLD.fecha,
+        'creado_en', OLD.creado_en
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
@@ -0,0 +1,87 @@
+-- Períodos contables mensuales (`AAAA-MM`). Un período cerrado no admite
+-- movimientos ni facturas con fecha dentro de él. Solo existen las filas de
+-- los períodos que alguna vez se cerraron; reabrir cambia el estado y queda
+-- en la auditoría.
+CREATE TABLE periodo (
+    id_periodo INTEGER PRIMARY KEY AUTOINCREMENT,
+    periodo TEXT NOT NULL UNIQUE,
+    estado TEXT NOT NULL CHECK (estado IN ('Cerrado', 'Abierto')),
+    metodo_valuacion TEXT NOT NULL,
+    valor_total REAL NOT NULL,
+    cerrado_en TEXT NOT NULL,
+    reabierto_en TEXT,
+    motivo_reapertura TEXT
+);
+
+-- Stock valorizado al cierre, con los nombres de ese momento, tal como se
+-- informó. Se reemplaza en cada cierre y no se audita.
+CREATE TABLE periodo_valuacion (
+    id_periodo INTEGER NOT NULL,
+    id_producto INTEGER NOT NULL,
+    codigo_interno TEXT NOT NULL,
+    descripcion TEXT NOT NULL,
+    categoria TEXT,
+    id_presentacion INTEGER NOT NULL,
+    unidad TEXT NOT NULL,
+    id_almacen INTEGER NOT NULL,
+    almacen TEXT NOT NULL,
+    cantidad REAL NOT NULL,
+    costo_unitario REAL,
+    valor REAL NOT NULL,
+    PRIMARY KEY (id_periodo, id_producto, id_presentacion, id_almacen),
+    FOREIGN KEY (id_periodo) REFERENCES periodo(id_periodo)
+);
+
+CREATE TRIGGER auditoria_periodo_insert AFTER INSERT ON periodo
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('periodo', NEW.id_periodo, 'INSERT', NULL, json_object(
+        'id_periodo', NEW.id_periodo,
+        'periodo', NEW.periodo,
+        'estado', NEW.estado,
+        'metodo_valuacion', NEW.metodo_valuacion,
+        'valor_total', NEW.valor_total,
+        'cerrado_en', NEW.cerrado_en,
+        'reabierto_en', NEW.reabierto_en,
+        'motivo_reapertura', NEW.motivo_reapertura
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_periodo_update AFTER UPDATE ON periodo
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('periodo', NEW.id_periodo, 'UPDATE', json_object(
+        'id_periodo', OLD.id_periodo,
+        'periodo', OLD.periodo,
+        'estado', OLD.estado,
+        'metodo_valuacion', OLD.metodo_valuacion,
+        'valor_total', OLD.valor_total,
+        'cerrado_en', OLD.cerrado_en,
+        'reabierto_en', OLD.reabierto_en,
+        'motivo_reapertura', OLD.motivo_reapertura
+    ), json_object(
+        'id_periodo', NEW.id_periodo,
+        'periodo', NEW.periodo,
+        'estado', NEW.estado,
+        'metodo_valuacion', NEW.metodo_valuacion,
+        'valor_total', NEW.valor_total,
+        'cerrado_en', NEW.cerrado_en,
+        'reabierto_en', NEW.reabierto_en,
+        'motivo_reapertura', NEW.motivo_reapertura
+    ), (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
+
+CREATE TRIGGER auditoria_periodo_delete AFTER DELETE ON periodo
+BEGIN
+    INSERT INTO auditoria (entidad, id_registro, operacion, antes, despues, usuario)
+    VALUES ('periodo', OLD.id_periodo, 'DELETE', json_object(
+        'id_periodo', OLD.id_periodo,
+        'periodo', OLD.periodo,
+        'estado', OLD.estado,
+        'metodo_valuacion', OLD.metodo_valuacion,
+        'valor_total', OLD.valor_total,
+        'cerrado_en', OLD.cerrado_en,
+        'reabierto_en', OLD.reabierto_en,
+        'motivo_reapertura', OLD.motivo_reapertura
+    ), NULL, (SELECT usuario FROM sesion WHERE id_sesion = 1));
+END;
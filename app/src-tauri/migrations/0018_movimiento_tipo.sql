@@ -0,0 +1,3 @@
+-- El tipo de movimiento se guarda en minúsculas y sin espacios, que es como
+-- lo comparan las consultas. Se corrigen los registrados antes.
+UPDATE movimiento SET tipo = LOWER(TRIM(tipo)) WHERE tipo <> LOWER(TRIM(tipo));
@@ -0,0 +1,8 @@
+-- Una sola fila por clave y almacén, incluida la global (id_almacen nulo).
+-- Si había repetidas se conserva la última que se guardó.
+DELETE FROM configuracion
+WHERE id_configuracion NOT IN (
+    SELECT MAX(id_configuracion) FROM configuracion GROUP BY clave, COALESCE(id_almacen, 0)
+);
+
+CREATE UNIQUE INDEX idx_configuracion_clave_almacen ON configuracion (clave, COALESCE(id_almacen, 0));
@@ -1,15 +1,33 @@
 use tauri::State;
 use sqlx::SqlitePool;
 
-use crate::models::almacen::{Almacen, CreateAlmacen, UpdateAlmacen};
+use crate::models::almacen::{Almacen, CreateAlmacen};
+use crate::error::AppError;
+use crate::validation::Validate;
 use crate::services::almacen_service;
 
 #[tauri::command]
-pub async fn get_almacenes(state: State<'_, SqlitePool>) -> Result<Vec<Almacen>, String> {
-    almacen_service::get_all(&state).await.map_err(|e| e.to_string())
+pub async fn get_almacenes(state: State<'_, SqlitePool>, incluir_archivados: Option<bool>) -> Result<Vec<Almacen>, AppError> {
+    almacen_service::get_all(&state, incluir_archivados.unwrap_or(false)).await
 }
 
 #[tauri::command]
-pub async fn create_almacen(state: State<'_, SqlitePool>, data: CreateAlmacen) -> Result<i64, String> {
-    almacen_service::create(&state, data).await.map_err(|e| e.to_string())
+pub async fn create_almacen(state: State<'_, SqlitePool>, data: CreateAlmacen) -> Result<i64, AppError> {
+    almacen_service::create(&state, data.validate()?).await
+}
+
+#[tauri::command]
+pub async fn delete_almacen(state: State<'_, SqlitePool>, almacen_id: i32) -> Result<bool, AppError> {
+    almacen_service::delete(&state, almacen_id).await.map(|rows| rows > 0)
+}
+
+#[tauri::command]
+pub async fn restore_almacen(state: State<'_, SqlitePool>, almacen_id: i32) -> Result<bool, AppError> {
+    almacen_service::restore(&state, almacen_id).await.map(|rows| rows > 0)
+}
+
+/// Solo para almacenes archivados y sin movimientos
+#[tauri::command]
+pub async fn purge_almacen(state: State<'_, SqlitePool>, almacen_id: i32) -> Result<bool, AppError> {
+    almacen_service::purge(&state, almacen_id).await.map(|rows| rows > 0)
 }
@@ -0,0 +1,48 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::auditoria::{Auditoria, FiltroAuditoria};
+use crate::error::AppError;
+use crate::services::auditoria_service;
+use crate::validation::Validate;
+
+#[tauri::command]
+pub async fn get_auditoria(pool: State<'_, SqlitePool>, filtro: FiltroAuditoria) -> Result<Vec<Auditoria>, AppError> {
+    let filtro = filtro.validate()?;
+    auditoria_service::buscar(&pool, &filtro).await
+}
+
+#[tauri::command]
+pub async fn get_auditoria_by_entidad(pool: State<'_, SqlitePool>, entidad: String) -> Result<Vec<Auditoria>, AppError> {
+    let filtro = FiltroAuditoria {
+        entidad: Some(entidad),
+        ..Default::default()
+    };
+    auditoria_service::buscar(&pool, &filtro).await
+}
+
+#[tauri::command]
+pub async fn get_auditoria_by_registro(
+    pool: State<'_, SqlitePool>,
+    entidad: String,
+    id_registro: i64,
+) -> Result<Vec<Auditoria>, AppError> {
+    auditoria_service::get_by_registro(&pool, &entidad, id_registro).await
+}
+
+/// Cambios entre dos fechas (`AAAA-MM-DD`, inclusivas), opcionalmente de una entidad
+#[tauri::command]
+pub async fn get_auditoria_by_fecha(
+    pool: State<'_, SqlitePool>,
+    desde: String,
+    hasta: String,
+    entidad: Option<String>,
+) -> Result<Vec<Auditoria>, AppError> {
+    let filtro = FiltroAuditoria {
+        entidad,
+        desde: Some(desde),
+        hasta: Some(hasta),
+        ..Default::default()
+    }
+    .validate()?;
+    auditoria_service::buscar(&pool, &filtro).await
+}
@@ -0,0 +1,50 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::configuracion::{EstrategiaSalida, PoliticaStock};
+use crate::error::AppError;
+use crate::services::configuracion_service;
+
+/// Política efectiva para un almacén, o la global si no se indica almacén.
+#[tauri::command]
+pub async fn get_politica_stock(pool: State<'_, SqlitePool>, almacen_id: Option<i32>) -> Result<PoliticaStock, AppError> {
+    configuracion_service::get_politica_stock(pool.inner(), almacen_id).await
+}
+
+#[tauri::command]
+pub async fn set_politica_stock(pool: State<'_, SqlitePool>, politica: PoliticaStock) -> Result<(), AppError> {
+    configuracion_service::set_politica_stock(&pool, None, Some(politica)).await
+}
+
+/// Sobrescribe la política de un almacén; sin `politica` vuelve a usar la global.
+#[tauri::command]
+pub async fn set_politica_stock_almacen(
+    pool: State<'_, SqlitePool>,
+    almacen_id: i32,
+    politica: Option<PoliticaStock>,
+) -> Result<(), AppError> {
+    configuracion_service::set_politica_stock(&pool, Some(almacen_id), politica).await
+}
+
+/// Estrategia efectiva para un almacén, o la global si no se indica almacén.
+#[tauri::command]
+pub async fn get_estrategia_salida(
+    pool: State<'_, SqlitePool>,
+    almacen_id: Option<i32>,
+) -> Result<EstrategiaSalida, AppError> {
+    configuracion_service::get_estrategia_salida(pool.inner(), almacen_id).await
+}
+
+#[tauri::command]
+pub async fn set_estrategia_salida(pool: State<'_, SqlitePool>, estrategia: EstrategiaSalida) -> Result<(), AppError> {
+    configuracion_service::set_estrategia_salida(&pool, None, Some(estrategia)).await
+}
+
+/// Sobrescribe la estrategia de un almacén; sin `estrategia` vuelve a usar la global.
+#[tauri::command]
+pub async fn set_estrategia_salida_almacen(
+    pool: State<'_, SqlitePool>,
+    almacen_id: i32,
+    estrategia: Option<EstrategiaSalida>,
+) -> Result<(), AppError> {
+    configuracion_service::set_estrategia_salida(&pool, Some(almacen_id), estrategia).await
+}
@@ -0,0 +1,52 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::conteo::{CantidadContada, Conteo, ConteoDetalle, CreateConteo};
+use crate::error::AppError;
+use crate::validation::Validate;
+use crate::services::conteo_service;
+
+#[tauri::command]
+pub async fn get_conteos(pool: State<'_, SqlitePool>) -> Result<Vec<Conteo>, AppError> {
+    conteo_service::get_all(&pool).await
+}
+
+#[tauri::command]
+pub async fn get_conteo_by_id(pool: State<'_, SqlitePool>, conteo_id: i32) -> Result<Option<Conteo>, AppError> {
+    conteo_service::get_by_id(pool.inner(), conteo_id).await
+}
+
+#[tauri::command]
+pub async fn get_conteos_by_almacen(pool: State<'_, SqlitePool>, almacen_id: i32) -> Result<Vec<Conteo>, AppError> {
+    conteo_service::get_by_almacen(&pool, almacen_id).await
+}
+
+#[tauri::command]
+pub async fn get_conteo_detalle(pool: State<'_, SqlitePool>, conteo_id: i32) -> Result<Vec<ConteoDetalle>, AppError> {
+    conteo_service::get_detalle(pool.inner(), conteo_id).await
+}
+
+/// Abre el conteo; desde ese momento el almacén no admite movimientos
+#[tauri::command]
+pub async fn create_conteo(pool: State<'_, SqlitePool>, data: CreateConteo) -> Result<i64, AppError> {
+    conteo_service::create(&pool, data.validate()?).await
+}
+
+#[tauri::command]
+pub async fn registrar_conteo(
+    pool: State<'_, SqlitePool>,
+    conteo_id: i32,
+    lineas: Vec<CantidadContada>,
+) -> Result<u64, AppError> {
+    conteo_service::registrar(&pool, conteo_id, lineas.validate()?).await
+}
+
+/// Aprueba el conteo y devuelve los ids de los ajustes registrados
+#[tauri::command]
+pub async fn aprobar_conteo(pool: State<'_, SqlitePool>, conteo_id: i32) -> Result<Vec<i64>, AppError> {
+    conteo_service::aprobar(&pool, conteo_id).await
+}
+
+#[tauri::command]
+pub async fn cancelar_conteo(pool: State<'_, SqlitePool>, conteo_id: i32) -> Result<(), AppError> {
+    conteo_service::cancelar(&pool, conteo_id).await
+}
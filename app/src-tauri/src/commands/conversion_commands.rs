@@ -0,0 +1,39 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::conversion::{ConversionRegistrada, CreateConversion, StockProductoBase};
+use crate::error::AppError;
+use crate::validation::Validate;
+use crate::services::conversion_service;
+
+#[tauri::command]
+pub async fn get_cantidad_base(pool: State<'_, SqlitePool>, presentacion_id: i32, cantidad: f64) -> Result<f64, AppError> {
+    conversion_service::a_unidad_base(&pool, presentacion_id, cantidad).await
+}
+
+#[tauri::command]
+pub async fn convertir_cantidad(
+    pool: State<'_, SqlitePool>,
+    presentacion_origen_id: i32,
+    presentacion_destino_id: i32,
+    cantidad: f64,
+) -> Result<f64, AppError> {
+    conversion_service::convertir(&pool, presentacion_origen_id, presentacion_destino_id, cantidad).await
+}
+
+/// Stock por producto en unidad base; sin almacén suma todos
+#[tauri::command]
+pub async fn get_stock_base(pool: State<'_, SqlitePool>, almacen_id: Option<i32>) -> Result<Vec<StockProductoBase>, AppError> {
+    conversion_service::get_stock_base(&pool, almacen_id).await
+}
+
+#[tauri::command]
+pub async fn create_conversion(pool: State<'_, SqlitePool>, data: CreateConversion) -> Result<ConversionRegistrada, AppError> {
+    conversion_service::create(&pool, data.validate()?).await
+}
+
+#[tauri::command]
+pub async fn delete_conversion(pool: State<'_, SqlitePool>, documento: String) -> Result<bool, AppError> {
+    conversion_service::delete(&pool, &documento)
+        .await
+        .map(|rows| rows > 0)
+}
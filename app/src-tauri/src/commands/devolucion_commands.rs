@@ -0,0 +1,50 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::devolucion::{CreateDevolucion, Devolucion, NotaCredito};
+use crate::error::AppError;
+use crate::validation::Validate;
+use crate::services::devolucion_service;
+
+#[tauri::command]
+pub async fn get_notas_credito(pool: State<'_, SqlitePool>) -> Result<Vec<NotaCredito>, AppError> {
+    devolucion_service::get_all(&pool).await
+}
+
+#[tauri::command]
+pub async fn get_nota_credito_by_id(pool: State<'_, SqlitePool>, nota_id: i32) -> Result<Option<NotaCredito>, AppError> {
+    devolucion_service::get_by_id(pool.inner(), nota_id).await
+}
+
+#[tauri::command]
+pub async fn get_notas_credito_by_factura(
+    pool: State<'_, SqlitePool>,
+    factura_id: i32,
+) -> Result<Vec<NotaCredito>, AppError> {
+    devolucion_service::get_by_factura(&pool, factura_id).await
+}
+
+#[tauri::command]
+pub async fn get_notas_credito_by_proveedor(
+    pool: State<'_, SqlitePool>,
+    proveedor_id: i32,
+) -> Result<Vec<NotaCredito>, AppError> {
+    devolucion_service::get_by_proveedor(&pool, proveedor_id).await
+}
+
+#[tauri::command]
+pub async fn get_devolucion(pool: State<'_, SqlitePool>, nota_id: i32) -> Result<Option<Devolucion>, AppError> {
+    devolucion_service::get_devolucion(&pool, nota_id).await
+}
+
+/// Devuelve mercadería al proveedor y emite la nota de crédito
+#[tauri::command]
+pub async fn create_devolucion(pool: State<'_, SqlitePool>, data: CreateDevolucion) -> Result<Devolucion, AppError> {
+    devolucion_service::create(&pool, data.validate()?).await
+}
+
+#[tauri::command]
+pub async fn delete_devolucion(pool: State<'_, SqlitePool>, nota_id: i32) -> Result<bool, AppError> {
+    devolucion_service::delete(&pool, nota_id)
+        .await
+        .map(|rows| rows > 0)
+}
@@ -1,55 +1,79 @@
 use tauri::State;
 use sqlx::SqlitePool;
 
-use crate::models::factura::{Factura, FacturaInput, FacturaUpdate};
+use crate::models::factura::{
+    AnularFactura, CreateFacturaConLineas, Factura, FacturaInput, FacturaRegistrada, FacturaTotales, FacturaUpdate,
+};
+use crate::error::AppError;
+use crate::validation::Validate;
 use crate::services::factura_service;
 
 #[tauri::command]
-pub async fn create_factura(pool: State<'_, SqlitePool>, data: FacturaInput) -> Result<i64, String> {
-    factura_service::create_factura(&pool, data)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn create_factura(pool: State<'_, SqlitePool>, data: FacturaInput) -> Result<i64, AppError> {
+    factura_service::create_factura(&pool, data.validate()?).await
 }
 
+/// Registra la factura junto con sus entradas; si una línea falla no se
+/// guarda nada
 #[tauri::command]
-pub async fn get_facturas(pool: State<'_, SqlitePool>) -> Result<Vec<Factura>, String> {
-    sqlx::query_as::<_, Factura>("SELECT * FROM factura ORDER BY id_factura ASC")
-        .fetch_all(pool.inner())
-        .await
-        .map_err(|e| e.to_string())
+pub async fn create_factura_con_lineas(
+    pool: State<'_, SqlitePool>,
+    data: CreateFacturaConLineas,
+) -> Result<FacturaRegistrada, AppError> {
+    factura_service::create_con_lineas(&pool, data.validate()?).await
 }
 
 #[tauri::command]
-pub async fn get_factura_by_id(pool: State<'_, SqlitePool>, factura_id: i32) -> Result<Option<Factura>, String> {
-    sqlx::query_as::<_, Factura>("SELECT * FROM factura WHERE id_factura = ?")
-        .bind(factura_id)
-        .fetch_optional(pool.inner())
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_facturas(pool: State<'_, SqlitePool>) -> Result<Vec<Factura>, AppError> {
+    factura_service::get_facturas(&pool).await
 }
 
 #[tauri::command]
-pub async fn get_facturas_by_proveedor(pool: State<'_, SqlitePool>, proveedor_id: i32) -> Result<Vec<Factura>, String> {
-    sqlx::query_as::<_, Factura>("SELECT * FROM factura WHERE id_proveedor = ? ORDER BY fecha DESC")
-        .bind(proveedor_id)
-        .fetch_all(pool.inner())
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_factura_by_id(pool: State<'_, SqlitePool>, factura_id: i32) -> Result<Option<Factura>, AppError> {
+    factura_service::get_factura_by_id(pool.inner(), factura_id).await
+}
+
+#[tauri::command]
+pub async fn get_facturas_by_proveedor(pool: State<'_, SqlitePool>, proveedor_id: i32) -> Result<Vec<Factura>, AppError> {
+    factura_service::get_facturas_by_proveedor(&pool, proveedor_id).await
 }
 
 #[tauri::command]
-pub async fn update_factura(pool: State<'_, SqlitePool>, factura_id: i32, data: FacturaUpdate) -> Result<bool, String> {
-    factura_service::update_factura(&pool, factura_id, data)
+pub async fn update_factura(pool: State<'_, SqlitePool>, factura_id: i32, data: FacturaUpdate) -> Result<bool, AppError> {
+    factura_service::update_factura(&pool, factura_id, data.validate()?)
         .await
         .map(|rows| rows > 0)
-        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn delete_factura(pool: State<'_, SqlitePool>, factura_id: i32) -> Result<bool, String> {
-    sqlx::query!("DELETE FROM factura WHERE id_factura = ?", factura_id)
-        .execute(pool.inner())
+pub async fn activar_factura(pool: State<'_, SqlitePool>, factura_id: i32) -> Result<(), AppError> {
+    factura_service::activar(&pool, factura_id).await
+}
+
+/// Anula la factura revirtiendo sus entradas y devuelve los ids de las
+/// salidas registradas
+#[tauri::command]
+pub async fn anular_factura(
+    pool: State<'_, SqlitePool>,
+    factura_id: i32,
+    data: AnularFactura,
+) -> Result<Vec<i64>, AppError> {
+    factura_service::anular(&pool, factura_id, data.validate()?).await
+}
+
+#[tauri::command]
+pub async fn delete_factura(pool: State<'_, SqlitePool>, factura_id: i32) -> Result<bool, AppError> {
+    factura_service::delete_factura(&pool, factura_id)
         .await
-        .map(|result| result.rows_affected() > 0)
-        .map_err(|e| e.to_string())
+        .map(|rows| rows > 0)
+}
+
+#[tauri::command]
+pub async fn get_factura_totales(pool: State<'_, SqlitePool>, factura_id: i32) -> Result<Option<FacturaTotales>, AppError> {
+    factura_service::get_totales(pool.inner(), factura_id).await
+}
+
+#[tauri::command]
+pub async fn get_facturas_descuadradas(pool: State<'_, SqlitePool>) -> Result<Vec<FacturaTotales>, AppError> {
+    factura_service::get_descuadradas(&pool).await
 }
\ No newline at end of file
@@ -0,0 +1,25 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::integridad::ReporteIntegridad;
+use crate::error::AppError;
+use crate::validation::Validator;
+use crate::services::integridad_service;
+
+/// Diferencias entre el stock registrado y los movimientos, y registros que
+/// apuntan a datos inexistentes
+#[tauri::command]
+pub async fn verificar_integridad_stock(pool: State<'_, SqlitePool>) -> Result<ReporteIntegridad, AppError> {
+    integridad_service::verificar(&pool).await
+}
+
+/// Registra con fecha `AAAA-MM-DD` los ajustes que explican cada diferencia
+#[tauri::command]
+pub async fn reparar_integridad_stock(
+    pool: State<'_, SqlitePool>,
+    fecha: String,
+) -> Result<ReporteIntegridad, AppError> {
+    let mut v = Validator::default();
+    v.date("fecha", Some(&fecha));
+    v.finish()?;
+    integridad_service::reparar(&pool, &fecha).await
+}
@@ -0,0 +1,12 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::kardex::{FiltroKardex, Kardex};
+use crate::error::AppError;
+use crate::validation::Validate;
+use crate::services::kardex_service;
+
+/// Kardex valorizado de un producto del proveedor en un rango de fechas
+#[tauri::command]
+pub async fn get_kardex(pool: State<'_, SqlitePool>, filtro: FiltroKardex) -> Result<Kardex, AppError> {
+    kardex_service::get_kardex(&pool, filtro.validate()?).await
+}
@@ -0,0 +1,46 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::lote::{Lote, LoteConsumo};
+use crate::models::vencimiento::{FiltroVencimiento, ReporteVencimiento};
+use crate::error::AppError;
+use crate::validation::Validate;
+use crate::services::{lote_service, vencimiento_service};
+
+#[tauri::command]
+pub async fn get_lotes(pool: State<'_, SqlitePool>) -> Result<Vec<Lote>, AppError> {
+    lote_service::get_all(&pool).await
+}
+
+#[tauri::command]
+pub async fn get_lote_by_id(pool: State<'_, SqlitePool>, lote_id: i32) -> Result<Option<Lote>, AppError> {
+    lote_service::get_by_id(pool.inner(), lote_id).await
+}
+
+#[tauri::command]
+pub async fn get_lotes_by_producto_proveedor(pool: State<'_, SqlitePool>, prod_prov_id: i32) -> Result<Vec<Lote>, AppError> {
+    lote_service::get_by_producto_proveedor(&pool, prod_prov_id).await
+}
+
+#[tauri::command]
+pub async fn get_lotes_disponibles(
+    pool: State<'_, SqlitePool>,
+    prod_prov_id: i32,
+    presentacion_id: i32,
+    almacen_id: i32,
+) -> Result<Vec<Lote>, AppError> {
+    lote_service::get_disponibles(pool.inner(), prod_prov_id, presentacion_id, almacen_id).await
+}
+
+#[tauri::command]
+pub async fn get_consumos_by_movimiento(pool: State<'_, SqlitePool>, movimiento_id: i32) -> Result<Vec<LoteConsumo>, AppError> {
+    lote_service::get_consumos_by_movimiento(pool.inner(), movimiento_id).await
+}
+
+/// Lotes vencidos o que vencen dentro de `dias`, con el valor en riesgo por almacén
+#[tauri::command]
+pub async fn get_lotes_por_vencer(
+    pool: State<'_, SqlitePool>,
+    filtro: FiltroVencimiento,
+) -> Result<ReporteVencimiento, AppError> {
+    vencimiento_service::get_por_vencer(&pool, filtro.validate()?).await
+}
@@ -6,6 +6,21 @@ pub mod factura_commands;
 pub mod almacen_commands;
 pub mod movimiento_commands;
 pub mod stock_commands;
+pub mod lote_commands;
+pub mod configuracion_commands;
+pub mod sesion_commands;
+pub mod auditoria_commands;
+pub mod transferencia_commands;
+pub mod conteo_commands;
+pub mod conversion_commands;
+pub mod stock_parametro_commands;
+pub mod orden_compra_commands;
+pub mod pago_commands;
+pub mod devolucion_commands;
+pub mod kardex_commands;
+pub mod stock_historico_commands;
+pub mod integridad_commands;
+pub mod periodo_commands;
 
 // Re-exportar todos los comandos
 pub use producto_commands::*;
@@ -16,6 +31,21 @@ pub use factura_commands::*;
 pub use almacen_commands::*;
 pub use movimiento_commands::*;
 pub use stock_commands::*;
+pub use lote_commands::*;
+pub use configuracion_commands::*;
+pub use sesion_commands::*;
+pub use auditoria_commands::*;
+pub use transferencia_commands::*;
+pub use conteo_commands::*;
+pub use conversion_commands::*;
+pub use stock_parametro_commands::*;
+pub use orden_compra_commands::*;
+pub use pago_commands::*;
+pub use devolucion_commands::*;
+pub use kardex_commands::*;
+pub use stock_historico_commands::*;
+pub use integridad_commands::*;
+pub use periodo_commands::*;
 
 #[cfg(test)]
 mod tests;
\ No newline at end of file
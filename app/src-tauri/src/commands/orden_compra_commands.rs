@@ -0,0 +1,76 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::orden_compra::{OrdenCompra, OrdenCompraInput, OrdenCompraLinea, RecepcionOrden};
+use crate::error::AppError;
+use crate::validation::Validate;
+use crate::services::orden_compra_service;
+
+#[tauri::command]
+pub async fn get_ordenes_compra(pool: State<'_, SqlitePool>) -> Result<Vec<OrdenCompra>, AppError> {
+    orden_compra_service::get_all(&pool).await
+}
+
+#[tauri::command]
+pub async fn get_orden_compra_by_id(pool: State<'_, SqlitePool>, orden_id: i32) -> Result<Option<OrdenCompra>, AppError> {
+    orden_compra_service::get_by_id(pool.inner(), orden_id).await
+}
+
+#[tauri::command]
+pub async fn get_ordenes_compra_by_proveedor(
+    pool: State<'_, SqlitePool>,
+    proveedor_id: i32,
+) -> Result<Vec<OrdenCompra>, AppError> {
+    orden_compra_service::get_by_proveedor(&pool, proveedor_id).await
+}
+
+#[tauri::command]
+pub async fn get_orden_compra_lineas(
+    pool: State<'_, SqlitePool>,
+    orden_id: i32,
+) -> Result<Vec<OrdenCompraLinea>, AppError> {
+    orden_compra_service::get_lineas(pool.inner(), orden_id).await
+}
+
+#[tauri::command]
+pub async fn create_orden_compra(pool: State<'_, SqlitePool>, data: OrdenCompraInput) -> Result<i64, AppError> {
+    orden_compra_service::create(&pool, data.validate()?).await
+}
+
+/// Solo las órdenes en borrador se pueden modificar
+#[tauri::command]
+pub async fn update_orden_compra(
+    pool: State<'_, SqlitePool>,
+    orden_id: i32,
+    data: OrdenCompraInput,
+) -> Result<bool, AppError> {
+    orden_compra_service::update(&pool, orden_id, data.validate()?)
+        .await
+        .map(|rows| rows > 0)
+}
+
+#[tauri::command]
+pub async fn delete_orden_compra(pool: State<'_, SqlitePool>, orden_id: i32) -> Result<bool, AppError> {
+    orden_compra_service::delete(&pool, orden_id)
+        .await
+        .map(|rows| rows > 0)
+}
+
+#[tauri::command]
+pub async fn enviar_orden_compra(pool: State<'_, SqlitePool>, orden_id: i32) -> Result<(), AppError> {
+    orden_compra_service::enviar(&pool, orden_id).await
+}
+
+/// Registra lo recibido contra la orden y devuelve los ids de las entradas
+#[tauri::command]
+pub async fn recibir_orden_compra(
+    pool: State<'_, SqlitePool>,
+    orden_id: i32,
+    data: RecepcionOrden,
+) -> Result<Vec<i64>, AppError> {
+    orden_compra_service::recibir(&pool, orden_id, data.validate()?).await
+}
+
+#[tauri::command]
+pub async fn cerrar_orden_compra(pool: State<'_, SqlitePool>, orden_id: i32) -> Result<(), AppError> {
+    orden_compra_service::cerrar(&pool, orden_id).await
+}
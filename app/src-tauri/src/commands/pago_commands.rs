@@ -0,0 +1,95 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::pago::{
+    AntiguedadSaldo, AplicacionPago, CreatePago, MovimientoCuenta, Pago, PagoAplicacion, SaldoFactura, SaldoProveedor,
+};
+use crate::error::AppError;
+use crate::validation::{Validate, Validator};
+use crate::services::pago_service;
+
+#[tauri::command]
+pub async fn get_pagos(pool: State<'_, SqlitePool>) -> Result<Vec<Pago>, AppError> {
+    pago_service::get_all(&pool).await
+}
+
+#[tauri::command]
+pub async fn get_pago_by_id(pool: State<'_, SqlitePool>, pago_id: i32) -> Result<Option<Pago>, AppError> {
+    pago_service::get_by_id(pool.inner(), pago_id).await
+}
+
+#[tauri::command]
+pub async fn get_pagos_by_proveedor(pool: State<'_, SqlitePool>, proveedor_id: i32) -> Result<Vec<Pago>, AppError> {
+    pago_service::get_by_proveedor(&pool, proveedor_id).await
+}
+
+#[tauri::command]
+pub async fn get_pago_aplicaciones(
+    pool: State<'_, SqlitePool>,
+    pago_id: i32,
+) -> Result<Vec<PagoAplicacion>, AppError> {
+    pago_service::get_aplicaciones(pool.inner(), pago_id).await
+}
+
+#[tauri::command]
+pub async fn get_pago_aplicaciones_by_factura(
+    pool: State<'_, SqlitePool>,
+    factura_id: i32,
+) -> Result<Vec<PagoAplicacion>, AppError> {
+    pago_service::get_aplicaciones_by_factura(&pool, factura_id).await
+}
+
+#[tauri::command]
+pub async fn create_pago(pool: State<'_, SqlitePool>, data: CreatePago) -> Result<i64, AppError> {
+    pago_service::create(&pool, data.validate()?).await
+}
+
+/// Aplica a facturas lo que quedó sin aplicar del pago
+#[tauri::command]
+pub async fn aplicar_pago(
+    pool: State<'_, SqlitePool>,
+    pago_id: i32,
+    aplicaciones: Vec<AplicacionPago>,
+) -> Result<(), AppError> {
+    pago_service::aplicar(&pool, pago_id, aplicaciones.validate()?).await
+}
+
+#[tauri::command]
+pub async fn delete_pago(pool: State<'_, SqlitePool>, pago_id: i32) -> Result<bool, AppError> {
+    pago_service::delete(&pool, pago_id)
+        .await
+        .map(|rows| rows > 0)
+}
+
+#[tauri::command]
+pub async fn get_saldos_factura(
+    pool: State<'_, SqlitePool>,
+    proveedor_id: Option<i32>,
+) -> Result<Vec<SaldoFactura>, AppError> {
+    pago_service::get_saldos_factura(&pool, proveedor_id).await
+}
+
+#[tauri::command]
+pub async fn get_saldos_proveedor(pool: State<'_, SqlitePool>) -> Result<Vec<SaldoProveedor>, AppError> {
+    pago_service::get_saldos_proveedor(&pool).await
+}
+
+/// Facturas, pagos y notas de crédito del proveedor con el saldo acumulado
+#[tauri::command]
+pub async fn get_estado_cuenta_proveedor(
+    pool: State<'_, SqlitePool>,
+    proveedor_id: i32,
+) -> Result<Vec<MovimientoCuenta>, AppError> {
+    pago_service::get_estado_cuenta(&pool, proveedor_id).await
+}
+
+/// Antigüedad de las cuentas por pagar a la fecha de corte (`AAAA-MM-DD`)
+#[tauri::command]
+pub async fn get_antiguedad_saldos(
+    pool: State<'_, SqlitePool>,
+    fecha_corte: String,
+) -> Result<Vec<AntiguedadSaldo>, AppError> {
+    let mut v = Validator::default();
+    v.date("fecha_corte", Some(&fecha_corte));
+    v.finish()?;
+    pago_service::get_antiguedad(&pool, &fecha_corte).await
+}
@@ -0,0 +1,30 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::periodo::{CerrarPeriodo, CierrePeriodo, Periodo, ReabrirPeriodo};
+use crate::error::AppError;
+use crate::validation::Validate;
+use crate::services::periodo_service;
+
+#[tauri::command]
+pub async fn get_periodos(pool: State<'_, SqlitePool>) -> Result<Vec<Periodo>, AppError> {
+    periodo_service::get_all(&pool).await
+}
+
+/// Período `AAAA-MM` con el stock valorizado de su último cierre
+#[tauri::command]
+pub async fn get_cierre_periodo(
+    pool: State<'_, SqlitePool>,
+    periodo: String,
+) -> Result<Option<CierrePeriodo>, AppError> {
+    periodo_service::get_cierre(&pool, &periodo).await
+}
+
+#[tauri::command]
+pub async fn cerrar_periodo(pool: State<'_, SqlitePool>, data: CerrarPeriodo) -> Result<CierrePeriodo, AppError> {
+    periodo_service::cerrar(&pool, data.validate()?).await
+}
+
+#[tauri::command]
+pub async fn reabrir_periodo(pool: State<'_, SqlitePool>, data: ReabrirPeriodo) -> Result<Periodo, AppError> {
+    periodo_service::reabrir(&pool, data.validate()?).await
+}
@@ -2,48 +2,40 @@ use tauri::State;
 use sqlx::SqlitePool;
 
 use crate::models::presentacion::{Presentacion, CreatePresentacion, UpdatePresentacion};
+use crate::error::AppError;
+use crate::validation::Validate;
 use crate::services::presentacion_service;
 
 #[tauri::command]
-pub async fn create_presentacion(pool: State<'_, SqlitePool>, data: CreatePresentacion) -> Result<i64, String> {
-    presentacion_service::create(&pool, data)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn create_presentacion(pool: State<'_, SqlitePool>, data: CreatePresentacion) -> Result<i64, AppError> {
+    presentacion_service::create(&pool, data.validate()?).await
 }
 
 #[tauri::command]
-pub async fn get_presentaciones(pool: State<'_, SqlitePool>) -> Result<Vec<Presentacion>, String> {
-    presentacion_service::get_all(&pool)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_presentaciones(pool: State<'_, SqlitePool>) -> Result<Vec<Presentacion>, AppError> {
+    presentacion_service::get_all(&pool).await
 }
 
 #[tauri::command]
-pub async fn get_presentacion_by_id(pool: State<'_, SqlitePool>, presentacion_id: i32) -> Result<Option<Presentacion>, String> {
-    presentacion_service::get_by_id(&pool, presentacion_id)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_presentacion_by_id(pool: State<'_, SqlitePool>, presentacion_id: i32) -> Result<Option<Presentacion>, AppError> {
+    presentacion_service::get_by_id(pool.inner(), presentacion_id).await
 }
 
 #[tauri::command]
-pub async fn get_presentaciones_by_producto(pool: State<'_, SqlitePool>, producto_id: i32) -> Result<Vec<Presentacion>, String> {
-    presentacion_service::get_by_producto(&pool, producto_id)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_presentaciones_by_producto(pool: State<'_, SqlitePool>, producto_id: i32) -> Result<Vec<Presentacion>, AppError> {
+    presentacion_service::get_by_producto(&pool, producto_id).await
 }
 
 #[tauri::command]
-pub async fn update_presentacion(pool: State<'_, SqlitePool>, presentacion_id: i32, data: UpdatePresentacion) -> Result<bool, String> {
-    presentacion_service::update(&pool, presentacion_id, data)
+pub async fn update_presentacion(pool: State<'_, SqlitePool>, presentacion_id: i32, data: UpdatePresentacion) -> Result<bool, AppError> {
+    presentacion_service::update(&pool, presentacion_id, data.validate()?)
         .await
         .map(|rows| rows > 0)
-        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn delete_presentacion(pool: State<'_, SqlitePool>, presentacion_id: i32) -> Result<bool, String> {
+pub async fn delete_presentacion(pool: State<'_, SqlitePool>, presentacion_id: i32) -> Result<bool, AppError> {
     presentacion_service::delete(&pool, presentacion_id)
         .await
         .map(|rows| rows > 0)
-        .map_err(|e| e.to_string())
 }
\ No newline at end of file
@@ -2,41 +2,50 @@ use tauri::State;
 use sqlx::SqlitePool;
 
 use crate::models::producto::{Producto, CreateProducto, UpdateProducto};
+use crate::error::AppError;
+use crate::validation::Validate;
 use crate::services::producto_service;
 
 #[tauri::command]
-pub async fn create_product(pool: State<'_, SqlitePool>, product_data: CreateProducto) -> Result<i64, String> {
-    producto_service::create(&pool, product_data)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn create_product(pool: State<'_, SqlitePool>, product_data: CreateProducto) -> Result<i64, AppError> {
+    producto_service::create(&pool, product_data.validate()?).await
+}
+
+#[tauri::command]
+pub async fn get_products(pool: State<'_, SqlitePool>, incluir_archivados: Option<bool>) -> Result<Vec<Producto>, AppError> {
+    producto_service::get_all(&pool, incluir_archivados.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn get_product_by_id(pool: State<'_, SqlitePool>, product_id: i32, incluir_archivados: Option<bool>) -> Result<Option<Producto>, AppError> {
+    producto_service::get_by_id(&pool, product_id, incluir_archivados.unwrap_or(false)).await
 }
 
 #[tauri::command]
-pub async fn get_products(pool: State<'_, SqlitePool>) -> Result<Vec<Producto>, String> {
-    producto_service::get_all(&pool)
+pub async fn update_product(pool: State<'_, SqlitePool>, product_id: i32, product_data: UpdateProducto) -> Result<bool, AppError> {
+    producto_service::update(&pool, product_id, product_data.validate()?)
         .await
-        .map_err(|e| e.to_string())
+        .map(|rows| rows > 0)
 }
 
 #[tauri::command]
-pub async fn get_product_by_id(pool: State<'_, SqlitePool>, product_id: i32) -> Result<Option<Producto>, String> {
-    producto_service::get_by_id(&pool, product_id)
+pub async fn delete_product(pool: State<'_, SqlitePool>, product_id: i32) -> Result<bool, AppError> {
+    producto_service::delete(&pool, product_id)
         .await
-        .map_err(|e| e.to_string())
+        .map(|rows| rows > 0)
 }
 
 #[tauri::command]
-pub async fn update_product(pool: State<'_, SqlitePool>, product_id: i32, product_data: UpdateProducto) -> Result<bool, String> {
-    producto_service::update(&pool, product_id, product_data)
+pub async fn restore_product(pool: State<'_, SqlitePool>, product_id: i32) -> Result<bool, AppError> {
+    producto_service::restore(&pool, product_id)
         .await
         .map(|rows| rows > 0)
-        .map_err(|e| e.to_string())
 }
 
+/// Solo para productos archivados y sin movimientos
 #[tauri::command]
-pub async fn delete_product(pool: State<'_, SqlitePool>, product_id: i32) -> Result<bool, String> {
-    producto_service::delete(&pool, product_id)
+pub async fn purge_product(pool: State<'_, SqlitePool>, product_id: i32) -> Result<bool, AppError> {
+    producto_service::purge(&pool, product_id)
         .await
         .map(|rows| rows > 0)
-        .map_err(|e| e.to_string())
-}
\ No newline at end of file
+}
@@ -2,41 +2,35 @@ use tauri::State;
 use sqlx::SqlitePool;
 
 use crate::models::producto_proveedor::{ProductoProveedor, CreateProductoProveedor, UpdateProductoProveedor};
+use crate::error::AppError;
+use crate::validation::Validate;
 use crate::services::producto_proveedor_service;
 
 #[tauri::command]
-pub async fn create_producto_proveedor(pool: State<'_, SqlitePool>, data: CreateProductoProveedor) -> Result<i64, String> {
-    producto_proveedor_service::create(&pool, data)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn create_producto_proveedor(pool: State<'_, SqlitePool>, data: CreateProductoProveedor) -> Result<i64, AppError> {
+    producto_proveedor_service::create(&pool, data.validate()?).await
 }
 
 #[tauri::command]
-pub async fn get_producto_proveedores(pool: State<'_, SqlitePool>) -> Result<Vec<ProductoProveedor>, String> {
-    producto_proveedor_service::get_all(&pool)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_producto_proveedores(pool: State<'_, SqlitePool>) -> Result<Vec<ProductoProveedor>, AppError> {
+    producto_proveedor_service::get_all(&pool).await
 }
 
 #[tauri::command]
-pub async fn get_producto_proveedor_by_id(pool: State<'_, SqlitePool>, pp_id: i32) -> Result<Option<ProductoProveedor>, String> {
-    producto_proveedor_service::get_by_id(&pool, pp_id)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_producto_proveedor_by_id(pool: State<'_, SqlitePool>, pp_id: i32) -> Result<Option<ProductoProveedor>, AppError> {
+    producto_proveedor_service::get_by_id(pool.inner(), pp_id).await
 }
 
 #[tauri::command]
-pub async fn update_producto_proveedor(pool: State<'_, SqlitePool>, pp_id: i32, data: UpdateProductoProveedor) -> Result<bool, String> {
-    producto_proveedor_service::update(&pool, pp_id, data)
+pub async fn update_producto_proveedor(pool: State<'_, SqlitePool>, pp_id: i32, data: UpdateProductoProveedor) -> Result<bool, AppError> {
+    producto_proveedor_service::update(&pool, pp_id, data.validate()?)
         .await
         .map(|rows| rows > 0)
-        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn delete_producto_proveedor(pool: State<'_, SqlitePool>, pp_id: i32) -> Result<bool, String> {
+pub async fn delete_producto_proveedor(pool: State<'_, SqlitePool>, pp_id: i32) -> Result<bool, AppError> {
     producto_proveedor_service::delete(&pool, pp_id)
         .await
         .map(|rows| rows > 0)
-        .map_err(|e| e.to_string())
 }
\ No newline at end of file
@@ -2,41 +2,50 @@ use tauri::State;
 use sqlx::SqlitePool;
 
 use crate::models::proveedor::{Proveedor, CreateProveedor, UpdateProveedor};
+use crate::error::AppError;
+use crate::validation::Validate;
 use crate::services::proveedor_service;
 
 #[tauri::command]
-pub async fn create_proveedor(pool: State<'_, SqlitePool>, proveedor_data: CreateProveedor) -> Result<i64, String> {
-    proveedor_service::create(&pool, proveedor_data)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn create_proveedor(pool: State<'_, SqlitePool>, proveedor_data: CreateProveedor) -> Result<i64, AppError> {
+    proveedor_service::create(&pool, proveedor_data.validate()?).await
+}
+
+#[tauri::command]
+pub async fn get_proveedores(pool: State<'_, SqlitePool>, incluir_archivados: Option<bool>) -> Result<Vec<Proveedor>, AppError> {
+    proveedor_service::get_all(&pool, incluir_archivados.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn get_proveedor_by_id(pool: State<'_, SqlitePool>, proveedor_id: i32, incluir_archivados: Option<bool>) -> Result<Option<Proveedor>, AppError> {
+    proveedor_service::get_by_id(&pool, proveedor_id, incluir_archivados.unwrap_or(false)).await
 }
 
 #[tauri::command]
-pub async fn get_proveedores(pool: State<'_, SqlitePool>) -> Result<Vec<Proveedor>, String> {
-    proveedor_service::get_all(&pool)
+pub async fn update_proveedor(pool: State<'_, SqlitePool>, proveedor_id: i32, proveedor_data: UpdateProveedor) -> Result<bool, AppError> {
+    proveedor_service::update(&pool, proveedor_id, proveedor_data.validate()?)
         .await
-        .map_err(|e| e.to_string())
+        .map(|rows| rows > 0)
 }
 
 #[tauri::command]
-pub async fn get_proveedor_by_id(pool: State<'_, SqlitePool>, proveedor_id: i32) -> Result<Option<Proveedor>, String> {
-    proveedor_service::get_by_id(&pool, proveedor_id)
+pub async fn delete_proveedor(pool: State<'_, SqlitePool>, proveedor_id: i32) -> Result<bool, AppError> {
+    proveedor_service::delete(&pool, proveedor_id)
         .await
-        .map_err(|e| e.to_string())
+        .map(|rows| rows > 0)
 }
 
 #[tauri::command]
-pub async fn update_proveedor(pool: State<'_, SqlitePool>, proveedor_id: i32, proveedor_data: UpdateProveedor) -> Result<bool, String> {
-    proveedor_service::update(&pool, proveedor_id, proveedor_data)
+pub async fn restore_proveedor(pool: State<'_, SqlitePool>, proveedor_id: i32) -> Result<bool, AppError> {
+    proveedor_service::restore(&pool, proveedor_id)
         .await
         .map(|rows| rows > 0)
-        .map_err(|e| e.to_string())
 }
 
+/// Solo para proveedores archivados, sin facturas ni movimientos
 #[tauri::command]
-pub async fn delete_proveedor(pool: State<'_, SqlitePool>, proveedor_id: i32) -> Result<bool, String> {
-    proveedor_service::delete(&pool, proveedor_id)
+pub async fn purge_proveedor(pool: State<'_, SqlitePool>, proveedor_id: i32) -> Result<bool, AppError> {
+    proveedor_service::purge(&pool, proveedor_id)
         .await
         .map(|rows| rows > 0)
-        .map_err(|e| e.to_string())
-}
\ No newline at end of file
+}
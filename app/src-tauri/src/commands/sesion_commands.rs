@@ -0,0 +1,15 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::error::AppError;
+use crate::services::sesion_service;
+
+#[tauri::command]
+pub async fn get_usuario_actual(pool: State<'_, SqlitePool>) -> Result<Option<String>, AppError> {
+    sesion_service::get_usuario(pool.inner()).await
+}
+
+/// Sin `usuario` se cierra la sesión
+#[tauri::command]
+pub async fn set_usuario_actual(pool: State<'_, SqlitePool>, usuario: Option<String>) -> Result<(), AppError> {
+    sesion_service::set_usuario(&pool, usuario.as_deref()).await
+}
@@ -3,50 +3,43 @@ use sqlx::{SqlitePool, FromRow};
 use serde::Serialize;
 
 use crate::models::stock_almacen::{StockAlmacen, CreateStockAlmacen, UpdateStockAlmacen};
-use crate::services::stock_almacen_service;
+use crate::models::valuacion::{FiltroValuacion, ValuacionStock};
+use crate::error::AppError;
+use crate::validation::Validate;
+use crate::services::{stock_almacen_service, stock_parametro_service, valuacion_service};
 
 #[tauri::command]
-pub async fn create_stock_almacen(pool: State<'_, SqlitePool>, data: CreateStockAlmacen) -> Result<i64, String> {
-    stock_almacen_service::create(&pool, data)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn create_stock_almacen(pool: State<'_, SqlitePool>, data: CreateStockAlmacen) -> Result<i64, AppError> {
+    stock_almacen_service::create(&pool, data.validate()?).await
 }
 
 #[tauri::command]
-pub async fn get_stock_almacen(pool: State<'_, SqlitePool>) -> Result<Vec<StockAlmacen>, String> {
-    stock_almacen_service::get_all(&pool)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_stock_almacen(pool: State<'_, SqlitePool>) -> Result<Vec<StockAlmacen>, AppError> {
+    stock_almacen_service::get_all(&pool).await
 }
 
 #[tauri::command]
-pub async fn get_stock_by_almacen(pool: State<'_, SqlitePool>, almacen_id: i32) -> Result<Vec<StockAlmacen>, String> {
-    stock_almacen_service::get_by_almacen(&pool, almacen_id)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_stock_by_almacen(pool: State<'_, SqlitePool>, almacen_id: i32) -> Result<Vec<StockAlmacen>, AppError> {
+    stock_almacen_service::get_by_almacen(&pool, almacen_id).await
 }
 
 #[tauri::command]
-pub async fn get_stock_by_producto_proveedor(pool: State<'_, SqlitePool>, prod_prov_id: i32) -> Result<Vec<StockAlmacen>, String> {
-    stock_almacen_service::get_by_producto_proveedor(&pool, prod_prov_id)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_stock_by_producto_proveedor(pool: State<'_, SqlitePool>, prod_prov_id: i32) -> Result<Vec<StockAlmacen>, AppError> {
+    stock_almacen_service::get_by_producto_proveedor(&pool, prod_prov_id).await
 }
 
 #[tauri::command]
-pub async fn update_stock_almacen(pool: State<'_, SqlitePool>, stock_id: i32, data: UpdateStockAlmacen) -> Result<bool, String> {
-    stock_almacen_service::update(&pool, stock_id, data)
+pub async fn update_stock_almacen(pool: State<'_, SqlitePool>, stock_id: i32, data: UpdateStockAlmacen) -> Result<bool, AppError> {
+    stock_almacen_service::update(&pool, stock_id, data.validate()?)
         .await
         .map(|rows| rows > 0)
-        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn delete_stock_almacen(pool: State<'_, SqlitePool>, stock_id: i32) -> Result<bool, String> {
+pub async fn delete_stock_almacen(pool: State<'_, SqlitePool>, stock_id: i32) -> Result<bool, AppError> {
     stock_almacen_service::delete(&pool, stock_id)
         .await
         .map(|rows| rows > 0)
-        .map_err(|e| e.to_string())
 }
 
 #[derive(Serialize, FromRow)]
@@ -58,7 +51,7 @@ pub struct StockActual {
 }
 
 #[tauri::command]
-pub async fn get_stock_actual_all(pool: State<'_, SqlitePool>) -> Result<Vec<StockActual>, String> {
+pub async fn get_stock_actual_all(pool: State<'_, SqlitePool>) -> Result<Vec<StockActual>, AppError> {
     sqlx::query_as::<_, StockActual>(
         "SELECT id_prod_prov, id_presentacion, id_almacen, stock_actual 
          FROM stock_almacen 
@@ -66,21 +59,18 @@ pub async fn get_stock_actual_all(pool: State<'_, SqlitePool>) -> Result<Vec<Sto
     )
     .fetch_all(pool.inner())
     .await
-    .map_err(|e| e.to_string())
+    .map_err(AppError::from)
 }
 
+/// Stock valorizado por producto, presentación y almacén, con subtotales por
+/// categoría y almacén
 #[tauri::command]
-pub async fn get_low_stock_products(pool: State<'_, SqlitePool>) -> Result<Vec<crate::models::Producto>, String> {
-    // Obtener productos con stock bajo (menos de 10 unidades en cualquier almacén)
-    sqlx::query_as::<_, crate::models::Producto>(
-        "SELECT DISTINCT p.id_producto, p.codigo_interno, p.descripcion, p.categoria, p.subcategoria, p.estado
-         FROM producto p
-         INNER JOIN producto_proveedor pp ON p.id_producto = pp.id_producto
-         INNER JOIN stock_almacen sa ON pp.id_prod_prov = sa.id_prod_prov
-         WHERE sa.stock_actual < 10
-         ORDER BY p.id_producto ASC"
-    )
-    .fetch_all(pool.inner())
-    .await
-    .map_err(|e| e.to_string())
-}
\ No newline at end of file
+pub async fn get_valuacion_stock(pool: State<'_, SqlitePool>, filtro: FiltroValuacion) -> Result<ValuacionStock, AppError> {
+    valuacion_service::get_valuacion(pool.inner(), filtro.validate()?).await
+}
+
+/// Productos bajo su stock mínimo o en su punto de reorden en algún almacén
+#[tauri::command]
+pub async fn get_low_stock_products(pool: State<'_, SqlitePool>) -> Result<Vec<crate::models::Producto>, AppError> {
+    stock_parametro_service::get_productos_stock_bajo(&pool).await
+}
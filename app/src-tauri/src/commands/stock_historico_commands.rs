@@ -0,0 +1,37 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::stock_historico::{CorteStock, FiltroStockHistorico, StockHistorico};
+use crate::error::AppError;
+use crate::validation::{Validate, Validator};
+use crate::services::stock_historico_service;
+
+/// Stock por ubicación al final de una fecha pasada
+#[tauri::command]
+pub async fn get_stock_a_fecha(
+    pool: State<'_, SqlitePool>,
+    filtro: FiltroStockHistorico,
+) -> Result<StockHistorico, AppError> {
+    stock_historico_service::get_stock_a_fecha(&pool, filtro.validate()?).await
+}
+
+#[tauri::command]
+pub async fn get_cortes_stock(pool: State<'_, SqlitePool>) -> Result<Vec<CorteStock>, AppError> {
+    stock_historico_service::get_cortes(&pool).await
+}
+
+/// Guarda el stock a la fecha (`AAAA-MM-DD`) para acelerar las consultas
+/// posteriores a ella
+#[tauri::command]
+pub async fn crear_corte_stock(pool: State<'_, SqlitePool>, fecha: String) -> Result<CorteStock, AppError> {
+    let mut v = Validator::default();
+    v.date("fecha", Some(&fecha));
+    v.finish()?;
+    stock_historico_service::crear_corte(&pool, &fecha).await
+}
+
+#[tauri::command]
+pub async fn delete_corte_stock(pool: State<'_, SqlitePool>, corte_id: i32) -> Result<bool, AppError> {
+    stock_historico_service::delete_corte(&pool, corte_id)
+        .await
+        .map(|rows| rows > 0)
+}
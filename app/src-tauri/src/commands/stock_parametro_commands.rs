@@ -0,0 +1,36 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::stock_parametro::{NivelStock, SetStockParametro, StockParametro, SugerenciaReorden};
+use crate::error::AppError;
+use crate::validation::Validate;
+use crate::services::stock_parametro_service;
+
+#[tauri::command]
+pub async fn get_stock_parametros(pool: State<'_, SqlitePool>, producto_id: i32) -> Result<Vec<StockParametro>, AppError> {
+    stock_parametro_service::get_by_producto(&pool, producto_id).await
+}
+
+/// Sin `id_almacen` guarda los parámetros generales del producto; sin valores
+/// elimina la fila
+#[tauri::command]
+pub async fn set_stock_parametro(pool: State<'_, SqlitePool>, data: SetStockParametro) -> Result<(), AppError> {
+    stock_parametro_service::set(&pool, data.validate()?).await
+}
+
+#[tauri::command]
+pub async fn get_niveles_stock(pool: State<'_, SqlitePool>, almacen_id: Option<i32>) -> Result<Vec<NivelStock>, AppError> {
+    stock_parametro_service::get_niveles(&pool, almacen_id).await
+}
+
+#[tauri::command]
+pub async fn get_stock_bajo(pool: State<'_, SqlitePool>, almacen_id: Option<i32>) -> Result<Vec<NivelStock>, AppError> {
+    stock_parametro_service::get_stock_bajo(&pool, almacen_id).await
+}
+
+#[tauri::command]
+pub async fn get_sugerencias_reorden(
+    pool: State<'_, SqlitePool>,
+    almacen_id: Option<i32>,
+) -> Result<Vec<SugerenciaReorden>, AppError> {
+    stock_parametro_service::get_sugerencias(&pool, almacen_id).await
+}
@@ -5,21 +5,7 @@ mod almacen_commands_tests {
     use sqlx::SqlitePool;
 
     async fn setup_test_db() -> SqlitePool {
-        let pool = SqlitePool::connect(":memory:").await.unwrap();
-        
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS almacen (
-                id_almacen INTEGER PRIMARY KEY AUTOINCREMENT,
-                nombre TEXT NOT NULL,
-                ubicacion TEXT,
-                responsable TEXT
-            )"
-        )
-        .execute(&pool)
-        .await
-        .unwrap();
-
-        pool
+        crate::db::test_pool().await
     }
 
     #[tokio::test]
@@ -43,7 +29,7 @@ mod almacen_commands_tests {
             .await
             .unwrap();
 
-        let result = almacen_service::get_all(&pool).await;
+        let result = almacen_service::get_all(&pool, false).await;
         
         assert!(result.is_ok());
         let almacenes = result.unwrap();
@@ -56,7 +42,7 @@ mod almacen_commands_tests {
     async fn test_get_almacenes_empty() {
         let pool = setup_test_db().await;
         
-        let result = almacen_service::get_all(&pool).await;
+        let result = almacen_service::get_all(&pool, false).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap().len(), 0);
     }
@@ -129,43 +115,13 @@ mod factura_commands_tests {
     use sqlx::SqlitePool;
 
     async fn setup_test_db() -> SqlitePool {
-        let pool = SqlitePool::connect(":memory:").await.unwrap();
-        
-        // Crear tabla proveedor
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS proveedor (
-                id_proveedor INTEGER PRIMARY KEY AUTOINCREMENT,
-                nombre TEXT NOT NULL,
-                contacto TEXT,
-                telefono TEXT,
-                email TEXT
-            )"
-        )
-        .execute(&pool)
-        .await
-        .unwrap();
+        let pool = crate::db::test_pool().await;
 
-        sqlx::query("INSERT INTO proveedor (nombre) VALUES ('Proveedor Test')")
+        sqlx::query("INSERT INTO proveedor (ruc_ci, nombre) VALUES ('0012505900001A', 'Proveedor Test')")
             .execute(&pool)
             .await
             .unwrap();
 
-        // Crear tabla factura
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS factura (
-                id_factura INTEGER PRIMARY KEY AUTOINCREMENT,
-                numero TEXT NOT NULL,
-                fecha TEXT NOT NULL,
-                id_proveedor INTEGER NOT NULL,
-                total REAL,
-                estado TEXT,
-                FOREIGN KEY (id_proveedor) REFERENCES proveedor(id_proveedor)
-            )"
-        )
-        .execute(&pool)
-        .await
-        .unwrap();
-
         pool
     }
 
@@ -178,7 +134,9 @@ mod factura_commands_tests {
             fecha: "2025-01-15".to_string(),
             id_proveedor: 1,
             total: Some(1500.50),
-            estado: Some("Pendiente".to_string()),
+            estado: Some("Activa".to_string()),
+            descuento: None,
+            porcentaje_impuesto: None,
         };
 
         let result = factura_service::create_factura(&pool, data).await;
@@ -200,7 +158,7 @@ mod factura_commands_tests {
         assert_eq!(factura.fecha, "2025-01-15");
         assert_eq!(factura.id_proveedor, 1);
         assert_eq!(factura.total, Some(1500.50));
-        assert_eq!(factura.estado, Some("Pendiente".to_string()));
+        assert_eq!(factura.estado, Some("Activa".to_string()));
     }
 
     #[tokio::test]
@@ -213,6 +171,8 @@ mod factura_commands_tests {
             id_proveedor: 1,
             total: None,
             estado: None,
+            descuento: None,
+            porcentaje_impuesto: None,
         };
 
         let result = factura_service::create_factura(&pool, data).await;
@@ -229,6 +189,8 @@ mod factura_commands_tests {
             id_proveedor: 999, // ID que no existe
             total: Some(100.0),
             estado: None,
+            descuento: None,
+            porcentaje_impuesto: None,
         };
 
         let result = factura_service::create_factura(&pool, data).await;
@@ -247,6 +209,8 @@ mod factura_commands_tests {
             id_proveedor: 1,
             total: None,
             estado: None,
+            descuento: None,
+            porcentaje_impuesto: None,
         };
 
         let result = factura_service::create_factura(&pool, data).await;
@@ -262,50 +226,7 @@ mod integration_tests {
     use crate::models::factura::FacturaInput;
 
     async fn setup_full_db() -> SqlitePool {
-        let pool = SqlitePool::connect(":memory:").await.unwrap();
-        
-        // Crear todas las tablas necesarias
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS almacen (
-                id_almacen INTEGER PRIMARY KEY AUTOINCREMENT,
-                nombre TEXT NOT NULL,
-                ubicacion TEXT,
-                responsable TEXT
-            )"
-        )
-        .execute(&pool)
-        .await
-        .unwrap();
-
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS proveedor (
-                id_proveedor INTEGER PRIMARY KEY AUTOINCREMENT,
-                nombre TEXT NOT NULL,
-                contacto TEXT,
-                telefono TEXT,
-                email TEXT
-            )"
-        )
-        .execute(&pool)
-        .await
-        .unwrap();
-
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS factura (
-                id_factura INTEGER PRIMARY KEY AUTOINCREMENT,
-                numero TEXT NOT NULL,
-                fecha TEXT NOT NULL,
-                id_proveedor INTEGER NOT NULL,
-                total REAL,
-                estado TEXT,
-                FOREIGN KEY (id_proveedor) REFERENCES proveedor(id_proveedor)
-            )"
-        )
-        .execute(&pool)
-        .await
-        .unwrap();
-
-        pool
+        crate::db::test_pool().await
     }
 
     #[tokio::test]
@@ -313,7 +234,7 @@ mod integration_tests {
         let pool = setup_full_db().await;
         
         // 1. Verificar que está vacío
-        let almacenes = almacen_service::get_all(&pool).await.unwrap();
+        let almacenes = almacen_service::get_all(&pool, false).await.unwrap();
         assert_eq!(almacenes.len(), 0);
 
         // 2. Crear múltiples almacenes
@@ -336,7 +257,7 @@ mod integration_tests {
         assert_eq!(id2, 2);
 
         // 4. Obtener todos y verificar
-        let almacenes = almacen_service::get_all(&pool).await.unwrap();
+        let almacenes = almacen_service::get_all(&pool, false).await.unwrap();
         assert_eq!(almacenes.len(), 2);
         assert_eq!(almacenes[0].nombre, "Almacén Principal");
         assert_eq!(almacenes[1].nombre, "Almacén Secundario");
@@ -347,7 +268,8 @@ mod integration_tests {
         let pool = setup_full_db().await;
         
         // Crear proveedor primero
-        sqlx::query("INSERT INTO proveedor (nombre, contacto) VALUES (?, ?)")
+        sqlx::query("INSERT INTO proveedor (ruc_ci, nombre, contacto) VALUES (?, ?, ?)")
+            .bind("0012505900001A")
             .bind("Proveedor ABC")
             .bind("contacto@abc.com")
             .execute(&pool)
@@ -360,7 +282,9 @@ mod integration_tests {
             fecha: "2025-01-15".to_string(),
             id_proveedor: 1,
             total: Some(1000.0),
-            estado: Some("Pendiente".to_string()),
+            estado: Some("Activa".to_string()),
+            descuento: None,
+            porcentaje_impuesto: None,
         };
         let id1 = factura_service::create_factura(&pool, data1).await.unwrap();
 
@@ -369,7 +293,9 @@ mod integration_tests {
             fecha: "2025-01-16".to_string(),
             id_proveedor: 1,
             total: Some(2000.0),
-            estado: Some("Pagada".to_string()),
+            estado: Some("Borrador".to_string()),
+            descuento: None,
+            porcentaje_impuesto: None,
         };
         let id2 = factura_service::create_factura(&pool, data2).await.unwrap();
 
@@ -384,3 +310,155 @@ mod integration_tests {
         assert_eq!(count, 2);
     }
 }
+
+#[cfg(test)]
+mod validation_tests {
+    use crate::error::AppError;
+    use crate::models::movimiento::CreateMovimiento;
+    use crate::models::producto::{CreateProducto, UpdateProducto};
+    use crate::models::proveedor::CreateProveedor;
+    use crate::validation::{self, Validate};
+
+    fn campos(error: AppError) -> Vec<String> {
+        match error {
+            AppError::Validation(errores) => errores.into_iter().map(|e| e.field).collect(),
+            other => panic!("se esperaba Validation, se obtuvo {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_producto_valido_se_normaliza() {
+        let data = CreateProducto {
+            codigo_interno: "  P-001 ".to_string(),
+            descripcion: "Producto Test".to_string(),
+            categoria: Some("   ".to_string()),
+            subcategoria: None,
+            estado: Some("Activo".to_string()),
+        };
+
+        let data = data.validate().unwrap();
+        assert_eq!(data.codigo_interno, "P-001");
+        assert_eq!(data.categoria, None);
+    }
+
+    #[test]
+    fn test_devuelve_todos_los_errores() {
+        let data = CreateProducto {
+            codigo_interno: " ".to_string(),
+            descripcion: String::new(),
+            categoria: None,
+            subcategoria: None,
+            estado: Some("Borrado".to_string()),
+        };
+
+        let campos = campos(data.validate().unwrap_err());
+        assert_eq!(campos, vec!["codigo_interno", "descripcion", "estado"]);
+    }
+
+    #[test]
+    fn test_update_no_permite_vaciar_campos_obligatorios() {
+        let data = UpdateProducto {
+            codigo_interno: None,
+            descripcion: Some("  ".to_string()),
+            categoria: None,
+            subcategoria: None,
+            estado: None,
+        };
+
+        assert_eq!(campos(data.validate().unwrap_err()), vec!["descripcion"]);
+    }
+
+    #[test]
+    fn test_proveedor_email_y_telefono() {
+        let data = CreateProveedor {
+            ruc_ci: "0012505900001A".to_string(),
+            nombre: "Proveedor ABC".to_string(),
+            contacto: None,
+            telefono: Some("abc-123".to_string()),
+            email: Some("ventas@abc".to_string()),
+            estado: None,
+            pais: None,
+            dias_credito: None,
+        };
+
+        assert_eq!(campos(data.validate().unwrap_err()), vec!["telefono", "email"]);
+    }
+
+    #[test]
+    fn test_movimiento_cantidad_fechas_y_tipo() {
+        let data = CreateMovimiento {
+            fecha: "2025-02-30".to_string(),
+            tipo: "traslado".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad: -5.0,
+            fecha_venc: Some("2024-12-31".to_string()),
+            id_almacen: 1,
+            ..Default::default()
+        };
+
+        let campos = campos(data.validate().unwrap_err());
+        assert_eq!(campos, vec!["fecha", "tipo", "cantidad", "fecha_venc"]);
+    }
+
+    #[test]
+    fn test_movimiento_no_acepta_subtipos_de_documento() {
+        let data = CreateMovimiento {
+            fecha: "2025-02-01".to_string(),
+            tipo: "salida".to_string(),
+            subtipo: Some("Transferencia".to_string()),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad: 5.0,
+            id_almacen: 1,
+            ..Default::default()
+        };
+
+        assert_eq!(campos(data.validate().unwrap_err()), vec!["subtipo"]);
+    }
+
+    #[test]
+    fn test_movimiento_tipo_se_guarda_en_minusculas() {
+        let data = CreateMovimiento {
+            fecha: "2025-02-01".to_string(),
+            tipo: " Entrada ".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad: 5.0,
+            id_almacen: 1,
+            ..Default::default()
+        };
+
+        assert_eq!(data.validate().unwrap().tipo, "entrada");
+    }
+
+    #[test]
+    fn test_movimiento_no_recibe_documento_ni_linea_de_orden() {
+        let data: CreateMovimiento = serde_json::from_value(serde_json::json!({
+            "fecha": "2025-02-01",
+            "tipo": "entrada",
+            "id_prod_prov": 1,
+            "id_presentacion": 1,
+            "cantidad": 5.0,
+            "id_almacen": 1,
+            "documento": "TR-000001",
+            "id_linea_orden": 1
+        }))
+        .unwrap();
+
+        assert_eq!(data.documento, None);
+        assert_eq!(data.id_linea_orden, None);
+    }
+
+    #[test]
+    fn test_formatos() {
+        assert!(validation::is_date("2024-02-29"));
+        assert!(!validation::is_date("2023-02-29"));
+        assert!(!validation::is_date("15/01/2025"));
+        assert!(!validation::is_date("2024-+1-15"));
+        assert!(validation::is_email("compras@proveedor.com.ni"));
+        assert!(!validation::is_email("compras@@proveedor.com"));
+        assert!(validation::is_phone("+505 2222-3333"));
+        assert!(!validation::is_phone("123"));
+    }
+}
@@ -0,0 +1,27 @@
+use tauri::State;
+use sqlx::SqlitePool;
+use crate::models::transferencia::{CreateTransferencia, Transferencia, TransferenciaRegistrada};
+use crate::error::AppError;
+use crate::validation::Validate;
+use crate::services::transferencia_service;
+
+#[tauri::command]
+pub async fn create_transferencia(
+    pool: State<'_, SqlitePool>,
+    data: CreateTransferencia,
+) -> Result<TransferenciaRegistrada, AppError> {
+    transferencia_service::create(&pool, data.validate()?).await
+}
+
+#[tauri::command]
+pub async fn get_transferencia(pool: State<'_, SqlitePool>, documento: String) -> Result<Option<Transferencia>, AppError> {
+    transferencia_service::get_by_documento(&pool, &documento).await
+}
+
+/// Anula la transferencia revirtiendo la salida y la entrada de cada línea
+#[tauri::command]
+pub async fn delete_transferencia(pool: State<'_, SqlitePool>, documento: String) -> Result<bool, AppError> {
+    transferencia_service::delete(&pool, &documento)
+        .await
+        .map(|rows| rows > 0)
+}
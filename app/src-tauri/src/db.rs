@@ -1,102 +1,135 @@
-use sqlx::{SqlitePool, Result};
-
-pub async fn init_db() -> Result<SqlitePool> {
-    // Ruta de tu base de datos
-    let db_url = "sqlite:inventory.db";
-
-    let pool = SqlitePool::connect(db_url).await?;
-    
-    // Crear tablas si no existen
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS producto (
-            id_producto INTEGER PRIMARY KEY AUTOINCREMENT,
-            codigo_interno TEXT NOT NULL UNIQUE,
-            descripcion TEXT NOT NULL,
-            categoria TEXT,
-            subcategoria TEXT,
-            estado TEXT DEFAULT 'Activo'
-        );
-
-        CREATE TABLE IF NOT EXISTS proveedor (
-            id_proveedor INTEGER PRIMARY KEY AUTOINCREMENT,
-            ruc_ci TEXT NOT NULL UNIQUE,
-            nombre TEXT NOT NULL,
-            contacto TEXT,
-            telefono TEXT,
-            email TEXT,
-            estado TEXT DEFAULT 'Activo'
-        );
-
-        CREATE TABLE IF NOT EXISTS almacen (
-            id_almacen INTEGER PRIMARY KEY AUTOINCREMENT,
-            nombre TEXT NOT NULL,
-            ubicacion TEXT,
-            responsable TEXT
-        );
-
-        CREATE TABLE IF NOT EXISTS presentacion (
-            id_presentacion INTEGER PRIMARY KEY AUTOINCREMENT,
-            id_producto INTEGER NOT NULL,
-            unidad TEXT NOT NULL,
-            cantidad REAL NOT NULL,
-            descripcion TEXT,
-            FOREIGN KEY (id_producto) REFERENCES producto(id_producto)
-        );
-
-        CREATE TABLE IF NOT EXISTS producto_proveedor (
-            id_prod_prov INTEGER PRIMARY KEY AUTOINCREMENT,
-            id_producto INTEGER NOT NULL,
-            id_proveedor INTEGER NOT NULL,
-            codigo_proveedor TEXT NOT NULL,
-            estado TEXT DEFAULT 'Activo',
-            FOREIGN KEY (id_producto) REFERENCES producto(id_producto),
-            FOREIGN KEY (id_proveedor) REFERENCES proveedor(id_proveedor)
-        );
-
-        CREATE TABLE IF NOT EXISTS factura (
-            id_factura INTEGER PRIMARY KEY AUTOINCREMENT,
-            numero TEXT NOT NULL,
-            fecha TEXT NOT NULL,
-            id_proveedor INTEGER NOT NULL,
-            total REAL,
-            estado TEXT DEFAULT 'Activa',
-            FOREIGN KEY (id_proveedor) REFERENCES proveedor(id_proveedor)
-        );
-
-        CREATE TABLE IF NOT EXISTS movimiento (
-            id_movimiento INTEGER PRIMARY KEY AUTOINCREMENT,
-            fecha TEXT NOT NULL,
-            tipo TEXT NOT NULL,
-            subtipo TEXT,
-            id_prod_prov INTEGER NOT NULL,
-            id_presentacion INTEGER NOT NULL,
-            cantidad REAL NOT NULL,
-            precio_unit REAL,
-            monto_total REAL,
-            lote TEXT,
-            fecha_venc TEXT,
-            obs TEXT,
-            id_factura INTEGER,
-            FOREIGN KEY (id_prod_prov) REFERENCES producto_proveedor(id_prod_prov),
-            FOREIGN KEY (id_presentacion) REFERENCES presentacion(id_presentacion),
-            FOREIGN KEY (id_factura) REFERENCES factura(id_factura)
-        );
-
-        CREATE TABLE IF NOT EXISTS stock_almacen (
-            id_stock INTEGER PRIMARY KEY AUTOINCREMENT,
-            id_prod_prov INTEGER NOT NULL,
-            id_presentacion INTEGER NOT NULL,
-            id_almacen INTEGER NOT NULL,
-            stock_actual REAL NOT NULL DEFAULT 0,
-            FOREIGN KEY (id_prod_prov) REFERENCES producto_proveedor(id_prod_prov),
-            FOREIGN KEY (id_presentacion) REFERENCES presentacion(id_presentacion),
-            FOREIGN KEY (id_almacen) REFERENCES almacen(id_almacen)
-        );
-        "#
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+
+/// Migraciones numeradas de `migrations/`, embebidas al compilar. Las versiones
+/// aplicadas quedan registradas en la tabla `_sqlx_migrations`.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+// Ruta de tu base de datos
+const DB_PATH: &str = "inventory.db";
+
+#[derive(Debug)]
+pub enum DbError {
+    Database(sqlx::Error),
+    Migracion(MigrateError),
+    Respaldo(sqlx::Error),
+    VersionPosterior { version_bd: i64, version_app: i64 },
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Database(e) => write!(f, "{}", e),
+            Self::Migracion(e) => write!(f, "Error aplicando migraciones: {}", e),
+            Self::Respaldo(e) => write!(f, "No se pudo respaldar la base de datos: {}", e),
+            Self::VersionPosterior { version_bd, version_app } => write!(
+                f,
+                "La base de datos está en la versión {} y esta aplicación solo conoce hasta la {}; \
+                 actualice la aplicación",
+                version_bd, version_app
+            ),
+        }
+    }
+}
+
+impl From<sqlx::Error> for DbError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::Database(e)
+    }
+}
+
+impl From<MigrateError> for DbError {
+    fn from(e: MigrateError) -> Self {
+        Self::Migracion(e)
+    }
+}
+
+/// Las claves foráneas se activan en cada conexión del pool: SQLite las trae
+/// desactivadas por defecto y el esquema depende de ellas.
+pub async fn init_db() -> Result<SqlitePool, DbError> {
+    let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", DB_PATH))?
+        .create_if_missing(true)
+        .foreign_keys(true);
+    let pool = SqlitePool::connect_with(options).await?;
+
+    migrar(&pool, Some(Path::new(DB_PATH))).await?;
+
+    Ok(pool)
+}
+
+/// Aplica las migraciones pendientes. Si la base ya tenía tablas, antes se
+/// guarda una copia junto a `ruta_bd`. Se niega a continuar si la base fue
+/// migrada por una versión más nueva de la aplicación.
+pub async fn migrar(pool: &SqlitePool, ruta_bd: Option<&Path>) -> Result<(), DbError> {
+    let aplicadas = versiones_aplicadas(pool).await?;
+    let version_bd = aplicadas.iter().copied().max().unwrap_or(0);
+    let version_app = MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0);
+
+    if version_bd > version_app {
+        return Err(DbError::VersionPosterior { version_bd, version_app });
+    }
+
+    let pendientes = MIGRATOR.iter().any(|m| !aplicadas.contains(&m.version));
+    if pendientes && tiene_tablas(pool).await? {
+        if let Some(ruta) = ruta_bd {
+            respaldar(pool, ruta, version_bd).await?;
+        }
+    }
+
+    MIGRATOR.run(pool).await?;
+    Ok(())
+}
+
+async fn versiones_aplicadas(pool: &SqlitePool) -> Result<Vec<i64>, sqlx::Error> {
+    let existe: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')"
     )
-    .execute(&pool)
+    .fetch_one(pool)
     .await?;
 
-    Ok(pool)
+    if !existe {
+        return Ok(Vec::new());
+    }
+
+    sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success = 1")
+        .fetch_all(pool)
+        .await
+}
+
+async fn tiene_tablas(pool: &SqlitePool) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%')"
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// Copia consistente de la base en `<ruta>.v<version>-<timestamp>.bak`
+async fn respaldar(pool: &SqlitePool, ruta_bd: &Path, version: i64) -> Result<(), DbError> {
+    let segundos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let destino = format!("{}.v{}-{}.bak", ruta_bd.display(), version, segundos);
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(destino)
+        .execute(pool)
+        .await
+        .map_err(DbError::Respaldo)?;
+
+    Ok(())
+}
+
+/// Base en memoria con el esquema completo, para pruebas
+#[cfg(test)]
+pub async fn test_pool() -> SqlitePool {
+    let options = SqliteConnectOptions::from_str(":memory:").unwrap().foreign_keys(true);
+    let pool = SqlitePool::connect_with(options).await.unwrap();
+    MIGRATOR.run(&pool).await.unwrap();
+    pool
 }
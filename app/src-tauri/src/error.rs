@@ -0,0 +1,172 @@
+use std::fmt;
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use sqlx::error::ErrorKind;
+use crate::models::stock_almacen::StockInsuficiente;
+
+/// Error asociado a un campo del formulario
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Registros que impiden eliminar otro, agrupados por tabla
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Dependiente {
+    pub tabla: String,
+    pub cantidad: i64,
+}
+
+/// Errores de dominio que devuelven los servicios y los comandos Tauri.
+///
+/// Se serializan con una forma estable para el frontend:
+/// `{ code, message, field, details }`, con `message` en español.
+#[derive(Debug)]
+pub enum AppError {
+    NotFound { entidad: String, id: Option<i64> },
+    Duplicate { tabla: String, field: String },
+    ForeignKeyViolation { field: Option<String>, dependientes: Vec<Dependiente> },
+    Validation(Vec<FieldError>),
+    InsufficientStock(StockInsuficiente),
+    Conflict(String),
+    Database(String),
+}
+
+pub type Result<T, E = AppError> = std::result::Result<T, E>;
+
+impl AppError {
+    pub fn not_found(entidad: &str, id: impl Into<i64>) -> Self {
+        Self::NotFound {
+            entidad: entidad.to_string(),
+            id: Some(id.into()),
+        }
+    }
+
+    pub fn validation(field: &str, message: impl Into<String>) -> Self {
+        Self::Validation(vec![FieldError::new(field, message)])
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound { .. } => "NOT_FOUND",
+            Self::Duplicate { .. } => "DUPLICATE",
+            Self::ForeignKeyViolation { .. } => "FOREIGN_KEY_VIOLATION",
+            Self::Validation(_) => "VALIDATION",
+            Self::InsufficientStock(_) => "INSUFFICIENT_STOCK",
+            Self::Conflict(_) => "CONFLICT",
+            Self::Database(_) => "DATABASE",
+        }
+    }
+
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Self::Duplicate { field, .. } => Some(field),
+            Self::ForeignKeyViolation { field, .. } => field.as_deref(),
+            Self::Validation(errores) => errores.first().map(|e| e.field.as_str()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound { entidad, id: Some(id) } => write!(f, "No se encontró {} con id {}", entidad, id),
+            Self::NotFound { entidad, id: None } => write!(f, "No se encontró {}", entidad),
+            Self::Duplicate { tabla, field } => {
+                write!(f, "Ya existe un registro de {} con el mismo valor en '{}'", tabla, field)
+            }
+            Self::ForeignKeyViolation { dependientes, .. } if !dependientes.is_empty() => {
+                let detalle: Vec<String> = dependientes
+                    .iter()
+                    .map(|d| format!("{} en {}", d.cantidad, d.tabla))
+                    .collect();
+                write!(
+                    f,
+                    "No se puede eliminar: tiene registros dependientes ({})",
+                    detalle.join(", ")
+                )
+            }
+            Self::ForeignKeyViolation { .. } => write!(
+                f,
+                "La operación hace referencia a un registro que no existe o que tiene registros dependientes"
+            ),
+            Self::Validation(errores) => match errores.as_slice() {
+                [unico] => write!(f, "{}", unico.message),
+                _ => write!(f, "Hay {} campos con datos inválidos", errores.len()),
+            },
+            Self::InsufficientStock(d) => write!(
+                f,
+                "Stock insuficiente: disponible {}, solicitado {} (faltan {})",
+                d.disponible, d.solicitado, d.faltante
+            ),
+            Self::Conflict(mensaje) => write!(f, "{}", mensaje),
+            Self::Database(mensaje) => write!(f, "Error de base de datos: {}", mensaje),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 4)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("field", &self.field())?;
+        match self {
+            Self::NotFound { entidad, id } => {
+                state.serialize_field("details", &serde_json::json!({ "entidad": entidad, "id": id }))?
+            }
+            Self::Duplicate { tabla, .. } => {
+                state.serialize_field("details", &serde_json::json!({ "tabla": tabla }))?
+            }
+            Self::ForeignKeyViolation { dependientes, .. } if !dependientes.is_empty() => {
+                state.serialize_field("details", dependientes)?
+            }
+            Self::Validation(errores) => state.serialize_field("details", errores)?,
+            Self::InsufficientStock(detalle) => state.serialize_field("details", detalle)?,
+            _ => state.serialize_field("details", &None::<()>)?,
+        }
+        state.end()
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::RowNotFound => Self::NotFound {
+                entidad: "el registro".to_string(),
+                id: None,
+            },
+            sqlx::Error::Database(db) => {
+                // SQLite informa la restricción como "UNIQUE constraint failed: tabla.columna"
+                let columna = db.message().split(": ").nth(1).and_then(|c| c.split(", ").next());
+                let (tabla, campo) = match columna.and_then(|c| c.split_once('.')) {
+                    Some((tabla, campo)) => (tabla.to_string(), campo.to_string()),
+                    None => (String::new(), String::new()),
+                };
+                match db.kind() {
+                    ErrorKind::UniqueViolation => Self::Duplicate { tabla, field: campo },
+                    ErrorKind::ForeignKeyViolation => Self::ForeignKeyViolation {
+                        field: None,
+                        dependientes: Vec::new(),
+                    },
+                    ErrorKind::NotNullViolation => Self::validation(&campo, format!("El campo '{}' es obligatorio", campo)),
+                    _ => Self::Database(db.message().to_string()),
+                }
+            }
+            _ => Self::Database(e.to_string()),
+        }
+    }
+}
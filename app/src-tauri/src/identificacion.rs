@@ -0,0 +1,202 @@
+//! Validación de identificadores fiscales (RUC, cédula, NIT, DUI...) por país.
+//!
+//! Para admitir un país nuevo basta con implementar `ValidadorIdentificacion`
+//! y agregarlo a `VALIDADORES`. Los países sin validador solo se normalizan.
+
+/// País que se asume cuando un proveedor no indica el suyo
+pub const PAIS_PREDETERMINADO: &str = "NI";
+
+pub trait ValidadorIdentificacion: Sync {
+    /// Código ISO 3166-1 alfa-2 del país
+    fn pais(&self) -> &'static str;
+
+    /// Verifica longitud y dígito verificador de un identificador ya normalizado
+    fn verificar(&self, id: &str) -> Result<(), String>;
+}
+
+static VALIDADORES: &[&dyn ValidadorIdentificacion] = &[
+    &Nicaragua,
+    &Guatemala,
+    &ElSalvador,
+    &Honduras,
+    &CostaRica,
+];
+
+/// Quita guiones y espacios y pasa a mayúsculas
+pub fn normalizar(id: &str) -> String {
+    id.chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+pub fn validador(pais: &str) -> Option<&'static dyn ValidadorIdentificacion> {
+    VALIDADORES
+        .iter()
+        .copied()
+        .find(|v| v.pais().eq_ignore_ascii_case(pais))
+}
+
+/// Normaliza y verifica el identificador según el país. Devuelve la forma
+/// normalizada, que es la que se guarda.
+pub fn validar(pais: &str, id: &str) -> Result<String, String> {
+    let normalizado = normalizar(id);
+    if normalizado.is_empty() {
+        return Err("Campo obligatorio".to_string());
+    }
+    if !normalizado.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("Solo se admiten letras, dígitos, guiones y espacios".to_string());
+    }
+    if let Some(validador) = validador(pais) {
+        validador.verificar(&normalizado)?;
+    }
+
+    Ok(normalizado)
+}
+
+fn solo_digitos(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
+}
+
+fn digitos(s: &str) -> Vec<u32> {
+    s.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+/// Cédula de identidad (3 dígitos de municipio, fecha de nacimiento DDMMAA,
+/// 4 dígitos de consecutivo y letra verificadora) o RUC de persona jurídica
+/// (J seguida de 13 dígitos).
+struct Nicaragua;
+
+impl Nicaragua {
+    const LETRAS: &'static [u8] = b"ABCDEFGHJKLMNPQRSTUVWXY";
+}
+
+impl ValidadorIdentificacion for Nicaragua {
+    fn pais(&self) -> &'static str {
+        "NI"
+    }
+
+    fn verificar(&self, id: &str) -> Result<(), String> {
+        if let Some(resto) = id.strip_prefix('J') {
+            if resto.len() == 13 && solo_digitos(resto) {
+                return Ok(());
+            }
+            return Err("El RUC de persona jurídica debe ser J seguida de 13 dígitos".to_string());
+        }
+
+        let (numero, letra) = id.split_at(id.len().saturating_sub(1));
+        if id.len() != 14 || !solo_digitos(numero) {
+            return Err("La cédula debe tener 13 dígitos y una letra (000-000000-0000A)".to_string());
+        }
+        let valor: u64 = numero.parse().map_err(|_| "Cédula inválida".to_string())?;
+        let esperada = Self::LETRAS[(valor % 23) as usize] as char;
+        if !letra.starts_with(esperada) {
+            return Err("La letra verificadora de la cédula no corresponde".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// NIT: dígitos seguidos de un verificador módulo 11, donde 10 se escribe K.
+struct Guatemala;
+
+impl ValidadorIdentificacion for Guatemala {
+    fn pais(&self) -> &'static str {
+        "GT"
+    }
+
+    fn verificar(&self, id: &str) -> Result<(), String> {
+        let (cuerpo, verificador) = id.split_at(id.len().saturating_sub(1));
+        if !(2..=13).contains(&id.len()) || !solo_digitos(cuerpo) {
+            return Err("El NIT debe tener entre 2 y 13 caracteres numéricos".to_string());
+        }
+
+        let suma: u32 = digitos(cuerpo)
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, d)| d * (i as u32 + 2))
+            .sum();
+        let esperado = match (11 - suma % 11) % 11 {
+            10 => 'K',
+            d => char::from_digit(d, 10).unwrap_or('0'),
+        };
+        if !verificador.starts_with(esperado) {
+            return Err("El dígito verificador del NIT no corresponde".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// DUI de 9 dígitos con verificador (pesos 9 a 2, módulo 10), o NIT de 14 dígitos.
+struct ElSalvador;
+
+impl ValidadorIdentificacion for ElSalvador {
+    fn pais(&self) -> &'static str {
+        "SV"
+    }
+
+    fn verificar(&self, id: &str) -> Result<(), String> {
+        if !solo_digitos(id) || (id.len() != 9 && id.len() != 14) {
+            return Err("Se espera un DUI de 9 dígitos o un NIT de 14 dígitos".to_string());
+        }
+        if id.len() == 14 {
+            return Ok(());
+        }
+
+        let d = digitos(id);
+        let suma: u32 = d[..8].iter().zip((2..=9).rev()).map(|(d, peso)| d * peso).sum();
+        if (10 - suma % 10) % 10 != d[8] {
+            return Err("El dígito verificador del DUI no corresponde".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// DNI de 13 dígitos o RTN de 14 dígitos.
+struct Honduras;
+
+impl ValidadorIdentificacion for Honduras {
+    fn pais(&self) -> &'static str {
+        "HN"
+    }
+
+    fn verificar(&self, id: &str) -> Result<(), String> {
+        if !solo_digitos(id) || (id.len() != 13 && id.len() != 14) {
+            return Err("Se espera un DNI de 13 dígitos o un RTN de 14 dígitos".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Cédula física de 9 dígitos, cédula jurídica de 10 dígitos que empieza con 3,
+/// o DIMEX de 11 o 12 dígitos.
+struct CostaRica;
+
+impl ValidadorIdentificacion for CostaRica {
+    fn pais(&self) -> &'static str {
+        "CR"
+    }
+
+    fn verificar(&self, id: &str) -> Result<(), String> {
+        let valido = solo_digitos(id)
+            && match id.len() {
+                9 => !id.starts_with('0'),
+                10 => id.starts_with('3'),
+                11 | 12 => true,
+                _ => false,
+            };
+        if !valido {
+            return Err(
+                "Se espera una cédula física (9 dígitos), jurídica (10 dígitos, inicia con 3) o DIMEX (11 o 12 dígitos)"
+                    .to_string(),
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -2,13 +2,18 @@ use tauri::State;
 use sqlx::SqlitePool;
 
 mod db;
+mod error;
+mod validation;
+mod identificacion;
 mod models;
 mod services;
 mod commands;
 
 #[tokio::main]
 async fn main() {
-    let pool = db::init_db().await.expect("Error al conectar con la base de datos");
+    let pool = db::init_db()
+        .await
+        .unwrap_or_else(|e| panic!("Error al inicializar la base de datos: {}", e));
 
     tauri::Builder::default()
         .manage(pool)
@@ -16,6 +21,9 @@ async fn main() {
             // Almacen commands
             commands::almacen_commands::get_almacenes,
             commands::almacen_commands::create_almacen,
+            commands::almacen_commands::delete_almacen,
+            commands::almacen_commands::restore_almacen,
+            commands::almacen_commands::purge_almacen,
             
             // Producto commands
             commands::producto_commands::create_product,
@@ -23,6 +31,8 @@ async fn main() {
             commands::producto_commands::get_product_by_id,
             commands::producto_commands::update_product,
             commands::producto_commands::delete_product,
+            commands::producto_commands::restore_product,
+            commands::producto_commands::purge_product,
             
             // Proveedor commands
             commands::proveedor_commands::create_proveedor,
@@ -30,6 +40,8 @@ async fn main() {
             commands::proveedor_commands::get_proveedor_by_id,
             commands::proveedor_commands::update_proveedor,
             commands::proveedor_commands::delete_proveedor,
+            commands::proveedor_commands::restore_proveedor,
+            commands::proveedor_commands::purge_proveedor,
             
             // Presentacion commands
             commands::presentacion_commands::create_presentacion,
@@ -55,6 +67,28 @@ async fn main() {
             commands::movimiento_commands::update_movimiento,
             commands::movimiento_commands::delete_movimiento,
             
+            // Transferencia commands
+            commands::transferencia_commands::create_transferencia,
+            commands::transferencia_commands::get_transferencia,
+            commands::transferencia_commands::delete_transferencia,
+            
+            // Conversion commands
+            commands::conversion_commands::get_cantidad_base,
+            commands::conversion_commands::convertir_cantidad,
+            commands::conversion_commands::get_stock_base,
+            commands::conversion_commands::create_conversion,
+            commands::conversion_commands::delete_conversion,
+            
+            // Conteo commands
+            commands::conteo_commands::get_conteos,
+            commands::conteo_commands::get_conteo_by_id,
+            commands::conteo_commands::get_conteos_by_almacen,
+            commands::conteo_commands::get_conteo_detalle,
+            commands::conteo_commands::create_conteo,
+            commands::conteo_commands::registrar_conteo,
+            commands::conteo_commands::aprobar_conteo,
+            commands::conteo_commands::cancelar_conteo,
+            
             // Stock Almacen commands
             commands::stock_commands::create_stock_almacen,
             commands::stock_commands::get_stock_almacen,
@@ -63,15 +97,108 @@ async fn main() {
             commands::stock_commands::update_stock_almacen,
             commands::stock_commands::delete_stock_almacen,
             commands::stock_commands::get_stock_actual_all,
+            commands::stock_commands::get_valuacion_stock,
             commands::stock_commands::get_low_stock_products,
             
+            // Stock Parametro commands
+            commands::stock_parametro_commands::get_stock_parametros,
+            commands::stock_parametro_commands::set_stock_parametro,
+            commands::stock_parametro_commands::get_niveles_stock,
+            commands::stock_parametro_commands::get_stock_bajo,
+            commands::stock_parametro_commands::get_sugerencias_reorden,
+            
+            // Orden Compra commands
+            commands::orden_compra_commands::get_ordenes_compra,
+            commands::orden_compra_commands::get_orden_compra_by_id,
+            commands::orden_compra_commands::get_ordenes_compra_by_proveedor,
+            commands::orden_compra_commands::get_orden_compra_lineas,
+            commands::orden_compra_commands::create_orden_compra,
+            commands::orden_compra_commands::update_orden_compra,
+            commands::orden_compra_commands::delete_orden_compra,
+            commands::orden_compra_commands::enviar_orden_compra,
+            commands::orden_compra_commands::recibir_orden_compra,
+            commands::orden_compra_commands::cerrar_orden_compra,
+            
+            // Pago commands
+            commands::pago_commands::get_pagos,
+            commands::pago_commands::get_pago_by_id,
+            commands::pago_commands::get_pagos_by_proveedor,
+            commands::pago_commands::get_pago_aplicaciones,
+            commands::pago_commands::get_pago_aplicaciones_by_factura,
+            commands::pago_commands::create_pago,
+            commands::pago_commands::aplicar_pago,
+            commands::pago_commands::delete_pago,
+            commands::pago_commands::get_saldos_factura,
+            commands::pago_commands::get_saldos_proveedor,
+            commands::pago_commands::get_estado_cuenta_proveedor,
+            commands::pago_commands::get_antiguedad_saldos,
+            
+            // Devolucion commands
+            commands::devolucion_commands::get_notas_credito,
+            commands::devolucion_commands::get_nota_credito_by_id,
+            commands::devolucion_commands::get_notas_credito_by_factura,
+            commands::devolucion_commands::get_notas_credito_by_proveedor,
+            commands::devolucion_commands::get_devolucion,
+            commands::devolucion_commands::create_devolucion,
+            commands::devolucion_commands::delete_devolucion,
+            
+            // Kardex commands
+            commands::kardex_commands::get_kardex,
+            
+            // Stock Historico commands
+            commands::stock_historico_commands::get_stock_a_fecha,
+            commands::stock_historico_commands::get_cortes_stock,
+            commands::stock_historico_commands::crear_corte_stock,
+            commands::stock_historico_commands::delete_corte_stock,
+            
+            // Integridad commands
+            commands::integridad_commands::verificar_integridad_stock,
+            commands::integridad_commands::reparar_integridad_stock,
+            
+            // Periodo commands
+            commands::periodo_commands::get_periodos,
+            commands::periodo_commands::get_cierre_periodo,
+            commands::periodo_commands::cerrar_periodo,
+            commands::periodo_commands::reabrir_periodo,
+            
+            // Lote commands
+            commands::lote_commands::get_lotes,
+            commands::lote_commands::get_lote_by_id,
+            commands::lote_commands::get_lotes_by_producto_proveedor,
+            commands::lote_commands::get_lotes_disponibles,
+            commands::lote_commands::get_consumos_by_movimiento,
+            commands::lote_commands::get_lotes_por_vencer,
+            
+            // Configuracion commands
+            commands::configuracion_commands::get_politica_stock,
+            commands::configuracion_commands::set_politica_stock,
+            commands::configuracion_commands::set_politica_stock_almacen,
+            commands::configuracion_commands::get_estrategia_salida,
+            commands::configuracion_commands::set_estrategia_salida,
+            commands::configuracion_commands::set_estrategia_salida_almacen,
+            
+            // Sesion commands
+            commands::sesion_commands::get_usuario_actual,
+            commands::sesion_commands::set_usuario_actual,
+            
+            // Auditoria commands
+            commands::auditoria_commands::get_auditoria,
+            commands::auditoria_commands::get_auditoria_by_entidad,
+            commands::auditoria_commands::get_auditoria_by_registro,
+            commands::auditoria_commands::get_auditoria_by_fecha,
+            
             // Factura commands
             commands::factura_commands::create_factura,
+            commands::factura_commands::create_factura_con_lineas,
             commands::factura_commands::get_facturas,
             commands::factura_commands::get_factura_by_id,
             commands::factura_commands::get_facturas_by_proveedor,
             commands::factura_commands::update_factura,
+            commands::factura_commands::activar_factura,
+            commands::factura_commands::anular_factura,
             commands::factura_commands::delete_factura,
+            commands::factura_commands::get_factura_totales,
+            commands::factura_commands::get_facturas_descuadradas,
         ])
         .run(tauri::generate_context!())
         .expect("Error ejecutando la app Tauri");
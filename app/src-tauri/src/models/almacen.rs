@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use crate::validation::{Validate, Validator, empty_as_none, trim, trim_opt};
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 pub struct Almacen {
@@ -7,6 +8,9 @@ pub struct Almacen {
     pub nombre: String,
     pub ubicacion: Option<String>,
     pub responsable: Option<String>,
+    /// Fecha en que se archivó; los archivados no se listan por defecto
+    pub eliminado_en: Option<String>,
+    pub eliminado_por: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -22,3 +26,29 @@ pub struct UpdateAlmacen {
     pub ubicacion: Option<String>,
     pub responsable: Option<String>,
 }
+
+impl Validate for CreateAlmacen {
+    fn normalize(&mut self) {
+        trim(&mut self.nombre);
+        empty_as_none(&mut self.ubicacion);
+        empty_as_none(&mut self.responsable);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.required("nombre", &self.nombre);
+        v.max_len("nombre", Some(&self.nombre), 100);
+    }
+}
+
+impl Validate for UpdateAlmacen {
+    fn normalize(&mut self) {
+        trim_opt(&mut self.nombre);
+        trim_opt(&mut self.ubicacion);
+        trim_opt(&mut self.responsable);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.not_blank("nombre", self.nombre.as_deref());
+        v.max_len("nombre", self.nombre.as_deref(), 100);
+    }
+}
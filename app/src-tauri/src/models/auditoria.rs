@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::validation::{self, Validate, Validator};
+
+/// Cambio registrado por los disparadores de auditoría. `antes` y `despues`
+/// son la fila completa en JSON; `antes` es nulo en un INSERT y `despues` en
+/// un DELETE.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Auditoria {
+    pub id_auditoria: i32,
+    pub entidad: String,
+    pub id_registro: i64,
+    pub operacion: String,
+    pub antes: Option<String>,
+    pub despues: Option<String>,
+    pub fecha: String,
+    pub usuario: Option<String>,
+}
+
+/// Filtros de búsqueda; los que se omiten no restringen. Las fechas son
+/// `AAAA-MM-DD` e incluyen ambos extremos.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FiltroAuditoria {
+    pub entidad: Option<String>,
+    pub id_registro: Option<i64>,
+    pub desde: Option<String>,
+    pub hasta: Option<String>,
+}
+
+impl Validate for FiltroAuditoria {
+    fn normalize(&mut self) {
+        validation::empty_as_none(&mut self.entidad);
+        validation::empty_as_none(&mut self.desde);
+        validation::empty_as_none(&mut self.hasta);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.date("desde", self.desde.as_deref());
+        v.date("hasta", self.hasta.as_deref());
+        if let (Some(desde), Some(hasta)) = (&self.desde, &self.hasta) {
+            if hasta < desde {
+                v.add("hasta", "No puede ser anterior a la fecha inicial");
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+/// Qué hacer cuando una salida deja el stock de una ubicación en negativo
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PoliticaStock {
+    #[default]
+    Rechazar,
+    Advertir,
+    Permitir,
+}
+
+impl PoliticaStock {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rechazar => "rechazar",
+            Self::Advertir => "advertir",
+            Self::Permitir => "permitir",
+        }
+    }
+
+    pub fn parse(valor: &str) -> Option<Self> {
+        match valor.trim().to_lowercase().as_str() {
+            "rechazar" => Some(Self::Rechazar),
+            "advertir" => Some(Self::Advertir),
+            "permitir" => Some(Self::Permitir),
+            _ => None,
+        }
+    }
+}
+
+/// Orden en que las salidas toman los lotes de una ubicación
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EstrategiaSalida {
+    /// Primero los lotes que entraron antes
+    #[default]
+    Peps,
+    /// Primero los lotes que vencen antes; los que no tienen vencimiento van
+    /// al final, en orden PEPS
+    Fefo,
+}
+
+impl EstrategiaSalida {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Peps => "peps",
+            Self::Fefo => "fefo",
+        }
+    }
+
+    pub fn parse(valor: &str) -> Option<Self> {
+        match valor.trim().to_lowercase().as_str() {
+            "peps" => Some(Self::Peps),
+            "fefo" => Some(Self::Fefo),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::validation::{Validate, Validator, empty_as_none, trim};
+
+/// Conteo creado con el stock esperado ya registrado
+pub const CONTEO_ABIERTO: &str = "Abierto";
+/// Conteo con al menos una cantidad contada
+pub const CONTEO_EN_CONTEO: &str = "En conteo";
+pub const CONTEO_APROBADO: &str = "Aprobado";
+pub const CONTEO_CANCELADO: &str = "Cancelado";
+
+/// Estados en los que el conteo bloquea los movimientos del almacén
+pub const ESTADOS_CONTEO_EN_CURSO: &[&str] = &[CONTEO_ABIERTO, CONTEO_EN_CONTEO];
+
+/// Subtipos de los ajustes que registra la aprobación de un conteo
+pub const SUBTIPO_SOBRANTE: &str = "sobrante";
+pub const SUBTIPO_FALTANTE: &str = "faltante";
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Conteo {
+    pub id_conteo: i32,
+    pub id_almacen: i32,
+    pub fecha: String,
+    pub estado: String,
+    pub obs: Option<String>,
+}
+
+/// Línea de un conteo. `diferencia` (contada − esperada) e `impacto`
+/// (diferencia × costo unitario) son nulos mientras no se cuente.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ConteoDetalle {
+    pub id_detalle: i32,
+    pub id_conteo: i32,
+    pub id_prod_prov: i32,
+    pub id_presentacion: i32,
+    pub cantidad_esperada: f64,
+    pub cantidad_contada: Option<f64>,
+    pub costo_unitario: f64,
+    pub diferencia: Option<f64>,
+    pub impacto: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateConteo {
+    pub id_almacen: i32,
+    pub fecha: String,
+    pub obs: Option<String>,
+}
+
+/// Cantidad contada de una ubicación del almacén
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CantidadContada {
+    pub id_prod_prov: i32,
+    pub id_presentacion: i32,
+    pub cantidad_contada: f64,
+}
+
+impl Validate for CreateConteo {
+    fn normalize(&mut self) {
+        trim(&mut self.fecha);
+        empty_as_none(&mut self.obs);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.id("id_almacen", Some(self.id_almacen));
+        v.required("fecha", &self.fecha);
+        v.date("fecha", Some(&self.fecha));
+    }
+}
+
+impl Validate for Vec<CantidadContada> {
+    fn rules(&self, v: &mut Validator) {
+        if self.is_empty() {
+            v.add("lineas", "Debe incluir al menos un producto");
+        }
+        for (i, linea) in self.iter().enumerate() {
+            v.id(&format!("lineas[{}].id_prod_prov", i), Some(linea.id_prod_prov));
+            v.id(&format!("lineas[{}].id_presentacion", i), Some(linea.id_presentacion));
+            v.non_negative(&format!("lineas[{}].cantidad_contada", i), Some(linea.cantidad_contada));
+        }
+    }
+}
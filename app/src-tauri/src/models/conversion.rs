@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::models::stock_almacen::StockInsuficiente;
+use crate::validation::{Validate, Validator, empty_as_none, trim};
+
+/// Subtipo de la salida y la entrada que registra una conversión
+pub const SUBTIPO_CONVERSION: &str = "conversion";
+
+/// Convierte stock de una presentación a otra del mismo producto en un
+/// almacén, por ejemplo al abrir una caja para vender por unidad.
+/// `cantidad` está en unidades de la presentación de origen.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateConversion {
+    pub fecha: String,
+    pub id_prod_prov: i32,
+    pub id_almacen: i32,
+    pub id_presentacion_origen: i32,
+    pub id_presentacion_destino: i32,
+    pub cantidad: f64,
+    pub obs: Option<String>,
+}
+
+/// Resultado de una conversión. `cantidad_destino` es lo que ingresó en la
+/// presentación de destino.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConversionRegistrada {
+    pub documento: String,
+    pub cantidad_destino: f64,
+    pub advertencia: Option<StockInsuficiente>,
+}
+
+/// Stock de un producto sumando todas sus presentaciones en unidad base.
+/// `unidad_base` es la presentación de factor 1, si el producto la tiene.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct StockProductoBase {
+    pub id_producto: i32,
+    pub codigo_interno: String,
+    pub descripcion: String,
+    pub unidad_base: Option<String>,
+    pub stock_base: f64,
+}
+
+impl Validate for CreateConversion {
+    fn normalize(&mut self) {
+        trim(&mut self.fecha);
+        empty_as_none(&mut self.obs);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.required("fecha", &self.fecha);
+        v.date("fecha", Some(&self.fecha));
+        v.id("id_prod_prov", Some(self.id_prod_prov));
+        v.id("id_almacen", Some(self.id_almacen));
+        v.id("id_presentacion_origen", Some(self.id_presentacion_origen));
+        v.id("id_presentacion_destino", Some(self.id_presentacion_destino));
+        if self.id_presentacion_origen == self.id_presentacion_destino {
+            v.add("id_presentacion_destino", "Debe ser distinta de la presentación de origen");
+        }
+        v.positive("cantidad", Some(self.cantidad));
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::models::movimiento::Movimiento;
+use crate::validation::{Validate, Validator, empty_as_none, trim};
+
+/// Subtipo de las salidas que devuelven mercadería al proveedor
+pub const SUBTIPO_DEVOLUCION: &str = "devolucion";
+
+/// Crédito del proveedor por mercadería devuelta de una de sus facturas. Su
+/// número es el documento de las salidas de la devolución.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct NotaCredito {
+    pub id_nota: i32,
+    pub numero: String,
+    pub fecha: String,
+    pub id_proveedor: i32,
+    pub id_factura: i32,
+    pub monto: f64,
+    pub motivo: Option<String>,
+    pub obs: Option<String>,
+}
+
+/// Cantidad que se devuelve de un lote recibido con la factura
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LineaDevolucion {
+    pub id_lote: i32,
+    pub cantidad: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateDevolucion {
+    pub fecha: String,
+    pub id_factura: i32,
+    pub motivo: Option<String>,
+    pub obs: Option<String>,
+    pub lineas: Vec<LineaDevolucion>,
+}
+
+/// Nota de crédito con las salidas que la originaron
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Devolucion {
+    pub nota: NotaCredito,
+    pub movimientos: Vec<Movimiento>,
+}
+
+impl Validate for CreateDevolucion {
+    fn normalize(&mut self) {
+        trim(&mut self.fecha);
+        empty_as_none(&mut self.motivo);
+        empty_as_none(&mut self.obs);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.required("fecha", &self.fecha);
+        v.date("fecha", Some(&self.fecha));
+        v.id("id_factura", Some(self.id_factura));
+        v.max_len("motivo", self.motivo.as_deref(), 200);
+        if self.lineas.is_empty() {
+            v.add("lineas", "Debe incluir al menos un lote");
+        }
+        for (i, linea) in self.lineas.iter().enumerate() {
+            v.id(&format!("lineas[{}].id_lote", i), Some(linea.id_lote));
+            v.positive(&format!("lineas[{}].cantidad", i), Some(linea.cantidad));
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use crate::validation::{Validate, Validator, empty_as_none, trim, trim_opt};
 
+/// `total` es el impreso por el proveedor; el calculado con las entradas de
+/// la factura está en `FacturaTotales`. `fecha_vencimiento` se calcula con
+/// los días de crédito del proveedor.
 #[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
 pub struct Factura {
     pub id_factura: i32,
@@ -9,6 +13,9 @@ pub struct Factura {
     pub id_proveedor: i32,
     pub total: Option<f64>,
     pub estado: Option<String>,
+    pub descuento: f64,
+    pub porcentaje_impuesto: f64,
+    pub fecha_vencimiento: Option<String>,
 }
 
 /// Datos para crear una factura
@@ -19,9 +26,12 @@ pub struct FacturaInput {
     pub id_proveedor: i32,
     pub total: Option<f64>,
     pub estado: Option<String>,
+    pub descuento: Option<f64>,
+    pub porcentaje_impuesto: Option<f64>,
 }
 
-/// Datos para actualizar una factura
+/// Datos para actualizar una factura. El estado solo avanza de borrador a
+/// activa y de activa a pagada; la anulación tiene su propia operación.
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct FacturaUpdate {
     pub numero: Option<String>,
@@ -29,4 +39,161 @@ pub struct FacturaUpdate {
     pub id_proveedor: Option<i32>,
     pub total: Option<f64>,
     pub estado: Option<String>,
+    pub descuento: Option<f64>,
+    pub porcentaje_impuesto: Option<f64>,
+}
+
+/// Producto que llega con la factura
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LineaFactura {
+    pub id_prod_prov: i32,
+    pub id_presentacion: i32,
+    pub cantidad: f64,
+    pub precio_unit: f64,
+    pub lote: Option<String>,
+    pub fecha_venc: Option<String>,
+    pub obs: Option<String>,
+}
+
+/// Factura con sus entradas, que se registran en `id_almacen`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateFacturaConLineas {
+    pub factura: FacturaInput,
+    pub id_almacen: i32,
+    pub lineas: Vec<LineaFactura>,
+}
+
+/// Importes calculados con las entradas de la factura. El impuesto se aplica
+/// sobre el subtotal menos el descuento. `diferencia` es el total impreso
+/// menos el calculado; `lineas_sin_importe` cuenta las entradas sin precio ni
+/// monto, que no suman al subtotal.
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
+pub struct FacturaTotales {
+    pub id_factura: i32,
+    pub numero: String,
+    pub subtotal: f64,
+    pub descuento: f64,
+    pub impuesto: f64,
+    pub total_calculado: f64,
+    pub total_impreso: Option<f64>,
+    pub diferencia: Option<f64>,
+    pub descuadrada: bool,
+    pub lineas_sin_importe: i64,
+}
+
+/// Resultado de registrar una factura con sus entradas
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FacturaRegistrada {
+    pub id_factura: i64,
+    pub movimientos: Vec<i64>,
+    pub totales: FacturaTotales,
+}
+
+/// Factura en preparación
+pub const FACTURA_BORRADOR: &str = "Borrador";
+/// Factura registrada, pendiente de pago
+pub const FACTURA_ACTIVA: &str = "Activa";
+pub const FACTURA_PAGADA: &str = "Pagada";
+/// Factura anulada; sus entradas quedaron revertidas
+pub const FACTURA_ANULADA: &str = "Anulada";
+
+/// Valores permitidos en `factura.estado`
+pub const ESTADOS_FACTURA: &[&str] = &[FACTURA_BORRADOR, FACTURA_ACTIVA, FACTURA_PAGADA, FACTURA_ANULADA];
+
+/// Estados con los que se puede crear una factura
+pub const ESTADOS_FACTURA_INICIALES: &[&str] = &[FACTURA_BORRADOR, FACTURA_ACTIVA];
+
+/// Subtipo de las salidas que revierten las entradas de una factura anulada
+pub const SUBTIPO_ANULACION: &str = "anulacion";
+
+/// Datos de la anulación de una factura; `fecha` es la de las salidas que
+/// revierten sus entradas
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnularFactura {
+    pub fecha: String,
+    pub obs: Option<String>,
+}
+
+impl Validate for FacturaInput {
+    fn normalize(&mut self) {
+        trim(&mut self.numero);
+        trim(&mut self.fecha);
+        empty_as_none(&mut self.estado);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.required("numero", &self.numero);
+        v.max_len("numero", Some(&self.numero), 50);
+        v.required("fecha", &self.fecha);
+        v.date("fecha", Some(&self.fecha));
+        v.id("id_proveedor", Some(self.id_proveedor));
+        v.non_negative("total", self.total);
+        v.one_of("estado", self.estado.as_deref(), ESTADOS_FACTURA_INICIALES);
+        validar_importes(v, self.descuento, self.porcentaje_impuesto);
+    }
+}
+
+impl Validate for FacturaUpdate {
+    fn normalize(&mut self) {
+        trim_opt(&mut self.numero);
+        trim_opt(&mut self.fecha);
+        trim_opt(&mut self.estado);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.not_blank("numero", self.numero.as_deref());
+        v.max_len("numero", self.numero.as_deref(), 50);
+        v.date("fecha", self.fecha.as_deref());
+        v.id("id_proveedor", self.id_proveedor);
+        v.non_negative("total", self.total);
+        v.one_of("estado", self.estado.as_deref(), ESTADOS_FACTURA);
+        validar_importes(v, self.descuento, self.porcentaje_impuesto);
+    }
+}
+
+impl Validate for CreateFacturaConLineas {
+    fn normalize(&mut self) {
+        self.factura.normalize();
+        for linea in &mut self.lineas {
+            empty_as_none(&mut linea.lote);
+            empty_as_none(&mut linea.fecha_venc);
+            empty_as_none(&mut linea.obs);
+        }
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        self.factura.rules(v);
+        v.id("id_almacen", Some(self.id_almacen));
+        if self.lineas.is_empty() {
+            v.add("lineas", "Debe incluir al menos un producto");
+        }
+        for (i, linea) in self.lineas.iter().enumerate() {
+            v.id(&format!("lineas[{}].id_prod_prov", i), Some(linea.id_prod_prov));
+            v.id(&format!("lineas[{}].id_presentacion", i), Some(linea.id_presentacion));
+            v.positive(&format!("lineas[{}].cantidad", i), Some(linea.cantidad));
+            v.non_negative(&format!("lineas[{}].precio_unit", i), Some(linea.precio_unit));
+            v.max_len(&format!("lineas[{}].lote", i), linea.lote.as_deref(), 50);
+            v.date(&format!("lineas[{}].fecha_venc", i), linea.fecha_venc.as_deref());
+        }
+    }
+}
+
+impl Validate for AnularFactura {
+    fn normalize(&mut self) {
+        trim(&mut self.fecha);
+        empty_as_none(&mut self.obs);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.required("fecha", &self.fecha);
+        v.date("fecha", Some(&self.fecha));
+    }
+}
+
+fn validar_importes(v: &mut Validator, descuento: Option<f64>, porcentaje_impuesto: Option<f64>) {
+    v.non_negative("descuento", descuento);
+    v.non_negative("porcentaje_impuesto", porcentaje_impuesto);
+    if porcentaje_impuesto.is_some_and(|p| p > 100.0) {
+        v.add("porcentaje_impuesto", "No puede ser mayor que 100");
+    }
 }
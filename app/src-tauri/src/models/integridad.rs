@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Ubicación cuyo stock registrado no coincide con el que resulta de sus
+/// movimientos. `diferencia` es registrado menos esperado.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, PartialEq)]
+pub struct DiferenciaStock {
+    pub id_prod_prov: i32,
+    pub id_presentacion: i32,
+    pub id_almacen: i32,
+    pub registrado: f64,
+    pub esperado: f64,
+    pub diferencia: f64,
+}
+
+/// Fila de `stock_almacen` o `movimiento` que apunta a un producto del
+/// proveedor, presentación o almacén inexistente, o a una presentación de
+/// otro producto. `problema` es `producto_proveedor_inexistente`,
+/// `presentacion_inexistente`, `almacen_inexistente` o
+/// `presentacion_de_otro_producto`.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, PartialEq)]
+pub struct RegistroHuerfano {
+    pub tabla: String,
+    pub id_registro: i32,
+    pub id_prod_prov: i32,
+    pub id_presentacion: i32,
+    pub id_almacen: Option<i32>,
+    pub problema: String,
+}
+
+/// Ubicación con diferencia que la reparación no pudo ajustar, por ejemplo
+/// por un conteo en curso, un período cerrado o la política de stock
+/// negativo; `motivo` es el mensaje del error
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UbicacionOmitida {
+    pub id_prod_prov: i32,
+    pub id_presentacion: i32,
+    pub id_almacen: i32,
+    pub motivo: String,
+}
+
+/// Resultado de verificar el stock. `ajustes` son los movimientos que
+/// registró la reparación y `omitidas` las ubicaciones que no pudo ajustar;
+/// ambos están vacíos en una verificación.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReporteIntegridad {
+    pub diferencias: Vec<DiferenciaStock>,
+    pub huerfanos: Vec<RegistroHuerfano>,
+    pub ajustes: Vec<i64>,
+    pub omitidas: Vec<UbicacionOmitida>,
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use crate::validation::{Validate, Validator, empty_as_none};
+
+/// Producto del proveedor del que se pide el kardex. Sin almacén se suman
+/// todos; sin presentación las cantidades se expresan en unidad base. Las
+/// fechas son `AAAA-MM-DD`, incluyen ambos extremos y sin ellas el rango no
+/// tiene límite.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FiltroKardex {
+    pub id_prod_prov: i32,
+    pub id_almacen: Option<i32>,
+    pub id_presentacion: Option<i32>,
+    pub desde: Option<String>,
+    pub hasta: Option<String>,
+}
+
+/// Cantidad y valor en existencia a una fecha
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct SaldoKardex {
+    pub cantidad: f64,
+    pub valor: f64,
+}
+
+/// Movimiento del kardex con el saldo que deja. `costo_unitario` es el valor
+/// del movimiento dividido por su cantidad; es nulo si no tiene valor.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LineaKardex {
+    pub id_movimiento: i32,
+    pub fecha: String,
+    pub tipo: String,
+    pub subtipo: Option<String>,
+    pub documento: Option<String>,
+    pub id_almacen: i32,
+    pub id_presentacion: i32,
+    pub entrada: f64,
+    pub salida: f64,
+    pub costo_unitario: Option<f64>,
+    pub valor: f64,
+    pub saldo: SaldoKardex,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Kardex {
+    pub filtro: FiltroKardex,
+    pub saldo_inicial: SaldoKardex,
+    pub lineas: Vec<LineaKardex>,
+    pub saldo_final: SaldoKardex,
+}
+
+impl Validate for FiltroKardex {
+    fn normalize(&mut self) {
+        empty_as_none(&mut self.desde);
+        empty_as_none(&mut self.hasta);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.id("id_prod_prov", Some(self.id_prod_prov));
+        v.id("id_almacen", self.id_almacen);
+        v.id("id_presentacion", self.id_presentacion);
+        v.date("desde", self.desde.as_deref());
+        v.date("hasta", self.hasta.as_deref());
+        if let (Some(desde), Some(hasta)) = (&self.desde, &self.hasta) {
+            if hasta < desde {
+                v.add("hasta", "No puede ser anterior a la fecha inicial");
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Lote abierto por una entrada; `cantidad_actual` es lo que queda por consumir.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Lote {
+    pub id_lote: i32,
+    pub id_movimiento: i32,
+    pub id_prod_prov: i32,
+    pub id_presentacion: i32,
+    pub id_almacen: i32,
+    pub numero_lote: Option<String>,
+    pub fecha_entrada: String,
+    pub fecha_venc: Option<String>,
+    pub cantidad_inicial: f64,
+    pub cantidad_actual: f64,
+    pub costo_unitario: f64,
+}
+
+/// Cantidad que una salida tomó de un lote y a qué costo.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct LoteConsumo {
+    pub id_consumo: i32,
+    pub id_movimiento: i32,
+    pub id_lote: i32,
+    pub cantidad: f64,
+    pub costo_unitario: f64,
+}
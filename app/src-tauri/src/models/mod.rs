@@ -6,6 +6,22 @@ pub mod factura;
 pub mod almacen;
 pub mod movimiento;
 pub mod stock_almacen;
+pub mod lote;
+pub mod configuracion;
+pub mod auditoria;
+pub mod transferencia;
+pub mod conteo;
+pub mod conversion;
+pub mod stock_parametro;
+pub mod orden_compra;
+pub mod pago;
+pub mod devolucion;
+pub mod kardex;
+pub mod valuacion;
+pub mod stock_historico;
+pub mod integridad;
+pub mod periodo;
+pub mod vencimiento;
 
 // Re-exportar todos los modelos para fácil acceso
 pub use producto::*;
@@ -15,4 +31,20 @@ pub use presentacion::*;
 pub use factura::*;
 pub use almacen::*;
 pub use movimiento::*;
-pub use stock_almacen::*;
\ No newline at end of file
+pub use stock_almacen::*;
+pub use lote::*;
+pub use configuracion::*;
+pub use auditoria::*;
+pub use transferencia::*;
+pub use conteo::*;
+pub use conversion::*;
+pub use stock_parametro::*;
+pub use orden_compra::*;
+pub use pago::*;
+pub use devolucion::*;
+pub use kardex::*;
+pub use valuacion::*;
+pub use stock_historico::*;
+pub use integridad::*;
+pub use periodo::*;
+pub use vencimiento::*;
\ No newline at end of file
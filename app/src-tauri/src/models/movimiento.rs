@@ -1,5 +1,21 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use crate::models::conversion::SUBTIPO_CONVERSION;
+use crate::models::devolucion::SUBTIPO_DEVOLUCION;
+use crate::models::factura::SUBTIPO_ANULACION;
+use crate::models::stock_almacen::StockInsuficiente;
+use crate::models::transferencia::SUBTIPO_TRANSFERENCIA;
+use crate::validation::{Validate, Validator, empty_as_none, trim, trim_opt};
+
+/// Subtipos de los movimientos que se registran en pares salida/entrada, o
+/// que revierten otro movimiento, y solo se deshacen con su documento
+/// completo. Los asigna la operación del documento, nunca la aplicación.
+pub const SUBTIPOS_DOCUMENTO: &[&str] = &[
+    SUBTIPO_TRANSFERENCIA,
+    SUBTIPO_CONVERSION,
+    SUBTIPO_ANULACION,
+    SUBTIPO_DEVOLUCION,
+];
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 pub struct Movimiento {
@@ -16,9 +32,12 @@ pub struct Movimiento {
     pub fecha_venc: Option<String>,
     pub obs: Option<String>,
     pub id_factura: Option<i32>,
+    pub id_almacen: Option<i32>,
+    pub documento: Option<String>,
+    pub id_linea_orden: Option<i32>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct CreateMovimiento {
     pub fecha: String,
     pub tipo: String,
@@ -32,9 +51,18 @@ pub struct CreateMovimiento {
     pub fecha_venc: Option<String>,
     pub obs: Option<String>,
     pub id_factura: Option<i32>,
+    pub id_almacen: i32,
+    /// Lo asignan las transferencias, conversiones, anulaciones y demás
+    /// operaciones que agrupan movimientos; no se recibe de la aplicación
+    #[serde(skip_deserializing)]
+    pub documento: Option<String>,
+    /// Lo asigna la recepción de órdenes de compra, que valida la línea y
+    /// actualiza el estado de la orden; no se recibe de la aplicación
+    #[serde(skip_deserializing)]
+    pub id_linea_orden: Option<i32>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct UpdateMovimiento {
     pub fecha: Option<String>,
     pub tipo: Option<String>,
@@ -48,4 +76,128 @@ pub struct UpdateMovimiento {
     pub fecha_venc: Option<String>,
     pub obs: Option<String>,
     pub id_factura: Option<i32>,
-}
\ No newline at end of file
+    pub id_almacen: Option<i32>,
+}
+
+/// Tipos de movimiento que afectan el stock de un almacén
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipoMovimiento {
+    Entrada,
+    Salida,
+    Ajuste,
+}
+
+impl TipoMovimiento {
+    pub fn parse(tipo: &str) -> Option<Self> {
+        match tipo.trim().to_lowercase().as_str() {
+            "entrada" => Some(Self::Entrada),
+            "salida" => Some(Self::Salida),
+            "ajuste" => Some(Self::Ajuste),
+            _ => None,
+        }
+    }
+
+    /// Signo con el que el movimiento afecta el stock. Los ajustes suman
+    /// salvo que su subtipo sea 'faltante'.
+    pub fn signo(&self, subtipo: Option<&str>) -> f64 {
+        match self {
+            Self::Entrada => 1.0,
+            Self::Salida => -1.0,
+            Self::Ajuste => match subtipo.map(|s| s.trim().to_lowercase()) {
+                Some(s) if s == "faltante" => -1.0,
+                _ => 1.0,
+            },
+        }
+    }
+}
+
+/// Resultado de registrar un movimiento. `advertencia` se informa cuando la
+/// política del almacén permite la salida pero deja el stock en negativo.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MovimientoRegistrado {
+    pub id_movimiento: i64,
+    pub advertencia: Option<StockInsuficiente>,
+}
+
+fn validar_subtipo(v: &mut Validator, subtipo: Option<&str>) {
+    if subtipo.is_some_and(|s| SUBTIPOS_DOCUMENTO.contains(&s.to_lowercase().as_str())) {
+        v.add("subtipo", "Lo asigna la operación que registra el documento");
+    }
+}
+
+fn validar_tipo(v: &mut Validator, tipo: Option<&str>) {
+    if tipo.is_some_and(|t| TipoMovimiento::parse(t).is_none()) {
+        v.add("tipo", "Tipo inválido, se espera entrada, salida o ajuste");
+    }
+}
+
+/// La fecha de vencimiento no puede ser anterior a la del movimiento. Ambas
+/// están en formato AAAA-MM-DD, por lo que se comparan como texto.
+fn validar_vencimiento(v: &mut Validator, fecha: Option<&str>, fecha_venc: Option<&str>) {
+    if let (Some(fecha), Some(venc)) = (fecha, fecha_venc) {
+        if venc < fecha {
+            v.add("fecha_venc", "No puede ser anterior a la fecha del movimiento");
+        }
+    }
+}
+
+impl Validate for CreateMovimiento {
+    fn normalize(&mut self) {
+        trim(&mut self.fecha);
+        trim(&mut self.tipo);
+        self.tipo = self.tipo.to_lowercase();
+        empty_as_none(&mut self.subtipo);
+        empty_as_none(&mut self.lote);
+        empty_as_none(&mut self.fecha_venc);
+        empty_as_none(&mut self.obs);
+        empty_as_none(&mut self.documento);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.required("fecha", &self.fecha);
+        v.date("fecha", Some(&self.fecha));
+        v.required("tipo", &self.tipo);
+        validar_tipo(v, Some(&self.tipo));
+        validar_subtipo(v, self.subtipo.as_deref());
+        v.id("id_prod_prov", Some(self.id_prod_prov));
+        v.id("id_presentacion", Some(self.id_presentacion));
+        v.positive("cantidad", Some(self.cantidad));
+        v.non_negative("precio_unit", self.precio_unit);
+        v.non_negative("monto_total", self.monto_total);
+        v.date("fecha_venc", self.fecha_venc.as_deref());
+        validar_vencimiento(v, Some(&self.fecha), self.fecha_venc.as_deref());
+        v.id("id_factura", self.id_factura);
+        v.id("id_almacen", Some(self.id_almacen));
+        v.max_len("documento", self.documento.as_deref(), 50);
+        v.id("id_linea_orden", self.id_linea_orden);
+    }
+}
+
+impl Validate for UpdateMovimiento {
+    fn normalize(&mut self) {
+        trim_opt(&mut self.fecha);
+        trim_opt(&mut self.tipo);
+        if let Some(tipo) = &mut self.tipo {
+            *tipo = tipo.to_lowercase();
+        }
+        trim_opt(&mut self.subtipo);
+        trim_opt(&mut self.lote);
+        trim_opt(&mut self.fecha_venc);
+        trim_opt(&mut self.obs);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.date("fecha", self.fecha.as_deref());
+        validar_tipo(v, self.tipo.as_deref());
+        validar_subtipo(v, self.subtipo.as_deref());
+        v.id("id_prod_prov", self.id_prod_prov);
+        v.id("id_presentacion", self.id_presentacion);
+        v.positive("cantidad", self.cantidad);
+        v.non_negative("precio_unit", self.precio_unit);
+        v.non_negative("monto_total", self.monto_total);
+        v.date("fecha_venc", self.fecha_venc.as_deref());
+        validar_vencimiento(v, self.fecha.as_deref(), self.fecha_venc.as_deref());
+        v.id("id_factura", self.id_factura);
+        v.id("id_almacen", self.id_almacen);
+    }
+}
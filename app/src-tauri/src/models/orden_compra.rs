@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::validation::{Validate, Validator, empty_as_none, trim};
+
+/// Orden en preparación; es la única que admite cambios
+pub const ORDEN_BORRADOR: &str = "Borrador";
+pub const ORDEN_ENVIADA: &str = "Enviada";
+pub const ORDEN_RECIBIDA_PARCIALMENTE: &str = "Recibida parcialmente";
+pub const ORDEN_RECIBIDA: &str = "Recibida";
+/// Orden terminada; lo que quedó sin recibir ya no se espera
+pub const ORDEN_CERRADA: &str = "Cerrada";
+
+/// Estados en los que lo pendiente de la orden cuenta como stock en tránsito
+/// y se puede recibir contra ella
+pub const ESTADOS_ORDEN_ABIERTA: &[&str] = &[ORDEN_ENVIADA, ORDEN_RECIBIDA_PARCIALMENTE];
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct OrdenCompra {
+    pub id_orden: i32,
+    pub numero: String,
+    pub fecha: String,
+    pub id_proveedor: i32,
+    pub id_almacen: i32,
+    pub estado: String,
+    pub fecha_entrega: Option<String>,
+    pub obs: Option<String>,
+}
+
+/// Línea de una orden con lo recibido hasta ahora, en la presentación pedida
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct OrdenCompraLinea {
+    pub id_linea: i32,
+    pub id_orden: i32,
+    pub id_prod_prov: i32,
+    pub id_presentacion: i32,
+    pub cantidad: f64,
+    pub precio_unit: f64,
+    pub cantidad_recibida: f64,
+}
+
+/// Producto, cantidad y precio acordado de una línea
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LineaOrdenInput {
+    pub id_prod_prov: i32,
+    pub id_presentacion: i32,
+    pub cantidad: f64,
+    pub precio_unit: f64,
+}
+
+/// Datos para crear una orden, o reemplazar una en borrador. Sin número se
+/// asigna el siguiente `OC-`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OrdenCompraInput {
+    pub numero: Option<String>,
+    pub fecha: String,
+    pub id_proveedor: i32,
+    pub id_almacen: i32,
+    pub fecha_entrega: Option<String>,
+    pub obs: Option<String>,
+    pub lineas: Vec<LineaOrdenInput>,
+}
+
+/// Cantidad recibida de una línea. Sin precio se usa el acordado en la orden.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LineaRecepcion {
+    pub id_linea: i32,
+    pub cantidad: f64,
+    pub precio_unit: Option<f64>,
+    pub lote: Option<String>,
+    pub fecha_venc: Option<String>,
+}
+
+/// Mercadería que llega contra una orden, con la factura que la acompaña
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecepcionOrden {
+    pub fecha: String,
+    pub id_factura: Option<i32>,
+    pub lineas: Vec<LineaRecepcion>,
+}
+
+impl Validate for OrdenCompraInput {
+    fn normalize(&mut self) {
+        empty_as_none(&mut self.numero);
+        trim(&mut self.fecha);
+        empty_as_none(&mut self.fecha_entrega);
+        empty_as_none(&mut self.obs);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.max_len("numero", self.numero.as_deref(), 50);
+        v.required("fecha", &self.fecha);
+        v.date("fecha", Some(&self.fecha));
+        v.id("id_proveedor", Some(self.id_proveedor));
+        v.id("id_almacen", Some(self.id_almacen));
+        v.date("fecha_entrega", self.fecha_entrega.as_deref());
+        if self.lineas.is_empty() {
+            v.add("lineas", "Debe incluir al menos un producto");
+        }
+        for (i, linea) in self.lineas.iter().enumerate() {
+            v.id(&format!("lineas[{}].id_prod_prov", i), Some(linea.id_prod_prov));
+            v.id(&format!("lineas[{}].id_presentacion", i), Some(linea.id_presentacion));
+            v.positive(&format!("lineas[{}].cantidad", i), Some(linea.cantidad));
+            v.non_negative(&format!("lineas[{}].precio_unit", i), Some(linea.precio_unit));
+        }
+    }
+}
+
+impl Validate for RecepcionOrden {
+    fn normalize(&mut self) {
+        trim(&mut self.fecha);
+        for linea in &mut self.lineas {
+            empty_as_none(&mut linea.lote);
+            empty_as_none(&mut linea.fecha_venc);
+        }
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.required("fecha", &self.fecha);
+        v.date("fecha", Some(&self.fecha));
+        v.id("id_factura", self.id_factura);
+        if self.lineas.is_empty() {
+            v.add("lineas", "Debe incluir al menos un producto");
+        }
+        for (i, linea) in self.lineas.iter().enumerate() {
+            v.id(&format!("lineas[{}].id_linea", i), Some(linea.id_linea));
+            v.positive(&format!("lineas[{}].cantidad", i), Some(linea.cantidad));
+            v.non_negative(&format!("lineas[{}].precio_unit", i), linea.precio_unit);
+            v.max_len(&format!("lineas[{}].lote", i), linea.lote.as_deref(), 50);
+            v.date(&format!("lineas[{}].fecha_venc", i), linea.fecha_venc.as_deref());
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::validation::{Validate, Validator, empty_as_none, trim};
+
+/// Valores permitidos en `pago.metodo`
+pub const METODOS_PAGO: &[&str] = &["Efectivo", "Transferencia", "Cheque", "Tarjeta"];
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Pago {
+    pub id_pago: i32,
+    pub fecha: String,
+    pub id_proveedor: i32,
+    pub monto: f64,
+    pub metodo: String,
+    pub referencia: Option<String>,
+    pub obs: Option<String>,
+}
+
+/// Parte de un pago aplicada a una factura
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PagoAplicacion {
+    pub id_aplicacion: i32,
+    pub id_pago: i32,
+    pub id_factura: i32,
+    pub monto: f64,
+}
+
+/// Monto que se aplica a una factura
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AplicacionPago {
+    pub id_factura: i32,
+    pub monto: f64,
+}
+
+/// Pago con las facturas que cancela. Lo que no se aplique queda como saldo
+/// a favor del proveedor.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreatePago {
+    pub fecha: String,
+    pub id_proveedor: i32,
+    pub monto: f64,
+    pub metodo: String,
+    pub referencia: Option<String>,
+    pub obs: Option<String>,
+    pub aplicaciones: Vec<AplicacionPago>,
+}
+
+/// Saldo pendiente de una factura activa. `acreditado` es lo descontado por
+/// notas de crédito.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SaldoFactura {
+    pub id_factura: i32,
+    pub numero: String,
+    pub id_proveedor: i32,
+    pub fecha: String,
+    pub fecha_vencimiento: Option<String>,
+    pub total: f64,
+    pub pagado: f64,
+    pub acreditado: f64,
+    pub saldo: f64,
+}
+
+/// Cuenta por pagar a un proveedor. `saldo_a_favor` es lo pagado que aún no
+/// se aplicó a ninguna factura más lo acreditado por encima de su saldo.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SaldoProveedor {
+    pub id_proveedor: i32,
+    pub nombre: String,
+    pub facturado: f64,
+    pub pagado: f64,
+    pub acreditado: f64,
+    pub saldo: f64,
+    pub saldo_a_favor: f64,
+}
+
+/// Saldo de un proveedor a una fecha de corte, por días transcurridos desde
+/// el vencimiento de cada factura
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct AntiguedadSaldo {
+    pub id_proveedor: i32,
+    pub nombre: String,
+    pub por_vencer: f64,
+    pub dias_0_30: f64,
+    pub dias_31_60: f64,
+    pub dias_61_90: f64,
+    pub dias_mas_90: f64,
+    pub total: f64,
+}
+
+/// Línea del estado de cuenta de un proveedor: una factura suma al saldo, y
+/// un pago o una nota de crédito lo descuenta. `saldo` es el acumulado.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct MovimientoCuenta {
+    pub fecha: String,
+    pub concepto: String,
+    pub id_documento: i32,
+    pub numero: Option<String>,
+    pub cargo: f64,
+    pub abono: f64,
+    pub saldo: f64,
+}
+
+impl Validate for CreatePago {
+    fn normalize(&mut self) {
+        trim(&mut self.fecha);
+        trim(&mut self.metodo);
+        empty_as_none(&mut self.referencia);
+        empty_as_none(&mut self.obs);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.required("fecha", &self.fecha);
+        v.date("fecha", Some(&self.fecha));
+        v.id("id_proveedor", Some(self.id_proveedor));
+        v.positive("monto", Some(self.monto));
+        v.one_of("metodo", Some(&self.metodo), METODOS_PAGO);
+        v.max_len("referencia", self.referencia.as_deref(), 100);
+        self.aplicaciones.rules(v);
+        let aplicado: f64 = self.aplicaciones.iter().map(|a| a.monto).sum();
+        if aplicado > self.monto + 1e-9 {
+            v.add("aplicaciones", "Lo aplicado supera el monto del pago");
+        }
+    }
+}
+
+impl Validate for Vec<AplicacionPago> {
+    fn rules(&self, v: &mut Validator) {
+        for (i, aplicacion) in self.iter().enumerate() {
+            v.id(&format!("aplicaciones[{}].id_factura", i), Some(aplicacion.id_factura));
+            v.positive(&format!("aplicaciones[{}].monto", i), Some(aplicacion.monto));
+        }
+    }
+}
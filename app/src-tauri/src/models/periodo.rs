@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::models::valuacion::{LineaValuacion, MetodoValuacion};
+use crate::validation::{Validate, Validator, is_date, trim};
+
+pub const PERIODO_CERRADO: &str = "Cerrado";
+pub const PERIODO_ABIERTO: &str = "Abierto";
+
+/// Período contable mensual `AAAA-MM` que se cerró alguna vez. Los meses sin
+/// fila están abiertos.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Periodo {
+    pub id_periodo: i32,
+    pub periodo: String,
+    pub estado: String,
+    pub metodo_valuacion: String,
+    pub valor_total: f64,
+    pub cerrado_en: String,
+    pub reabierto_en: Option<String>,
+    pub motivo_reapertura: Option<String>,
+}
+
+/// Período cerrado con el stock valorizado que se guardó al cerrarlo
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CierrePeriodo {
+    pub periodo: Periodo,
+    pub lineas: Vec<LineaValuacion>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CerrarPeriodo {
+    pub periodo: String,
+    #[serde(default)]
+    pub metodo: MetodoValuacion,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReabrirPeriodo {
+    pub periodo: String,
+    pub motivo: String,
+}
+
+impl Validate for CerrarPeriodo {
+    fn normalize(&mut self) {
+        trim(&mut self.periodo);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        validar_periodo(v, &self.periodo);
+    }
+}
+
+impl Validate for ReabrirPeriodo {
+    fn normalize(&mut self) {
+        trim(&mut self.periodo);
+        trim(&mut self.motivo);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        validar_periodo(v, &self.periodo);
+        v.required("motivo", &self.motivo);
+        v.max_len("motivo", Some(&self.motivo), 500);
+    }
+}
+
+fn validar_periodo(v: &mut Validator, periodo: &str) {
+    v.required("periodo", periodo);
+    if !periodo.is_empty() && (periodo.len() != 7 || !is_date(&format!("{}-01", periodo))) {
+        v.add("periodo", "Debe tener el formato AAAA-MM");
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use crate::validation::{Validate, Validator, empty_as_none, trim, trim_opt};
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 pub struct Presentacion {
@@ -24,4 +25,32 @@ pub struct UpdatePresentacion {
     pub unidad: Option<String>,
     pub cantidad: Option<f64>,
     pub descripcion: Option<String>,
-}
\ No newline at end of file
+}
+
+impl Validate for CreatePresentacion {
+    fn normalize(&mut self) {
+        trim(&mut self.unidad);
+        empty_as_none(&mut self.descripcion);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.id("id_producto", Some(self.id_producto));
+        v.required("unidad", &self.unidad);
+        v.max_len("unidad", Some(&self.unidad), 50);
+        v.positive("cantidad", Some(self.cantidad));
+    }
+}
+
+impl Validate for UpdatePresentacion {
+    fn normalize(&mut self) {
+        trim_opt(&mut self.unidad);
+        trim_opt(&mut self.descripcion);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.id("id_producto", self.id_producto);
+        v.not_blank("unidad", self.unidad.as_deref());
+        v.max_len("unidad", self.unidad.as_deref(), 50);
+        v.positive("cantidad", self.cantidad);
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use crate::validation::{Validate, Validator, ESTADOS_REGISTRO, empty_as_none, trim, trim_opt};
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 pub struct Producto {
@@ -9,6 +10,9 @@ pub struct Producto {
     pub categoria: Option<String>,
     pub subcategoria: Option<String>,
     pub estado: Option<String>,
+    /// Fecha en que se archivó; los archivados no se listan por defecto
+    pub eliminado_en: Option<String>,
+    pub eliminado_por: Option<String>,
 }
 
 // DTOs para las operaciones
@@ -28,4 +32,40 @@ pub struct UpdateProducto {
     pub categoria: Option<String>,
     pub subcategoria: Option<String>,
     pub estado: Option<String>,
-}
\ No newline at end of file
+}
+
+impl Validate for CreateProducto {
+    fn normalize(&mut self) {
+        trim(&mut self.codigo_interno);
+        trim(&mut self.descripcion);
+        empty_as_none(&mut self.categoria);
+        empty_as_none(&mut self.subcategoria);
+        empty_as_none(&mut self.estado);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.required("codigo_interno", &self.codigo_interno);
+        v.max_len("codigo_interno", Some(&self.codigo_interno), 50);
+        v.required("descripcion", &self.descripcion);
+        v.max_len("descripcion", Some(&self.descripcion), 255);
+        v.one_of("estado", self.estado.as_deref(), ESTADOS_REGISTRO);
+    }
+}
+
+impl Validate for UpdateProducto {
+    fn normalize(&mut self) {
+        trim_opt(&mut self.codigo_interno);
+        trim_opt(&mut self.descripcion);
+        trim_opt(&mut self.categoria);
+        trim_opt(&mut self.subcategoria);
+        trim_opt(&mut self.estado);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.not_blank("codigo_interno", self.codigo_interno.as_deref());
+        v.max_len("codigo_interno", self.codigo_interno.as_deref(), 50);
+        v.not_blank("descripcion", self.descripcion.as_deref());
+        v.max_len("descripcion", self.descripcion.as_deref(), 255);
+        v.one_of("estado", self.estado.as_deref(), ESTADOS_REGISTRO);
+    }
+}
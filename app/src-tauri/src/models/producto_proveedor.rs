@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use crate::validation::{Validate, Validator, ESTADOS_REGISTRO, empty_as_none, trim, trim_opt};
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 pub struct ProductoProveedor {
@@ -24,4 +25,34 @@ pub struct UpdateProductoProveedor {
     pub id_proveedor: Option<i32>,
     pub codigo_proveedor: Option<String>,
     pub estado: Option<String>,
-}
\ No newline at end of file
+}
+
+impl Validate for CreateProductoProveedor {
+    fn normalize(&mut self) {
+        trim(&mut self.codigo_proveedor);
+        empty_as_none(&mut self.estado);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.id("id_producto", Some(self.id_producto));
+        v.id("id_proveedor", Some(self.id_proveedor));
+        v.required("codigo_proveedor", &self.codigo_proveedor);
+        v.max_len("codigo_proveedor", Some(&self.codigo_proveedor), 50);
+        v.one_of("estado", self.estado.as_deref(), ESTADOS_REGISTRO);
+    }
+}
+
+impl Validate for UpdateProductoProveedor {
+    fn normalize(&mut self) {
+        trim_opt(&mut self.codigo_proveedor);
+        trim_opt(&mut self.estado);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.id("id_producto", self.id_producto);
+        v.id("id_proveedor", self.id_proveedor);
+        v.not_blank("codigo_proveedor", self.codigo_proveedor.as_deref());
+        v.max_len("codigo_proveedor", self.codigo_proveedor.as_deref(), 50);
+        v.one_of("estado", self.estado.as_deref(), ESTADOS_REGISTRO);
+    }
+}
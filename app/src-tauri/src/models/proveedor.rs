@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use crate::validation::{Validate, Validator, ESTADOS_REGISTRO, empty_as_none, trim, trim_opt};
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 pub struct Proveedor {
@@ -10,6 +11,12 @@ pub struct Proveedor {
     pub telefono: Option<String>,
     pub email: Option<String>,
     pub estado: Option<String>,
+    pub pais: String,
+    /// Fecha en que se archivó; los archivados no se listan por defecto
+    pub eliminado_en: Option<String>,
+    pub eliminado_por: Option<String>,
+    /// Plazo de pago; define el vencimiento de sus facturas
+    pub dias_credito: i32,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -20,6 +27,10 @@ pub struct CreateProveedor {
     pub telefono: Option<String>,
     pub email: Option<String>,
     pub estado: Option<String>,
+    /// Código ISO del país; por defecto NI
+    pub pais: Option<String>,
+    /// Por defecto, pago de contado
+    pub dias_credito: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -30,4 +41,69 @@ pub struct UpdateProveedor {
     pub telefono: Option<String>,
     pub email: Option<String>,
     pub estado: Option<String>,
-}
\ No newline at end of file
+    pub pais: Option<String>,
+    pub dias_credito: Option<i32>,
+}
+
+fn validar_dias_credito(v: &mut Validator, dias: Option<i32>) {
+    if dias.is_some_and(|d| d < 0) {
+        v.add("dias_credito", "No puede ser negativo");
+    }
+}
+
+/// Código de país de dos letras
+fn validar_pais(v: &mut Validator, pais: Option<&str>) {
+    if pais.is_some_and(|p| p.len() != 2 || !p.chars().all(|c| c.is_ascii_uppercase())) {
+        v.add("pais", "Código de país inválido, se esperan dos letras (p. ej. NI)");
+    }
+}
+
+impl Validate for CreateProveedor {
+    fn normalize(&mut self) {
+        trim(&mut self.ruc_ci);
+        trim(&mut self.nombre);
+        empty_as_none(&mut self.contacto);
+        empty_as_none(&mut self.telefono);
+        empty_as_none(&mut self.email);
+        empty_as_none(&mut self.estado);
+        empty_as_none(&mut self.pais);
+        self.pais = self.pais.take().map(|p| p.to_uppercase());
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.required("ruc_ci", &self.ruc_ci);
+        v.max_len("ruc_ci", Some(&self.ruc_ci), 30);
+        v.required("nombre", &self.nombre);
+        v.max_len("nombre", Some(&self.nombre), 255);
+        v.phone("telefono", self.telefono.as_deref());
+        v.email("email", self.email.as_deref());
+        v.one_of("estado", self.estado.as_deref(), ESTADOS_REGISTRO);
+        validar_pais(v, self.pais.as_deref());
+        validar_dias_credito(v, self.dias_credito);
+    }
+}
+
+impl Validate for UpdateProveedor {
+    fn normalize(&mut self) {
+        trim_opt(&mut self.ruc_ci);
+        trim_opt(&mut self.nombre);
+        trim_opt(&mut self.contacto);
+        empty_as_none(&mut self.telefono);
+        empty_as_none(&mut self.email);
+        trim_opt(&mut self.estado);
+        trim_opt(&mut self.pais);
+        self.pais = self.pais.take().map(|p| p.to_uppercase());
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.not_blank("ruc_ci", self.ruc_ci.as_deref());
+        v.max_len("ruc_ci", self.ruc_ci.as_deref(), 30);
+        v.not_blank("nombre", self.nombre.as_deref());
+        v.max_len("nombre", self.nombre.as_deref(), 255);
+        v.phone("telefono", self.telefono.as_deref());
+        v.email("email", self.email.as_deref());
+        v.one_of("estado", self.estado.as_deref(), ESTADOS_REGISTRO);
+        validar_pais(v, self.pais.as_deref());
+        validar_dias_credito(v, self.dias_credito);
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use crate::validation::{Validate, Validator};
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 pub struct StockAlmacen {
@@ -24,4 +25,37 @@ pub struct UpdateStockAlmacen {
     pub id_presentacion: Option<i32>,
     pub id_almacen: Option<i32>,
     pub stock_actual: Option<f64>,
-}
\ No newline at end of file
+}
+
+/// Detalle de una salida que supera el stock disponible de su ubicación
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StockInsuficiente {
+    pub id_prod_prov: i32,
+    pub id_presentacion: i32,
+    pub id_almacen: i32,
+    pub disponible: f64,
+    pub solicitado: f64,
+    pub faltante: f64,
+}
+
+impl Validate for CreateStockAlmacen {
+    fn rules(&self, v: &mut Validator) {
+        v.id("id_prod_prov", Some(self.id_prod_prov));
+        v.id("id_presentacion", Some(self.id_presentacion));
+        v.id("id_almacen", Some(self.id_almacen));
+        if !self.stock_actual.is_finite() {
+            v.add("stock_actual", "Cantidad inválida");
+        }
+    }
+}
+
+impl Validate for UpdateStockAlmacen {
+    fn rules(&self, v: &mut Validator) {
+        v.id("id_prod_prov", self.id_prod_prov);
+        v.id("id_presentacion", self.id_presentacion);
+        v.id("id_almacen", self.id_almacen);
+        if self.stock_actual.is_some_and(|s| !s.is_finite()) {
+            v.add("stock_actual", "Cantidad inválida");
+        }
+    }
+}
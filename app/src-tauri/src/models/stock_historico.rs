@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::validation::{Validate, Validator, trim};
+
+/// Saldos de stock guardados a una fecha. Se descarta solo si luego se
+/// registra, modifica o elimina un movimiento con fecha igual o anterior.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct CorteStock {
+    pub id_corte: i32,
+    pub fecha: String,
+    pub creado_en: String,
+}
+
+/// Stock de una ubicación a una fecha
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, PartialEq)]
+pub struct SaldoHistorico {
+    pub id_prod_prov: i32,
+    pub id_presentacion: i32,
+    pub id_almacen: i32,
+    pub cantidad: f64,
+}
+
+/// Fecha `AAAA-MM-DD` a la que se pide el stock, incluida. Los movimientos se
+/// registran por día, así que el stock solo se reconstruye al final de un día
+/// completo y se rechaza una fecha con hora. Sin almacén ni producto se
+/// incluyen todos.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FiltroStockHistorico {
+    pub fecha: String,
+    pub id_almacen: Option<i32>,
+    pub id_prod_prov: Option<i32>,
+}
+
+/// Stock a una fecha y el corte del que se partió para calcularlo, si había
+/// alguno anterior
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StockHistorico {
+    pub fecha: String,
+    pub id_corte_base: Option<i32>,
+    pub saldos: Vec<SaldoHistorico>,
+}
+
+impl Validate for FiltroStockHistorico {
+    fn normalize(&mut self) {
+        trim(&mut self.fecha);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.required("fecha", &self.fecha);
+        if self.fecha.contains([' ', 'T']) {
+            v.add("fecha", "Los movimientos se registran por día; debe ser una fecha AAAA-MM-DD sin hora");
+        }
+        v.date("fecha", Some(&self.fecha));
+        v.id("id_almacen", self.id_almacen);
+        v.id("id_prod_prov", self.id_prod_prov);
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::validation::{Validate, Validator};
+
+/// Parámetros de stock de un producto en unidad base. Sin `id_almacen` es el
+/// valor general; con almacén sobrescribe los campos que no sean nulos.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct StockParametro {
+    pub id_parametro: i32,
+    pub id_producto: i32,
+    pub id_almacen: Option<i32>,
+    pub stock_minimo: Option<f64>,
+    pub stock_maximo: Option<f64>,
+    pub punto_reorden: Option<f64>,
+}
+
+/// Reemplaza los parámetros generales (`id_almacen` nulo) o los de un almacén
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SetStockParametro {
+    pub id_producto: i32,
+    pub id_almacen: Option<i32>,
+    pub stock_minimo: Option<f64>,
+    pub stock_maximo: Option<f64>,
+    pub punto_reorden: Option<f64>,
+}
+
+/// Stock de un producto en un almacén, en unidad base, frente a sus
+/// parámetros efectivos. `en_transito` es lo pedido en órdenes de compra
+/// abiertas que aún no llegó.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct NivelStock {
+    pub id_producto: i32,
+    pub codigo_interno: String,
+    pub descripcion: String,
+    pub id_almacen: i32,
+    pub stock_base: f64,
+    pub en_transito: f64,
+    pub stock_minimo: Option<f64>,
+    pub stock_maximo: Option<f64>,
+    pub punto_reorden: Option<f64>,
+}
+
+/// Cantidad a pedir, en unidad base, para llevar un producto al stock máximo
+/// de un almacén: máximo − stock actual − en tránsito. Se propone el
+/// proveedor de la última entrada del producto.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SugerenciaReorden {
+    pub id_producto: i32,
+    pub codigo_interno: String,
+    pub descripcion: String,
+    pub id_almacen: i32,
+    pub id_prod_prov: Option<i32>,
+    pub id_proveedor: Option<i32>,
+    pub proveedor: Option<String>,
+    pub stock_base: f64,
+    pub en_transito: f64,
+    pub stock_maximo: f64,
+    pub cantidad_sugerida: f64,
+}
+
+impl Validate for SetStockParametro {
+    fn rules(&self, v: &mut Validator) {
+        v.id("id_producto", Some(self.id_producto));
+        v.id("id_almacen", self.id_almacen);
+        v.non_negative("stock_minimo", self.stock_minimo);
+        v.non_negative("stock_maximo", self.stock_maximo);
+        v.non_negative("punto_reorden", self.punto_reorden);
+
+        if let (Some(minimo), Some(maximo)) = (self.stock_minimo, self.stock_maximo) {
+            if maximo < minimo {
+                v.add("stock_maximo", "No puede ser menor que el stock mínimo");
+            }
+        }
+        if let (Some(minimo), Some(reorden)) = (self.stock_minimo, self.punto_reorden) {
+            if reorden < minimo {
+                v.add("punto_reorden", "No puede ser menor que el stock mínimo");
+            }
+        }
+        if let (Some(reorden), Some(maximo)) = (self.punto_reorden, self.stock_maximo) {
+            if reorden > maximo {
+                v.add("punto_reorden", "No puede ser mayor que el stock máximo");
+            }
+        }
+    }
+}
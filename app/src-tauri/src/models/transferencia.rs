@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use crate::models::movimiento::Movimiento;
+use crate::models::stock_almacen::StockInsuficiente;
+use crate::validation::{Validate, Validator, empty_as_none, trim};
+
+/// Subtipo de la salida y la entrada que registra una transferencia
+pub const SUBTIPO_TRANSFERENCIA: &str = "transferencia";
+
+/// Producto y cantidad que se traslada
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LineaTransferencia {
+    pub id_prod_prov: i32,
+    pub id_presentacion: i32,
+    pub cantidad: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateTransferencia {
+    pub fecha: String,
+    pub id_almacen_origen: i32,
+    pub id_almacen_destino: i32,
+    pub obs: Option<String>,
+    pub lineas: Vec<LineaTransferencia>,
+}
+
+/// Movimientos de una transferencia, enlazados por su número de documento
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Transferencia {
+    pub documento: String,
+    pub movimientos: Vec<Movimiento>,
+}
+
+/// Resultado de registrar una transferencia. Hay una advertencia por cada
+/// línea que dejó el origen en negativo cuando la política lo permite.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransferenciaRegistrada {
+    pub documento: String,
+    pub advertencias: Vec<StockInsuficiente>,
+}
+
+impl Validate for CreateTransferencia {
+    fn normalize(&mut self) {
+        trim(&mut self.fecha);
+        empty_as_none(&mut self.obs);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.required("fecha", &self.fecha);
+        v.date("fecha", Some(&self.fecha));
+        v.id("id_almacen_origen", Some(self.id_almacen_origen));
+        v.id("id_almacen_destino", Some(self.id_almacen_destino));
+        if self.id_almacen_origen == self.id_almacen_destino {
+            v.add("id_almacen_destino", "Debe ser distinto del almacén de origen");
+        }
+        if self.lineas.is_empty() {
+            v.add("lineas", "Debe incluir al menos un producto");
+        }
+        for (i, linea) in self.lineas.iter().enumerate() {
+            v.id(&format!("lineas[{}].id_prod_prov", i), Some(linea.id_prod_prov));
+            v.id(&format!("lineas[{}].id_presentacion", i), Some(linea.id_presentacion));
+            v.positive(&format!("lineas[{}].cantidad", i), Some(linea.cantidad));
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::validation::{Validate, Validator, empty_as_none};
+
+/// Costo unitario con que se valoriza el stock
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MetodoValuacion {
+    /// Costo de los lotes que quedan en existencia (primero en entrar,
+    /// primero en salir)
+    #[default]
+    Peps,
+    /// Promedio ponderado de todo lo ingresado a la ubicación
+    Promedio,
+    /// Costo del último lote ingresado a la ubicación
+    UltimoCosto,
+}
+
+impl MetodoValuacion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Peps => "peps",
+            Self::Promedio => "promedio",
+            Self::UltimoCosto => "ultimo_costo",
+        }
+    }
+}
+
+/// Sin fecha de corte se valoriza el stock actual; sin almacén, todos
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FiltroValuacion {
+    pub metodo: MetodoValuacion,
+    pub fecha_corte: Option<String>,
+    pub id_almacen: Option<i32>,
+}
+
+/// Existencia valorizada de un producto en una presentación y un almacén.
+/// `costo_unitario` es nulo si la ubicación nunca tuvo lotes con costo.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct LineaValuacion {
+    pub id_producto: i32,
+    pub codigo_interno: String,
+    pub descripcion: String,
+    pub categoria: Option<String>,
+    pub id_presentacion: i32,
+    pub unidad: String,
+    pub id_almacen: i32,
+    pub almacen: String,
+    pub cantidad: f64,
+    pub costo_unitario: Option<f64>,
+    pub valor: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SubtotalCategoria {
+    pub categoria: Option<String>,
+    pub valor: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SubtotalAlmacen {
+    pub id_almacen: i32,
+    pub almacen: String,
+    pub valor: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ValuacionStock {
+    pub filtro: FiltroValuacion,
+    pub lineas: Vec<LineaValuacion>,
+    pub por_categoria: Vec<SubtotalCategoria>,
+    pub por_almacen: Vec<SubtotalAlmacen>,
+    pub total: f64,
+}
+
+impl Validate for FiltroValuacion {
+    fn normalize(&mut self) {
+        empty_as_none(&mut self.fecha_corte);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        v.date("fecha_corte", self.fecha_corte.as_deref());
+        v.id("id_almacen", self.id_almacen);
+    }
+}
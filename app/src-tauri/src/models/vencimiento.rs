@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use crate::validation::{Validate, Validator, empty_as_none};
+
+/// Lotes con saldo que vencen dentro de `dias` días desde `fecha`
+/// (`AAAA-MM-DD`, hoy si se omite) o que ya vencieron. Sin almacén se
+/// incluyen todos.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FiltroVencimiento {
+    pub dias: i32,
+    pub fecha: Option<String>,
+    pub id_almacen: Option<i32>,
+}
+
+/// Lote con saldo y vencimiento. `dias_restantes` es negativo si ya venció;
+/// `valor` es el saldo al costo del lote.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct LoteVencimiento {
+    pub id_lote: i32,
+    pub id_almacen: i32,
+    pub almacen: String,
+    pub id_prod_prov: i32,
+    pub id_producto: i32,
+    pub codigo_interno: String,
+    pub descripcion: String,
+    pub id_presentacion: i32,
+    pub unidad: String,
+    pub numero_lote: Option<String>,
+    pub fecha_venc: String,
+    pub dias_restantes: i32,
+    pub vencido: bool,
+    pub cantidad: f64,
+    pub costo_unitario: f64,
+    pub valor: f64,
+}
+
+/// Valor en riesgo de un almacén, separado entre vencido y por vencer
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RiesgoAlmacen {
+    pub id_almacen: i32,
+    pub almacen: String,
+    pub lotes_vencidos: i32,
+    pub valor_vencido: f64,
+    pub lotes_por_vencer: i32,
+    pub valor_por_vencer: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReporteVencimiento {
+    pub fecha: String,
+    pub dias: i32,
+    pub lotes: Vec<LoteVencimiento>,
+    pub por_almacen: Vec<RiesgoAlmacen>,
+    pub valor_vencido: f64,
+    pub valor_por_vencer: f64,
+}
+
+impl Validate for FiltroVencimiento {
+    fn normalize(&mut self) {
+        empty_as_none(&mut self.fecha);
+    }
+
+    fn rules(&self, v: &mut Validator) {
+        if self.dias < 0 {
+            v.add("dias", "No puede ser negativo");
+        }
+        v.date("fecha", self.fecha.as_deref());
+        v.id("id_almacen", self.id_almacen);
+    }
+}
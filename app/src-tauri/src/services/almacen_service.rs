@@ -1,17 +1,52 @@
-use sqlx::{SqlitePool, Result};
+use sqlx::{SqlitePool, SqliteConnection};
+use crate::error::{AppError, Result};
 use crate::models::almacen::{Almacen, CreateAlmacen, UpdateAlmacen};
+use crate::services::dependencia_service::{self, Referencia};
 
-pub async fn get_all(pool: &SqlitePool) -> Result<Vec<Almacen>> {
-    sqlx::query_as::<_, Almacen>("SELECT * FROM almacen")
-        .fetch_all(pool)
-        .await
+const DEPENDIENTES: &[Referencia] = &[
+    ("movimiento", "id_almacen = ?"),
+    ("lote", "id_almacen = ?"),
+    ("stock_almacen", "id_almacen = ?"),
+    ("conteo", "id_almacen = ?"),
+    ("orden_compra", "id_almacen = ?"),
+];
+
+/// Los almacenes archivados solo se incluyen si se piden
+pub async fn get_all(pool: &SqlitePool, incluir_archivados: bool) -> Result<Vec<Almacen>> {
+    sqlx::query_as::<_, Almacen>(
+        "SELECT * FROM almacen WHERE (? OR eliminado_en IS NULL)"
+    )
+    .bind(incluir_archivados)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::from)
 }
 
-pub async fn get_by_id(pool: &SqlitePool, id: i32) -> Result<Option<Almacen>> {
-    sqlx::query_as::<_, Almacen>("SELECT * FROM almacen WHERE id_almacen = ?")
-        .bind(id)
-        .fetch_optional(pool)
-        .await
+pub async fn get_by_id(pool: &SqlitePool, id: i32, incluir_archivados: bool) -> Result<Option<Almacen>> {
+    sqlx::query_as::<_, Almacen>(
+        "SELECT * FROM almacen WHERE id_almacen = ? AND (? OR eliminado_en IS NULL)"
+    )
+    .bind(id)
+    .bind(incluir_archivados)
+    .fetch_optional(pool)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Falla con `NotFound` si el almacén no existe o está archivado
+pub(crate) async fn verificar_activo(conn: &mut SqliteConnection, id: i32) -> Result<()> {
+    let activo: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM almacen WHERE id_almacen = ? AND eliminado_en IS NULL"
+    )
+    .bind(id)
+    .fetch_one(conn)
+    .await?;
+
+    if !activo {
+        return Err(AppError::not_found("el almacén", id));
+    }
+
+    Ok(())
 }
 
 pub async fn create(pool: &SqlitePool, data: CreateAlmacen) -> Result<i64> {
@@ -43,9 +78,60 @@ pub async fn update(pool: &SqlitePool, id: i32, data: UpdateAlmacen) -> Result<u
     Ok(result.rows_affected())
 }
 
+/// Archiva el almacén: deja de listarse pero conserva su historial.
 pub async fn delete(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let result = sqlx::query!(
+        "UPDATE almacen SET
+            eliminado_en = datetime('now'),
+            eliminado_por = (SELECT usuario FROM sesion WHERE id_sesion = 1)
+         WHERE id_almacen = ? AND eliminado_en IS NULL",
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn restore(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let result = sqlx::query!(
+        "UPDATE almacen SET eliminado_en = NULL, eliminado_por = NULL
+         WHERE id_almacen = ? AND eliminado_en IS NOT NULL",
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Elimina definitivamente un almacén archivado con su configuración y sus
+/// parámetros de stock propios y sus filas de stock en cero. Se rechaza si
+/// tiene movimientos, lotes, stock o conteos.
+pub async fn purge(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    if !dependencia_service::verificar_archivado(&mut tx, "almacen", "id_almacen", id).await? {
+        return Ok(0);
+    }
+
+    sqlx::query!("DELETE FROM stock_almacen WHERE id_almacen = ? AND stock_actual = 0", id)
+        .execute(&mut *tx)
+        .await?;
+
+    dependencia_service::restringir(&mut tx, DEPENDIENTES, id).await?;
+
+    sqlx::query!("DELETE FROM configuracion WHERE id_almacen = ?", id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query!("DELETE FROM stock_parametro WHERE id_almacen = ?", id)
+        .execute(&mut *tx)
+        .await?;
+
     let result = sqlx::query!("DELETE FROM almacen WHERE id_almacen = ?", id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
+
+    tx.commit().await?;
     Ok(result.rows_affected())
 }
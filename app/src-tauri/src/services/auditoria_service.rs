@@ -0,0 +1,36 @@
+use sqlx::SqlitePool;
+use crate::error::{AppError, Result};
+use crate::models::auditoria::{Auditoria, FiltroAuditoria};
+
+/// Cambios que cumplen el filtro, del más reciente al más antiguo
+pub async fn buscar(pool: &SqlitePool, filtro: &FiltroAuditoria) -> Result<Vec<Auditoria>> {
+    sqlx::query_as::<_, Auditoria>(
+        "SELECT * FROM auditoria
+         WHERE (? IS NULL OR entidad = ?)
+           AND (? IS NULL OR id_registro = ?)
+           AND (? IS NULL OR date(fecha) >= ?)
+           AND (? IS NULL OR date(fecha) <= ?)
+         ORDER BY id_auditoria DESC"
+    )
+    .bind(&filtro.entidad)
+    .bind(&filtro.entidad)
+    .bind(filtro.id_registro)
+    .bind(filtro.id_registro)
+    .bind(&filtro.desde)
+    .bind(&filtro.desde)
+    .bind(&filtro.hasta)
+    .bind(&filtro.hasta)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Historial completo de un registro
+pub async fn get_by_registro(pool: &SqlitePool, entidad: &str, id_registro: i64) -> Result<Vec<Auditoria>> {
+    let filtro = FiltroAuditoria {
+        entidad: Some(entidad.to_string()),
+        id_registro: Some(id_registro),
+        ..Default::default()
+    };
+    buscar(pool, &filtro).await
+}
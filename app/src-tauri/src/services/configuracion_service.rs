@@ -0,0 +1,100 @@
+use sqlx::{SqlitePool, SqliteExecutor};
+use crate::error::{AppError, Result};
+use crate::models::configuracion::{EstrategiaSalida, PoliticaStock};
+
+pub const POLITICA_STOCK: &str = "politica_stock_negativo";
+pub const ESTRATEGIA_SALIDA: &str = "estrategia_salida";
+
+/// Valor de una clave para un almacén; si el almacén no la sobrescribe se usa
+/// el valor global (`id_almacen` nulo).
+pub async fn get_valor<'e, E: SqliteExecutor<'e>>(
+    executor: E,
+    clave: &str,
+    almacen_id: Option<i32>,
+) -> Result<Option<String>> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT valor FROM configuracion
+         WHERE clave = ? AND (id_almacen = ? OR id_almacen IS NULL)
+         ORDER BY id_almacen IS NULL ASC
+         LIMIT 1"
+    )
+    .bind(clave)
+    .bind(almacen_id)
+    .fetch_optional(executor)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Guarda el valor global (`almacen_id` = None) o el de un almacén. Con
+/// `valor` = None se elimina la entrada.
+pub async fn set_valor(
+    pool: &SqlitePool,
+    clave: &str,
+    almacen_id: Option<i32>,
+    valor: Option<&str>,
+) -> Result<()> {
+    match valor {
+        Some(valor) => {
+            sqlx::query!(
+                "INSERT INTO configuracion (clave, id_almacen, valor) VALUES (?, ?, ?)
+                 ON CONFLICT (clave, COALESCE(id_almacen, 0)) DO UPDATE SET valor = excluded.valor",
+                clave,
+                almacen_id,
+                valor
+            )
+            .execute(pool)
+            .await?;
+        }
+        None => {
+            sqlx::query!(
+                "DELETE FROM configuracion WHERE clave = ? AND id_almacen IS ?",
+                clave,
+                almacen_id
+            )
+            .execute(pool)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_politica_stock<'e, E: SqliteExecutor<'e>>(
+    executor: E,
+    almacen_id: Option<i32>,
+) -> Result<PoliticaStock> {
+    let valor = get_valor(executor, POLITICA_STOCK, almacen_id).await?;
+
+    Ok(valor
+        .as_deref()
+        .and_then(PoliticaStock::parse)
+        .unwrap_or_default())
+}
+
+pub async fn set_politica_stock(
+    pool: &SqlitePool,
+    almacen_id: Option<i32>,
+    politica: Option<PoliticaStock>,
+) -> Result<()> {
+    set_valor(pool, POLITICA_STOCK, almacen_id, politica.map(|p| p.as_str())).await
+}
+
+pub async fn get_estrategia_salida<'e, E: SqliteExecutor<'e>>(
+    executor: E,
+    almacen_id: Option<i32>,
+) -> Result<EstrategiaSalida> {
+    let valor = get_valor(executor, ESTRATEGIA_SALIDA, almacen_id).await?;
+
+    Ok(valor
+        .as_deref()
+        .and_then(EstrategiaSalida::parse)
+        .unwrap_or_default())
+}
+
+pub async fn set_estrategia_salida(
+    pool: &SqlitePool,
+    almacen_id: Option<i32>,
+    estrategia: Option<EstrategiaSalida>,
+) -> Result<()> {
+    set_valor(pool, ESTRATEGIA_SALIDA, almacen_id, estrategia.map(|e| e.as_str())).await
+}
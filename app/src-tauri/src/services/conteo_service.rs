@@ -0,0 +1,273 @@
+use sqlx::{SqlitePool, SqliteConnection, SqliteExecutor};
+use crate::error::{AppError, Result};
+use crate::models::conteo::{
+    CantidadContada, Conteo, ConteoDetalle, CreateConteo, CONTEO_ABIERTO, CONTEO_APROBADO, CONTEO_CANCELADO,
+    CONTEO_EN_CONTEO, ESTADOS_CONTEO_EN_CURSO, SUBTIPO_FALTANTE, SUBTIPO_SOBRANTE,
+};
+use crate::models::movimiento::CreateMovimiento;
+use crate::models::stock_almacen::StockAlmacen;
+use crate::services::{almacen_service, lote_service, posting_service, producto_proveedor_service};
+
+/// Tolerancia para considerar que no hay diferencia
+const EPSILON: f64 = 1e-9;
+
+pub async fn get_all(pool: &SqlitePool) -> Result<Vec<Conteo>> {
+    sqlx::query_as::<_, Conteo>("SELECT * FROM conteo ORDER BY id_conteo ASC")
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_by_id<'e, E: SqliteExecutor<'e>>(executor: E, id: i32) -> Result<Option<Conteo>> {
+    sqlx::query_as::<_, Conteo>("SELECT * FROM conteo WHERE id_conteo = ?")
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_by_almacen(pool: &SqlitePool, almacen_id: i32) -> Result<Vec<Conteo>> {
+    sqlx::query_as::<_, Conteo>("SELECT * FROM conteo WHERE id_almacen = ? ORDER BY fecha DESC")
+        .bind(almacen_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Líneas del conteo con su diferencia e impacto en costo
+pub async fn get_detalle<'e, E: SqliteExecutor<'e>>(executor: E, id: i32) -> Result<Vec<ConteoDetalle>> {
+    sqlx::query_as::<_, ConteoDetalle>(
+        "SELECT *,
+                cantidad_contada - cantidad_esperada AS diferencia,
+                (cantidad_contada - cantidad_esperada) * costo_unitario AS impacto
+         FROM conteo_detalle
+         WHERE id_conteo = ?
+         ORDER BY id_detalle ASC"
+    )
+    .bind(id)
+    .fetch_all(executor)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Conteo en curso del almacén, si lo hay
+pub async fn get_en_curso<'e, E: SqliteExecutor<'e>>(executor: E, almacen_id: i32) -> Result<Option<i32>> {
+    sqlx::query_scalar::<_, i32>(
+        "SELECT id_conteo FROM conteo WHERE id_almacen = ? AND estado IN (?, ?)"
+    )
+    .bind(almacen_id)
+    .bind(ESTADOS_CONTEO_EN_CURSO[0])
+    .bind(ESTADOS_CONTEO_EN_CURSO[1])
+    .fetch_optional(executor)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Falla con `Conflict` si el almacén tiene un conteo en curso: mientras se
+/// cuenta, el stock del almacén no puede cambiar.
+pub async fn verificar_sin_conteo(conn: &mut SqliteConnection, almacen_id: i32) -> Result<()> {
+    if let Some(id) = get_en_curso(conn, almacen_id).await? {
+        return Err(AppError::Conflict(format!(
+            "El almacén tiene el conteo {} en curso; no admite movimientos hasta que se apruebe o cancele",
+            id
+        )));
+    }
+
+    Ok(())
+}
+
+/// Abre un conteo y registra el stock esperado y el costo unitario de cada
+/// ubicación del almacén.
+pub async fn create(pool: &SqlitePool, data: CreateConteo) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    almacen_service::verificar_activo(&mut tx, data.id_almacen).await?;
+    verificar_sin_conteo(&mut tx, data.id_almacen).await?;
+
+    let id = sqlx::query!(
+        "INSERT INTO conteo (id_almacen, fecha, estado, obs) VALUES (?, ?, ?, ?)",
+        data.id_almacen,
+        data.fecha,
+        CONTEO_ABIERTO,
+        data.obs
+    )
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    let stock = sqlx::query_as::<_, StockAlmacen>(
+        "SELECT * FROM stock_almacen WHERE id_almacen = ? ORDER BY id_stock ASC"
+    )
+    .bind(data.id_almacen)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for ubicacion in stock {
+        let costo = costo_unitario(&mut tx, ubicacion.id_prod_prov, ubicacion.id_presentacion, data.id_almacen).await?;
+        sqlx::query!(
+            "INSERT INTO conteo_detalle (id_conteo, id_prod_prov, id_presentacion, cantidad_esperada, costo_unitario)
+             VALUES (?, ?, ?, ?, ?)",
+            id,
+            ubicacion.id_prod_prov,
+            ubicacion.id_presentacion,
+            ubicacion.stock_actual,
+            costo
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+/// Guarda cantidades contadas. Un producto sin stock registrado en el almacén
+/// se agrega al conteo con cantidad esperada cero, en una presentación de ese
+/// producto.
+pub async fn registrar(pool: &SqlitePool, id: i32, lineas: Vec<CantidadContada>) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    let conteo = get_by_id(&mut *tx, id).await?.ok_or(AppError::not_found("el conteo", id))?;
+    if !ESTADOS_CONTEO_EN_CURSO.contains(&conteo.estado.as_str()) {
+        return Err(AppError::Conflict(format!(
+            "El conteo {} está {}; ya no admite cantidades",
+            id,
+            conteo.estado.to_lowercase()
+        )));
+    }
+
+    let mut rows = 0;
+    for (i, linea) in lineas.iter().enumerate() {
+        if !producto_proveedor_service::corresponde_presentacion(&mut tx, linea.id_prod_prov, linea.id_presentacion).await? {
+            return Err(AppError::validation(
+                &format!("lineas[{}].id_presentacion", i),
+                "Debe ser una de las presentaciones del producto",
+            ));
+        }
+        let costo = costo_unitario(&mut tx, linea.id_prod_prov, linea.id_presentacion, conteo.id_almacen).await?;
+        rows += sqlx::query!(
+            "INSERT INTO conteo_detalle (id_conteo, id_prod_prov, id_presentacion, cantidad_esperada,
+                                         cantidad_contada, costo_unitario)
+             VALUES (?, ?, ?, 0, ?, ?)
+             ON CONFLICT (id_conteo, id_prod_prov, id_presentacion)
+             DO UPDATE SET cantidad_contada = excluded.cantidad_contada",
+            id,
+            linea.id_prod_prov,
+            linea.id_presentacion,
+            linea.cantidad_contada,
+            costo
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    sqlx::query!(
+        "UPDATE conteo SET estado = ? WHERE id_conteo = ? AND estado = ?",
+        CONTEO_EN_CONTEO,
+        id,
+        CONTEO_ABIERTO
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(rows)
+}
+
+/// Aprueba el conteo y registra un ajuste `sobrante` o `faltante` por cada
+/// línea con diferencia, de modo que el stock quede igual a lo contado.
+/// Devuelve los ids de los ajustes. Todas las líneas deben estar contadas.
+pub async fn aprobar(pool: &SqlitePool, id: i32) -> Result<Vec<i64>> {
+    let mut tx = pool.begin().await?;
+
+    let conteo = cambiar_estado(&mut tx, id, &[CONTEO_EN_CONTEO], CONTEO_APROBADO).await?;
+    let detalle = get_detalle(&mut *tx, id).await?;
+
+    let pendientes = detalle.iter().filter(|d| d.cantidad_contada.is_none()).count();
+    if pendientes > 0 {
+        return Err(AppError::Conflict(format!(
+            "Faltan {} productos por contar en el conteo {}",
+            pendientes, id
+        )));
+    }
+
+    let mut ajustes = Vec::new();
+    for linea in detalle {
+        let diferencia = linea.diferencia.unwrap_or(0.0);
+        if diferencia.abs() <= EPSILON {
+            continue;
+        }
+
+        let ajuste = CreateMovimiento {
+            fecha: conteo.fecha.clone(),
+            tipo: "ajuste".to_string(),
+            subtipo: Some(if diferencia > 0.0 { SUBTIPO_SOBRANTE } else { SUBTIPO_FALTANTE }.to_string()),
+            id_prod_prov: linea.id_prod_prov,
+            id_presentacion: linea.id_presentacion,
+            cantidad: diferencia.abs(),
+            precio_unit: Some(linea.costo_unitario).filter(|_| diferencia > 0.0),
+            id_almacen: conteo.id_almacen,
+            documento: Some(documento(id)),
+            ..Default::default()
+        };
+        ajustes.push(posting_service::post_in(&mut tx, &ajuste).await?.id_movimiento);
+    }
+
+    tx.commit().await?;
+    Ok(ajustes)
+}
+
+/// Cancela un conteo en curso sin tocar el stock
+pub async fn cancelar(pool: &SqlitePool, id: i32) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    cambiar_estado(&mut tx, id, ESTADOS_CONTEO_EN_CURSO, CONTEO_CANCELADO).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Documento con el que se agrupan los ajustes de un conteo
+pub fn documento(id: i32) -> String {
+    format!("CI-{:06}", id)
+}
+
+/// Pasa el conteo a `hacia` si está en uno de los estados `desde`
+async fn cambiar_estado(
+    conn: &mut SqliteConnection,
+    id: i32,
+    desde: &[&str],
+    hacia: &str,
+) -> Result<Conteo> {
+    let conteo = get_by_id(&mut *conn, id).await?.ok_or(AppError::not_found("el conteo", id))?;
+    if !desde.contains(&conteo.estado.as_str()) {
+        return Err(AppError::Conflict(format!(
+            "El conteo {} está {}; no puede pasar a {}",
+            id,
+            conteo.estado.to_lowercase(),
+            hacia.to_lowercase()
+        )));
+    }
+
+    sqlx::query!("UPDATE conteo SET estado = ? WHERE id_conteo = ?", hacia, id)
+        .execute(conn)
+        .await?;
+
+    Ok(conteo)
+}
+
+/// Costo con el que se valoran las diferencias: promedio del saldo de los
+/// lotes, o el último costo conocido si no queda saldo.
+async fn costo_unitario(
+    conn: &mut SqliteConnection,
+    prod_prov_id: i32,
+    presentacion_id: i32,
+    almacen_id: i32,
+) -> Result<f64> {
+    if let Some(costo) = lote_service::costo_promedio(&mut *conn, prod_prov_id, presentacion_id, almacen_id).await? {
+        return Ok(costo);
+    }
+    let ultimo = lote_service::ultimo_costo(conn, prod_prov_id, presentacion_id, almacen_id).await?;
+
+    Ok(ultimo.unwrap_or(0.0))
+}
@@ -0,0 +1,137 @@
+use sqlx::{SqlitePool, SqliteConnection};
+use crate::error::{AppError, Result};
+use crate::models::conversion::{ConversionRegistrada, CreateConversion, StockProductoBase, SUBTIPO_CONVERSION};
+use crate::models::movimiento::CreateMovimiento;
+use crate::models::presentacion::Presentacion;
+use crate::services::{almacen_service, movimiento_service, posting_service, presentacion_service, producto_proveedor_service};
+
+/// Prefijo de los números de documento de las conversiones
+const PREFIJO: &str = "CV-";
+
+/// Expresa `cantidad` de una presentación en la unidad base del producto
+pub async fn a_unidad_base(pool: &SqlitePool, presentacion_id: i32, cantidad: f64) -> Result<f64> {
+    let mut conn = pool.acquire().await?;
+    let presentacion = presentacion(&mut conn, presentacion_id, "id_presentacion").await?;
+
+    Ok(cantidad * presentacion.cantidad)
+}
+
+/// Expresa `cantidad` de la presentación de origen en la de destino, que debe
+/// ser del mismo producto
+pub async fn convertir(pool: &SqlitePool, origen_id: i32, destino_id: i32, cantidad: f64) -> Result<f64> {
+    let mut conn = pool.acquire().await?;
+    let factor = factor(&mut conn, origen_id, destino_id).await?;
+
+    Ok(cantidad * factor)
+}
+
+/// Stock de cada producto en unidad base, sumando todas sus presentaciones,
+/// en un almacén o en todos. Los productos sin stock figuran con cero; se
+/// excluyen los archivados.
+pub async fn get_stock_base(pool: &SqlitePool, almacen_id: Option<i32>) -> Result<Vec<StockProductoBase>> {
+    sqlx::query_as::<_, StockProductoBase>(
+        "SELECT p.id_producto, p.codigo_interno, p.descripcion,
+                (SELECT b.unidad FROM presentacion b
+                 WHERE b.id_producto = p.id_producto AND b.cantidad = 1
+                 ORDER BY b.id_presentacion ASC
+                 LIMIT 1) AS unidad_base,
+                COALESCE(SUM(s.stock_actual * pr.cantidad), 0.0) AS stock_base
+         FROM producto p
+         LEFT JOIN presentacion pr ON pr.id_producto = p.id_producto
+         LEFT JOIN stock_almacen s ON s.id_presentacion = pr.id_presentacion
+                                  AND (? IS NULL OR s.id_almacen = ?)
+         WHERE p.eliminado_en IS NULL
+         GROUP BY p.id_producto
+         ORDER BY p.id_producto ASC"
+    )
+    .bind(almacen_id)
+    .bind(almacen_id)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Pasa stock de una presentación a otra del mismo producto dentro de un
+/// almacén. Registra una salida y una entrada con el mismo documento; el
+/// costo total se conserva y los lotes pasan a la nueva presentación con su
+/// número y vencimiento.
+pub async fn create(pool: &SqlitePool, data: CreateConversion) -> Result<ConversionRegistrada> {
+    let mut tx = pool.begin().await?;
+
+    almacen_service::verificar_activo(&mut tx, data.id_almacen).await?;
+    let factor = factor(&mut tx, data.id_presentacion_origen, data.id_presentacion_destino).await?;
+    let origen = presentacion(&mut tx, data.id_presentacion_origen, "id_presentacion_origen").await?;
+    let producto_proveedor = producto_proveedor_service::get_by_id(&mut *tx, data.id_prod_prov)
+        .await?
+        .ok_or(AppError::not_found("el producto del proveedor", data.id_prod_prov))?;
+    if producto_proveedor.id_producto != origen.id_producto {
+        return Err(AppError::validation(
+            "id_presentacion_origen",
+            "Debe ser una presentación del producto del proveedor",
+        ));
+    }
+
+    let documento = movimiento_service::siguiente_documento(&mut tx, PREFIJO).await?;
+    let salida = CreateMovimiento {
+        fecha: data.fecha.clone(),
+        tipo: "salida".to_string(),
+        subtipo: Some(SUBTIPO_CONVERSION.to_string()),
+        id_prod_prov: data.id_prod_prov,
+        id_presentacion: data.id_presentacion_origen,
+        cantidad: data.cantidad,
+        obs: data.obs.clone(),
+        id_almacen: data.id_almacen,
+        documento: Some(documento.clone()),
+        ..Default::default()
+    };
+    let registrada = posting_service::post_in(&mut tx, &salida).await?;
+
+    let entrada = CreateMovimiento {
+        tipo: "entrada".to_string(),
+        id_presentacion: data.id_presentacion_destino,
+        ..salida
+    };
+    posting_service::post_recepcion_in(&mut tx, registrada.id_movimiento as i32, entrada, factor).await?;
+
+    tx.commit().await?;
+    Ok(ConversionRegistrada {
+        documento,
+        cantidad_destino: data.cantidad * factor,
+        advertencia: registrada.advertencia,
+    })
+}
+
+/// Anula una conversión. Falla si ya salió mercadería de la presentación de
+/// destino.
+pub async fn delete(pool: &SqlitePool, documento: &str) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+    let rows = posting_service::delete_documento_in(&mut tx, documento, SUBTIPO_CONVERSION).await?;
+    tx.commit().await?;
+
+    Ok(rows)
+}
+
+async fn presentacion(conn: &mut SqliteConnection, id: i32, campo: &str) -> Result<Presentacion> {
+    let presentacion = presentacion_service::get_by_id(conn, id)
+        .await?
+        .ok_or(AppError::not_found("la presentación", id))?;
+    if presentacion.cantidad <= 0.0 {
+        return Err(AppError::validation(campo, "La presentación no tiene un factor de conversión válido"));
+    }
+
+    Ok(presentacion)
+}
+
+/// Unidades de destino que equivalen a una unidad de origen
+async fn factor(conn: &mut SqliteConnection, origen_id: i32, destino_id: i32) -> Result<f64> {
+    let origen = presentacion(&mut *conn, origen_id, "id_presentacion_origen").await?;
+    let destino = presentacion(conn, destino_id, "id_presentacion_destino").await?;
+    if origen.id_producto != destino.id_producto {
+        return Err(AppError::validation(
+            "id_presentacion_destino",
+            "Debe ser una presentación del mismo producto",
+        ));
+    }
+
+    Ok(origen.cantidad / destino.cantidad)
+}
@@ -0,0 +1,67 @@
+use sqlx::SqliteConnection;
+use crate::error::{AppError, Dependiente, Result};
+
+/// Tabla que apunta al registro que se quiere eliminar y condición que
+/// selecciona sus filas, con un único `?` para el id
+pub type Referencia = (&'static str, &'static str);
+
+/// Cuenta, por tabla, las filas que referencian a `id`
+pub async fn contar(conn: &mut SqliteConnection, referencias: &[Referencia], id: i32) -> Result<Vec<Dependiente>> {
+    let mut dependientes = Vec::new();
+
+    for (tabla, condicion) in referencias {
+        // Tabla y condición son constantes del código, nunca datos del usuario
+        let sql = format!("SELECT COUNT(*) FROM {} WHERE {}", tabla, condicion);
+        let cantidad: i64 = sqlx::query_scalar(&sql)
+            .bind(id)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        if cantidad > 0 {
+            dependientes.push(Dependiente {
+                tabla: tabla.to_string(),
+                cantidad,
+            });
+        }
+    }
+
+    Ok(dependientes)
+}
+
+/// Falla con `ForeignKeyViolation`, listando los dependientes, si algún
+/// registro referencia a `id`.
+pub async fn restringir(conn: &mut SqliteConnection, referencias: &[Referencia], id: i32) -> Result<()> {
+    let dependientes = contar(conn, referencias, id).await?;
+    if dependientes.is_empty() {
+        return Ok(());
+    }
+
+    Err(AppError::ForeignKeyViolation {
+        field: None,
+        dependientes,
+    })
+}
+
+/// Antes de purgar, el registro debe estar archivado. Devuelve `false` si no
+/// existe.
+pub async fn verificar_archivado(
+    conn: &mut SqliteConnection,
+    tabla: &'static str,
+    columna_id: &'static str,
+    id: i32,
+) -> Result<bool> {
+    let sql = format!("SELECT eliminado_en FROM {} WHERE {} = ?", tabla, columna_id);
+    let eliminado_en: Option<Option<String>> = sqlx::query_scalar(&sql)
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    match eliminado_en {
+        None => Ok(false),
+        Some(None) => Err(AppError::Conflict(format!(
+            "El registro de {} debe archivarse antes de purgarse",
+            tabla
+        ))),
+        Some(Some(_)) => Ok(true),
+    }
+}
@@ -0,0 +1,156 @@
+use sqlx::{SqlitePool, SqliteExecutor};
+use crate::error::{AppError, Result};
+use crate::models::devolucion::{CreateDevolucion, Devolucion, NotaCredito, SUBTIPO_DEVOLUCION};
+use crate::models::factura::{FACTURA_ACTIVA, FACTURA_PAGADA};
+use crate::models::movimiento::CreateMovimiento;
+use crate::services::{factura_service, lote_service, movimiento_service, pago_service, posting_service};
+
+/// Prefijo de los números de las notas de crédito
+const PREFIJO: &str = "NC-";
+
+/// Tolerancia para comparar cantidades
+const EPSILON: f64 = 1e-9;
+
+pub async fn get_all(pool: &SqlitePool) -> Result<Vec<NotaCredito>> {
+    sqlx::query_as::<_, NotaCredito>("SELECT * FROM nota_credito ORDER BY fecha DESC, id_nota DESC")
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_by_id<'e, E: SqliteExecutor<'e>>(executor: E, id: i32) -> Result<Option<NotaCredito>> {
+    sqlx::query_as::<_, NotaCredito>("SELECT * FROM nota_credito WHERE id_nota = ?")
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_by_factura(pool: &SqlitePool, factura_id: i32) -> Result<Vec<NotaCredito>> {
+    sqlx::query_as::<_, NotaCredito>("SELECT * FROM nota_credito WHERE id_factura = ? ORDER BY id_nota ASC")
+        .bind(factura_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_by_proveedor(pool: &SqlitePool, proveedor_id: i32) -> Result<Vec<NotaCredito>> {
+    sqlx::query_as::<_, NotaCredito>(
+        "SELECT * FROM nota_credito WHERE id_proveedor = ? ORDER BY fecha DESC, id_nota DESC"
+    )
+    .bind(proveedor_id)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Nota de crédito con sus salidas
+pub async fn get_devolucion(pool: &SqlitePool, id: i32) -> Result<Option<Devolucion>> {
+    let Some(nota) = get_by_id(pool, id).await? else {
+        return Ok(None);
+    };
+    let movimientos = movimiento_service::get_by_documento(pool, &nota.numero).await?;
+
+    Ok(Some(Devolucion { nota, movimientos }))
+}
+
+/// Devuelve al proveedor mercadería de una factura activa o pagada. Cada línea
+/// registra una salida `devolucion` del lote original, al costo con el que
+/// entró, y la nota de crédito acredita el total contra la factura.
+pub async fn create(pool: &SqlitePool, data: CreateDevolucion) -> Result<Devolucion> {
+    let mut tx = pool.begin().await?;
+
+    let factura = factura_service::get_factura_by_id(&mut *tx, data.id_factura)
+        .await?
+        .ok_or(AppError::not_found("la factura", data.id_factura))?;
+    let estado = factura.estado.as_deref().unwrap_or(FACTURA_ACTIVA);
+    if estado != FACTURA_ACTIVA && estado != FACTURA_PAGADA {
+        return Err(AppError::Conflict(format!(
+            "La factura {} está {}; no admite devoluciones",
+            factura.numero,
+            estado.to_lowercase()
+        )));
+    }
+
+    let numero = movimiento_service::siguiente_documento(&mut tx, PREFIJO).await?;
+    let mut monto = 0.0;
+
+    for (i, linea) in data.lineas.iter().enumerate() {
+        let lote = lote_service::get_by_id(&mut *tx, linea.id_lote)
+            .await?
+            .ok_or(AppError::not_found("el lote", linea.id_lote))?;
+        let origen: Option<i32> = sqlx::query_scalar(
+            "SELECT id_factura FROM movimiento WHERE id_movimiento = ? AND tipo = 'entrada'"
+        )
+        .bind(lote.id_movimiento)
+        .fetch_optional(&mut *tx)
+        .await?
+        .flatten();
+        if origen != Some(factura.id_factura) {
+            return Err(AppError::validation(
+                &format!("lineas[{}].id_lote", i),
+                "Debe ser un lote recibido con la factura",
+            ));
+        }
+        if linea.cantidad > lote.cantidad_actual + EPSILON {
+            return Err(AppError::validation(
+                &format!("lineas[{}].cantidad", i),
+                format!("Supera lo que queda en el lote ({})", lote.cantidad_actual),
+            ));
+        }
+
+        let salida = CreateMovimiento {
+            fecha: data.fecha.clone(),
+            obs: data.obs.clone(),
+            id_factura: Some(factura.id_factura),
+            documento: Some(numero.clone()),
+            ..Default::default()
+        };
+        let id = posting_service::post_devolucion_in(&mut tx, &lote, linea.cantidad, salida).await?;
+        let movimiento = movimiento_service::get_by_id(&mut *tx, id as i32)
+            .await?
+            .ok_or(AppError::not_found("el movimiento", id))?;
+        monto += movimiento.monto_total.unwrap_or(0.0);
+    }
+
+    let id = sqlx::query!(
+        "INSERT INTO nota_credito (numero, fecha, id_proveedor, id_factura, monto, motivo, obs)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        numero,
+        data.fecha,
+        factura.id_proveedor,
+        factura.id_factura,
+        monto,
+        data.motivo,
+        data.obs
+    )
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    pago_service::actualizar_estado(&mut tx, factura.id_factura).await?;
+
+    let nota = get_by_id(&mut *tx, id as i32).await?.ok_or(AppError::not_found("la nota de crédito", id))?;
+    let movimientos = movimiento_service::get_by_documento(&mut *tx, &numero).await?;
+
+    tx.commit().await?;
+    Ok(Devolucion { nota, movimientos })
+}
+
+/// Elimina la nota de crédito y devuelve a los lotes la mercadería que salió
+/// con ella; la factura recupera el saldo acreditado.
+pub async fn delete(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    let Some(nota) = get_by_id(&mut *tx, id).await? else {
+        return Ok(0);
+    };
+    posting_service::delete_documento_in(&mut tx, &nota.numero, SUBTIPO_DEVOLUCION).await?;
+    let result = sqlx::query!("DELETE FROM nota_credito WHERE id_nota = ?", id)
+        .execute(&mut *tx)
+        .await?;
+    pago_service::actualizar_estado(&mut tx, nota.id_factura).await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
@@ -1,40 +1,400 @@
-use crate::models::factura::{Factura, FacturaInput, FacturaUpdate};
-use sqlx::{SqlitePool, Result};
+use crate::models::factura::{
+    AnularFactura, CreateFacturaConLineas, Factura, FacturaInput, FacturaRegistrada, FacturaTotales, FacturaUpdate,
+    ESTADOS_FACTURA_INICIALES, FACTURA_ACTIVA, FACTURA_ANULADA, FACTURA_BORRADOR, FACTURA_PAGADA,
+};
+use crate::models::movimiento::{Movimiento, TipoMovimiento};
+use crate::models::movimiento::CreateMovimiento;
+use crate::services::dependencia_service::{self, Referencia};
+use crate::services::{almacen_service, pago_service, periodo_service, posting_service, producto_proveedor_service};
+use sqlx::{SqlitePool, SqliteConnection, SqliteExecutor};
+use crate::error::{AppError, Result};
+
+const DEPENDIENTES: &[Referencia] = &[
+    ("movimiento", "id_factura = ?"),
+    ("pago_aplicacion", "id_factura = ?"),
+    ("nota_credito", "id_factura = ?"),
+];
+
+/// Importes de cada factura calculados con sus entradas. Un total impreso que
+/// difiere del calculado en medio centavo o más marca la factura como
+/// descuadrada.
+pub(crate) const TOTALES: &str = "
+    WITH importes AS (
+        SELECT f.id_factura, f.numero, f.total, f.descuento, f.porcentaje_impuesto,
+               COALESCE(SUM(COALESCE(m.monto_total, m.cantidad * m.precio_unit)), 0.0) AS subtotal,
+               COUNT(m.id_movimiento) - COUNT(COALESCE(m.monto_total, m.precio_unit)) AS lineas_sin_importe
+        FROM factura f
+        LEFT JOIN movimiento m ON m.id_factura = f.id_factura AND m.tipo = 'entrada'
+        GROUP BY f.id_factura
+    ),
+    impuestos AS (
+        SELECT *, ROUND((subtotal - descuento) * porcentaje_impuesto / 100.0, 2) AS impuesto
+        FROM importes
+    ),
+    totales AS (
+        SELECT id_factura, numero, subtotal, descuento, impuesto,
+               subtotal - descuento + impuesto AS total_calculado,
+               total AS total_impreso, lineas_sin_importe
+        FROM impuestos
+    )
+    SELECT id_factura, numero, subtotal, descuento, impuesto, total_calculado, total_impreso,
+           total_impreso - total_calculado AS diferencia,
+           COALESCE(ABS(total_impreso - total_calculado) >= 0.005, 0) AS descuadrada,
+           lineas_sin_importe
+    FROM totales";
+
+pub async fn get_facturas(pool: &SqlitePool) -> Result<Vec<Factura>> {
+    sqlx::query_as::<_, Factura>("SELECT * FROM factura ORDER BY id_factura ASC")
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_factura_by_id<'e, E: SqliteExecutor<'e>>(executor: E, id: i32) -> Result<Option<Factura>> {
+    sqlx::query_as::<_, Factura>("SELECT * FROM factura WHERE id_factura = ?")
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_facturas_by_proveedor(pool: &SqlitePool, proveedor_id: i32) -> Result<Vec<Factura>> {
+    sqlx::query_as::<_, Factura>("SELECT * FROM factura WHERE id_proveedor = ? ORDER BY fecha DESC")
+        .bind(proveedor_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
 
 pub async fn create_factura(pool: &SqlitePool, data: FacturaInput) -> Result<i64> {
+    let mut conn = pool.acquire().await?;
+    create_in(&mut conn, &data).await
+}
+
+async fn create_in(conn: &mut SqliteConnection, data: &FacturaInput) -> Result<i64> {
+    if data.estado.as_deref().is_some_and(|e| !ESTADOS_FACTURA_INICIALES.contains(&e)) {
+        return Err(AppError::validation("estado", "Una factura se crea en borrador o activa"));
+    }
+    let estado = data.estado.as_deref().unwrap_or(FACTURA_ACTIVA);
+    periodo_service::verificar_abierto(&mut *conn, &data.fecha).await?;
+
     let result = sqlx::query!(
-        "INSERT INTO factura (numero, fecha, id_proveedor, total, estado)
-         VALUES (?, ?, ?, ?, ?)",
+        "INSERT INTO factura (numero, fecha, id_proveedor, total, estado, descuento, porcentaje_impuesto,
+                              fecha_vencimiento)
+         VALUES (?, ?, ?, ?, ?, COALESCE(?, 0), COALESCE(?, 0),
+                 (SELECT date(?, '+' || dias_credito || ' days') FROM proveedor WHERE id_proveedor = ?))",
         data.numero,
         data.fecha,
         data.id_proveedor,
         data.total,
-        data.estado
+        estado,
+        data.descuento,
+        data.porcentaje_impuesto,
+        data.fecha,
+        data.id_proveedor
     )
-        .execute(pool)
+        .execute(conn)
         .await?;
 
     Ok(result.last_insert_rowid())
 }
 
+/// Registra la factura y una entrada por cada línea en una sola transacción.
+/// Cada producto debe ser del proveedor de la factura.
+pub async fn create_con_lineas(pool: &SqlitePool, data: CreateFacturaConLineas) -> Result<FacturaRegistrada> {
+    let mut tx = pool.begin().await?;
+
+    almacen_service::verificar_activo(&mut tx, data.id_almacen).await?;
+    let id = create_in(&mut tx, &data.factura).await?;
+
+    let mut movimientos = Vec::new();
+    for (i, linea) in data.lineas.iter().enumerate() {
+        let corresponde = producto_proveedor_service::corresponde(
+            &mut tx,
+            linea.id_prod_prov,
+            data.factura.id_proveedor,
+            linea.id_presentacion,
+        )
+        .await?;
+        if !corresponde {
+            return Err(AppError::validation(
+                &format!("lineas[{}].id_prod_prov", i),
+                "Debe ser un producto del proveedor de la factura, en una de sus presentaciones",
+            ));
+        }
+
+        let entrada = CreateMovimiento {
+            fecha: data.factura.fecha.clone(),
+            tipo: "entrada".to_string(),
+            id_prod_prov: linea.id_prod_prov,
+            id_presentacion: linea.id_presentacion,
+            cantidad: linea.cantidad,
+            precio_unit: Some(linea.precio_unit),
+            lote: linea.lote.clone(),
+            fecha_venc: linea.fecha_venc.clone(),
+            obs: linea.obs.clone(),
+            id_factura: Some(id as i32),
+            id_almacen: data.id_almacen,
+            ..Default::default()
+        };
+        movimientos.push(posting_service::post_in(&mut tx, &entrada).await?.id_movimiento);
+    }
+
+    let totales = get_totales(&mut *tx, id as i32)
+        .await?
+        .ok_or(AppError::not_found("la factura", id))?;
+
+    tx.commit().await?;
+    Ok(FacturaRegistrada { id_factura: id, movimientos, totales })
+}
+
+pub async fn get_totales<'e, E: SqliteExecutor<'e>>(executor: E, id: i32) -> Result<Option<FacturaTotales>> {
+    let sql = format!("SELECT * FROM ({}) WHERE id_factura = ?", TOTALES);
+    sqlx::query_as::<_, FacturaTotales>(&sql)
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Facturas cuyo total impreso no coincide con el calculado
+pub async fn get_descuadradas(pool: &SqlitePool) -> Result<Vec<FacturaTotales>> {
+    let sql = format!(
+        "SELECT * FROM ({}) WHERE descuadrada
+           AND id_factura NOT IN (SELECT id_factura FROM factura WHERE estado = ?)
+         ORDER BY id_factura ASC",
+        TOTALES
+    );
+    sqlx::query_as::<_, FacturaTotales>(&sql)
+        .bind(FACTURA_ANULADA)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Las facturas pagadas o anuladas no admiten cambios, y el estado solo pasa
+/// de borrador a activa. Pagada la marcan los pagos: si cambia el importe se
+/// vuelve a comparar con lo pagado.
 pub async fn update_factura(pool: &SqlitePool, id: i32, data: FacturaUpdate) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    let Some(factura) = get_factura_by_id(&mut *tx, id).await? else {
+        return Ok(0);
+    };
+    let actual = estado(&factura);
+    if actual == FACTURA_PAGADA || actual == FACTURA_ANULADA {
+        return Err(AppError::Conflict(format!(
+            "La factura {} está {}; no admite cambios",
+            factura.numero,
+            actual.to_lowercase()
+        )));
+    }
+    periodo_service::verificar_abierto(&mut tx, &factura.fecha).await?;
+    if let Some(fecha) = &data.fecha {
+        periodo_service::verificar_abierto(&mut tx, fecha).await?;
+    }
+    if let Some(nuevo) = data.estado.as_deref().filter(|nuevo| *nuevo != actual) {
+        if nuevo == FACTURA_ANULADA {
+            return Err(AppError::Conflict(
+                "La anulación revierte los movimientos de la factura; debe hacerse con su propia operación".to_string(),
+            ));
+        }
+        if nuevo == FACTURA_PAGADA {
+            return Err(AppError::Conflict(
+                "Una factura queda pagada cuando sus pagos cubren el total; no se marca a mano".to_string(),
+            ));
+        }
+        verificar_transicion(&factura, nuevo)?;
+    }
+
     let result = sqlx::query!(
         "UPDATE factura SET
             numero = COALESCE(?, numero),
             fecha = COALESCE(?, fecha),
             id_proveedor = COALESCE(?, id_proveedor),
             total = COALESCE(?, total),
-            estado = COALESCE(?, estado)
+            estado = COALESCE(?, estado),
+            descuento = COALESCE(?, descuento),
+            porcentaje_impuesto = COALESCE(?, porcentaje_impuesto)
          WHERE id_factura = ?",
         data.numero,
         data.fecha,
         data.id_proveedor,
         data.total,
         data.estado,
+        data.descuento,
+        data.porcentaje_impuesto,
         id
     )
-        .execute(pool)
+        .execute(&mut *tx)
+        .await?;
+
+    if data.fecha.is_some() || data.id_proveedor.is_some() {
+        sqlx::query!(
+            "UPDATE factura SET fecha_vencimiento = (
+                SELECT date(factura.fecha, '+' || p.dias_credito || ' days')
+                FROM proveedor p WHERE p.id_proveedor = factura.id_proveedor
+             )
+             WHERE id_factura = ?",
+            id
+        )
+        .execute(&mut *tx)
         .await?;
+    }
+    if data.total.is_some() || data.descuento.is_some() || data.porcentaje_impuesto.is_some() {
+        pago_service::actualizar_estado(&mut tx, id).await?;
+    }
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// Pasa una factura en borrador a activa
+pub async fn activar(pool: &SqlitePool, id: i32) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    let factura = get_factura_by_id(&mut *tx, id).await?.ok_or(AppError::not_found("la factura", id))?;
+    verificar_transicion(&factura, FACTURA_ACTIVA)?;
+    periodo_service::verificar_abierto(&mut tx, &factura.fecha).await?;
+    cambiar_estado(&mut tx, id, FACTURA_ACTIVA).await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Anula una factura activa y revierte cada una de sus entradas con una
+/// salida `anulacion` que toma los mismos lotes. Falla si de alguno ya salió
+/// mercadería. Devuelve los ids de las salidas.
+pub async fn anular(pool: &SqlitePool, id: i32, data: AnularFactura) -> Result<Vec<i64>> {
+    let mut tx = pool.begin().await?;
+
+    let factura = get_factura_by_id(&mut *tx, id).await?.ok_or(AppError::not_found("la factura", id))?;
+    verificar_transicion(&factura, FACTURA_ANULADA)?;
+    periodo_service::verificar_abierto(&mut tx, &factura.fecha).await?;
+    let pagos: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pago_aplicacion WHERE id_factura = ?")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+    if pagos > 0 {
+        return Err(AppError::Conflict(format!(
+            "La factura {} tiene pagos aplicados; deben eliminarse antes de anularla",
+            factura.numero
+        )));
+    }
+    let notas: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM nota_credito WHERE id_factura = ?")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+    if notas > 0 {
+        return Err(AppError::Conflict(format!(
+            "La factura {} tiene devoluciones registradas; deben eliminarse antes de anularla",
+            factura.numero
+        )));
+    }
+
+    let movimientos = sqlx::query_as::<_, Movimiento>(
+        "SELECT * FROM movimiento WHERE id_factura = ? ORDER BY id_movimiento ASC"
+    )
+    .bind(id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let documento = documento_anulacion(id);
+    let mut reversos = Vec::new();
+    for movimiento in &movimientos {
+        if TipoMovimiento::parse(&movimiento.tipo) != Some(TipoMovimiento::Entrada) {
+            return Err(AppError::Conflict(format!(
+                "El movimiento {} de la factura no es una entrada; debe revertirse antes de anularla",
+                movimiento.id_movimiento
+            )));
+        }
+        let reverso = posting_service::post_reverso_in(&mut tx, movimiento, &data.fecha, &documento, data.obs.clone()).await?;
+        reversos.push(reverso);
+    }
+
+    cambiar_estado(&mut tx, id, FACTURA_ANULADA).await?;
+
+    tx.commit().await?;
+    Ok(reversos)
+}
+
+/// Documento con el que se agrupan las salidas que revierten una factura
+pub fn documento_anulacion(id: i32) -> String {
+    format!("AN-{:06}", id)
+}
+
+/// Falla con `Conflict` si la factura está anulada: sus movimientos ya se
+/// revirtieron y no se le pueden asociar otros.
+pub(crate) async fn verificar_no_anulada(conn: &mut SqliteConnection, id: Option<i32>) -> Result<()> {
+    let Some(id) = id else {
+        return Ok(());
+    };
+    if let Some(factura) = get_factura_by_id(conn, id).await? {
+        if estado(&factura) == FACTURA_ANULADA {
+            return Err(AppError::Conflict(format!(
+                "La factura {} está anulada; sus movimientos no admiten cambios",
+                factura.numero
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Las facturas anteriores a los estados se consideran activas
+fn estado(factura: &Factura) -> &str {
+    factura.estado.as_deref().unwrap_or(FACTURA_ACTIVA)
+}
+
+fn verificar_transicion(factura: &Factura, nuevo: &str) -> Result<()> {
+    let actual = estado(factura);
+    let permitida = matches!(
+        (actual, nuevo),
+        (FACTURA_BORRADOR, FACTURA_ACTIVA) | (FACTURA_ACTIVA, FACTURA_ANULADA)
+    );
+    if !permitida {
+        return Err(AppError::Conflict(format!(
+            "La factura {} está {}; no puede pasar a {}",
+            factura.numero,
+            actual.to_lowercase(),
+            nuevo.to_lowercase()
+        )));
+    }
+
+    Ok(())
+}
+
+async fn cambiar_estado(conn: &mut SqliteConnection, id: i32, estado: &str) -> Result<()> {
+    sqlx::query!("UPDATE factura SET estado = ? WHERE id_factura = ?", estado, id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Solo se eliminan facturas en borrador o activas, y se rechaza si tienen
+/// movimientos asociados.
+pub async fn delete_factura(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    if let Some(factura) = get_factura_by_id(&mut *tx, id).await? {
+        let actual = estado(&factura);
+        if actual == FACTURA_PAGADA || actual == FACTURA_ANULADA {
+            return Err(AppError::Conflict(format!(
+                "La factura {} está {}; no se puede eliminar",
+                factura.numero,
+                actual.to_lowercase()
+            )));
+        }
+        periodo_service::verificar_abierto(&mut tx, &factura.fecha).await?;
+    }
+    dependencia_service::restringir(&mut tx, DEPENDIENTES, id).await?;
+
+    let result = sqlx::query!("DELETE FROM factura WHERE id_factura = ?", id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
 
     Ok(result.rows_affected())
 }
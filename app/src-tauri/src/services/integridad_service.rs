@@ -0,0 +1,177 @@
+use sqlx::{Connection, SqlitePool, SqliteConnection};
+use crate::error::{AppError, Result};
+use crate::models::conteo::{SUBTIPO_FALTANTE, SUBTIPO_SOBRANTE};
+use crate::models::integridad::{DiferenciaStock, RegistroHuerfano, ReporteIntegridad, UbicacionOmitida};
+use crate::models::movimiento::CreateMovimiento;
+use crate::services::{movimiento_service, posting_service, stock_almacen_service};
+
+/// Prefijo de los documentos de los ajustes de reparación
+const PREFIJO: &str = "IN-";
+
+/// Compara el stock registrado de cada ubicación con el que resulta de sus
+/// movimientos y busca filas que apuntan a registros inexistentes. No
+/// modifica nada.
+pub async fn verificar(pool: &SqlitePool) -> Result<ReporteIntegridad> {
+    let mut conn = pool.acquire().await?;
+
+    Ok(ReporteIntegridad {
+        diferencias: diferencias(&mut conn).await?,
+        huerfanos: huerfanos(&mut conn).await?,
+        ajustes: Vec::new(),
+        omitidas: Vec::new(),
+    })
+}
+
+/// Registra un ajuste `sobrante` o `faltante` por cada diferencia, para que
+/// los movimientos expliquen el stock registrado sin cambiarlo. Las
+/// ubicaciones con registros huérfanos no se reparan; quedan en el reporte.
+/// Las que no admiten el ajuste se omiten sin deshacer las demás.
+pub async fn reparar(pool: &SqlitePool, fecha: &str) -> Result<ReporteIntegridad> {
+    let mut tx = pool.begin().await?;
+
+    let diferencias = diferencias(&mut tx).await?;
+    let huerfanos = huerfanos(&mut tx).await?;
+    let documento = movimiento_service::siguiente_documento(&mut tx, PREFIJO).await?;
+    let mut ajustes = Vec::new();
+    let mut omitidas = Vec::new();
+
+    for diferencia in &diferencias {
+        let huerfana = huerfanos.iter().any(|h| {
+            h.id_prod_prov == diferencia.id_prod_prov
+                && h.id_presentacion == diferencia.id_presentacion
+                && (h.id_almacen.is_none() || h.id_almacen == Some(diferencia.id_almacen))
+        });
+        if huerfana {
+            continue;
+        }
+
+        // Cada ubicación se ajusta en su propio punto de guardado
+        let mut ubicacion = tx.begin().await?;
+        match ajustar(&mut ubicacion, diferencia, fecha, &documento).await {
+            Ok(id) => {
+                ubicacion.commit().await?;
+                ajustes.push(id);
+            }
+            Err(error @ (AppError::Conflict(_) | AppError::InsufficientStock(_))) => {
+                ubicacion.rollback().await?;
+                omitidas.push(UbicacionOmitida {
+                    id_prod_prov: diferencia.id_prod_prov,
+                    id_presentacion: diferencia.id_presentacion,
+                    id_almacen: diferencia.id_almacen,
+                    motivo: error.to_string(),
+                });
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    tx.commit().await?;
+    Ok(ReporteIntegridad {
+        diferencias,
+        huerfanos,
+        ajustes,
+        omitidas,
+    })
+}
+
+/// Registra el ajuste que explica una diferencia y devuelve su id
+async fn ajustar(
+    conn: &mut SqliteConnection,
+    diferencia: &DiferenciaStock,
+    fecha: &str,
+    documento: &str,
+) -> Result<i64> {
+    // El ajuste vuelve a sumar la diferencia al stock, con sus lotes
+    stock_almacen_service::ajustar(
+        &mut *conn,
+        diferencia.id_prod_prov,
+        diferencia.id_presentacion,
+        diferencia.id_almacen,
+        -diferencia.diferencia,
+    )
+    .await?;
+    let subtipo = if diferencia.diferencia > 0.0 { SUBTIPO_SOBRANTE } else { SUBTIPO_FALTANTE };
+    let ajuste = CreateMovimiento {
+        fecha: fecha.to_string(),
+        tipo: "ajuste".to_string(),
+        subtipo: Some(subtipo.to_string()),
+        id_prod_prov: diferencia.id_prod_prov,
+        id_presentacion: diferencia.id_presentacion,
+        cantidad: diferencia.diferencia.abs(),
+        obs: Some("Ajuste de reparación: stock registrado sin movimientos".to_string()),
+        id_almacen: diferencia.id_almacen,
+        documento: Some(documento.to_string()),
+        ..Default::default()
+    };
+
+    Ok(posting_service::post_in(conn, &ajuste).await?.id_movimiento)
+}
+
+async fn diferencias(conn: &mut SqliteConnection) -> Result<Vec<DiferenciaStock>> {
+    let sql = format!(
+        "WITH esperado AS (
+            SELECT m.id_prod_prov, m.id_presentacion, m.id_almacen, SUM({}) AS cantidad
+            FROM movimiento m
+            WHERE m.id_almacen IS NOT NULL
+            GROUP BY m.id_prod_prov, m.id_presentacion, m.id_almacen
+        ),
+        registrado AS (
+            SELECT id_prod_prov, id_presentacion, id_almacen, SUM(stock_actual) AS cantidad
+            FROM stock_almacen
+            GROUP BY id_prod_prov, id_presentacion, id_almacen
+        ),
+        ubicaciones AS (
+            SELECT id_prod_prov, id_presentacion, id_almacen FROM esperado
+            UNION
+            SELECT id_prod_prov, id_presentacion, id_almacen FROM registrado
+        ),
+        comparacion AS (
+            SELECT u.id_prod_prov, u.id_presentacion, u.id_almacen,
+                   COALESCE(r.cantidad, 0.0) AS registrado, COALESCE(e.cantidad, 0.0) AS esperado
+            FROM ubicaciones u
+            LEFT JOIN registrado r ON r.id_prod_prov = u.id_prod_prov AND r.id_presentacion = u.id_presentacion
+                                  AND r.id_almacen = u.id_almacen
+            LEFT JOIN esperado e ON e.id_prod_prov = u.id_prod_prov AND e.id_presentacion = u.id_presentacion
+                                AND e.id_almacen = u.id_almacen
+        )
+        SELECT *, registrado - esperado AS diferencia
+        FROM comparacion
+        WHERE ABS(registrado - esperado) > 1e-9
+        ORDER BY id_almacen ASC, id_prod_prov ASC, id_presentacion ASC",
+        movimiento_service::CANTIDAD_CON_SIGNO
+    );
+    let diferencias = sqlx::query_as::<_, DiferenciaStock>(&sql).fetch_all(conn).await?;
+
+    Ok(diferencias)
+}
+
+async fn huerfanos(conn: &mut SqliteConnection) -> Result<Vec<RegistroHuerfano>> {
+    let huerfanos = sqlx::query_as::<_, RegistroHuerfano>(
+        "WITH referencias AS (
+            SELECT 'stock_almacen' AS tabla, id_stock AS id_registro, id_prod_prov, id_presentacion, id_almacen
+            FROM stock_almacen
+            UNION ALL
+            SELECT 'movimiento', id_movimiento, id_prod_prov, id_presentacion, id_almacen
+            FROM movimiento
+        ),
+        revisadas AS (
+            SELECT r.*,
+                   CASE WHEN pp.id_prod_prov IS NULL THEN 'producto_proveedor_inexistente'
+                        WHEN pr.id_presentacion IS NULL THEN 'presentacion_inexistente'
+                        WHEN r.id_almacen IS NOT NULL AND a.id_almacen IS NULL THEN 'almacen_inexistente'
+                        WHEN pr.id_producto <> pp.id_producto THEN 'presentacion_de_otro_producto'
+                   END AS problema
+            FROM referencias r
+            LEFT JOIN producto_proveedor pp ON pp.id_prod_prov = r.id_prod_prov
+            LEFT JOIN presentacion pr ON pr.id_presentacion = r.id_presentacion
+            LEFT JOIN almacen a ON a.id_almacen = r.id_almacen
+        )
+        SELECT * FROM revisadas
+        WHERE problema IS NOT NULL
+        ORDER BY tabla DESC, id_registro ASC"
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(huerfanos)
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use sqlx::SqlitePool;
+use crate::error::{AppError, Result};
+use crate::models::kardex::{FiltroKardex, Kardex, LineaKardex, SaldoKardex};
+use crate::models::movimiento::{Movimiento, TipoMovimiento};
+use crate::services::{movimiento_service, presentacion_service, producto_proveedor_service};
+
+/// Kardex valorizado de un producto del proveedor: el saldo al inicio del
+/// rango, cada movimiento con su saldo acumulado en cantidad y valor, y el
+/// saldo al final. Solo cuentan los movimientos con almacén, que son los que
+/// afectan el stock.
+///
+/// Las entradas valen lo que costaron los lotes que abrieron y las salidas el
+/// costo PEPS con que se registraron.
+pub async fn get_kardex(pool: &SqlitePool, filtro: FiltroKardex) -> Result<Kardex> {
+    let prod_prov = producto_proveedor_service::get_by_id(pool, filtro.id_prod_prov)
+        .await?
+        .ok_or(AppError::not_found("el producto del proveedor", filtro.id_prod_prov))?;
+
+    // Sin presentación se convierte todo a unidad base
+    let presentaciones = presentacion_service::get_by_producto(pool, prod_prov.id_producto).await?;
+    if let Some(id) = filtro.id_presentacion {
+        if !presentaciones.iter().any(|p| p.id_presentacion == id) {
+            return Err(AppError::validation("id_presentacion", "Debe ser una presentación del producto"));
+        }
+    }
+    let factores: HashMap<i32, f64> = presentaciones
+        .iter()
+        .map(|p| (p.id_presentacion, if filtro.id_presentacion.is_some() { 1.0 } else { p.cantidad }))
+        .collect();
+
+    let costos = costos_lotes(pool, filtro.id_prod_prov).await?;
+
+    let mut movimientos: Vec<Movimiento> = movimiento_service::get_by_producto_proveedor(pool, filtro.id_prod_prov)
+        .await?
+        .into_iter()
+        .filter(|m| m.id_almacen.is_some() && (filtro.id_almacen.is_none() || m.id_almacen == filtro.id_almacen))
+        .filter(|m| filtro.id_presentacion.is_none() || filtro.id_presentacion == Some(m.id_presentacion))
+        .collect();
+    movimientos.sort_by(|a, b| a.fecha.cmp(&b.fecha).then(a.id_movimiento.cmp(&b.id_movimiento)));
+
+    let mut saldo_inicial = SaldoKardex::default();
+    let mut saldo = SaldoKardex::default();
+    let mut lineas = Vec::new();
+
+    for movimiento in movimientos {
+        if filtro.hasta.as_ref().is_some_and(|hasta| movimiento.fecha.as_str() > hasta.as_str()) {
+            break;
+        }
+        let Some(tipo) = TipoMovimiento::parse(&movimiento.tipo) else {
+            continue;
+        };
+        let signo = tipo.signo(movimiento.subtipo.as_deref());
+        let cantidad = movimiento.cantidad * factores.get(&movimiento.id_presentacion).copied().unwrap_or(1.0);
+        let valor = valor(&movimiento, signo, &costos);
+
+        saldo.cantidad += signo * cantidad;
+        saldo.valor += signo * valor;
+
+        if filtro.desde.as_ref().is_some_and(|desde| movimiento.fecha.as_str() < desde.as_str()) {
+            saldo_inicial = saldo;
+            continue;
+        }
+
+        lineas.push(LineaKardex {
+            id_movimiento: movimiento.id_movimiento,
+            fecha: movimiento.fecha,
+            tipo: movimiento.tipo,
+            subtipo: movimiento.subtipo,
+            documento: movimiento.documento,
+            id_almacen: movimiento.id_almacen.unwrap_or_default(),
+            id_presentacion: movimiento.id_presentacion,
+            entrada: if signo > 0.0 { cantidad } else { 0.0 },
+            salida: if signo < 0.0 { cantidad } else { 0.0 },
+            costo_unitario: (valor != 0.0 && cantidad > 0.0).then(|| valor / cantidad),
+            valor,
+            saldo,
+        });
+    }
+
+    Ok(Kardex {
+        filtro,
+        saldo_inicial,
+        lineas,
+        saldo_final: saldo,
+    })
+}
+
+/// Costo total de los lotes que abrió cada movimiento del producto
+async fn costos_lotes(pool: &SqlitePool, prod_prov_id: i32) -> Result<HashMap<i32, f64>> {
+    let filas: Vec<(i32, f64)> = sqlx::query_as(
+        "SELECT id_movimiento, SUM(cantidad_inicial * costo_unitario) FROM lote
+         WHERE id_prod_prov = ? GROUP BY id_movimiento"
+    )
+    .bind(prod_prov_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(filas.into_iter().collect())
+}
+
+/// Valor del movimiento, sin signo
+fn valor(movimiento: &Movimiento, signo: f64, costos: &HashMap<i32, f64>) -> f64 {
+    let lotes = if signo > 0.0 { costos.get(&movimiento.id_movimiento).copied() } else { None };
+    lotes
+        .or(movimiento.monto_total)
+        .or(movimiento.precio_unit.map(|precio| precio * movimiento.cantidad))
+        .unwrap_or(0.0)
+}
@@ -0,0 +1,293 @@
+use sqlx::{SqlitePool, SqliteConnection, SqliteExecutor};
+use crate::error::{AppError, Result};
+use crate::models::configuracion::EstrategiaSalida;
+use crate::models::lote::{Lote, LoteConsumo};
+use crate::models::movimiento::Movimiento;
+use crate::services::configuracion_service;
+
+/// Tolerancia para considerar agotado un lote
+const EPSILON: f64 = 1e-9;
+
+pub async fn get_all(pool: &SqlitePool) -> Result<Vec<Lote>> {
+    sqlx::query_as::<_, Lote>("SELECT * FROM lote ORDER BY id_lote ASC")
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_by_id<'e, E: SqliteExecutor<'e>>(executor: E, id: i32) -> Result<Option<Lote>> {
+    sqlx::query_as::<_, Lote>("SELECT * FROM lote WHERE id_lote = ?")
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_by_producto_proveedor(pool: &SqlitePool, prod_prov_id: i32) -> Result<Vec<Lote>> {
+    sqlx::query_as::<_, Lote>(
+        "SELECT * FROM lote WHERE id_prod_prov = ? ORDER BY fecha_entrada ASC, id_lote ASC"
+    )
+    .bind(prod_prov_id)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Lotes con saldo de una ubicación, en orden PEPS.
+pub async fn get_disponibles<'e, E: SqliteExecutor<'e>>(
+    executor: E,
+    prod_prov_id: i32,
+    presentacion_id: i32,
+    almacen_id: i32,
+) -> Result<Vec<Lote>> {
+    sqlx::query_as::<_, Lote>(
+        "SELECT * FROM lote
+         WHERE id_prod_prov = ? AND id_presentacion = ? AND id_almacen = ? AND cantidad_actual > 0
+         ORDER BY fecha_entrada ASC, id_lote ASC"
+    )
+    .bind(prod_prov_id)
+    .bind(presentacion_id)
+    .bind(almacen_id)
+    .fetch_all(executor)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Lotes abiertos por un movimiento de entrada.
+pub async fn get_by_movimiento(conn: &mut SqliteConnection, movimiento_id: i32) -> Result<Vec<Lote>> {
+    sqlx::query_as::<_, Lote>("SELECT * FROM lote WHERE id_movimiento = ? ORDER BY id_lote ASC")
+        .bind(movimiento_id)
+        .fetch_all(conn)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Lotes de los que tomó una salida, con la cantidad y el costo aplicados.
+pub async fn get_consumos_by_movimiento<'e, E: SqliteExecutor<'e>>(
+    executor: E,
+    movimiento_id: i32,
+) -> Result<Vec<LoteConsumo>> {
+    sqlx::query_as::<_, LoteConsumo>(
+        "SELECT * FROM lote_consumo WHERE id_movimiento = ? ORDER BY id_consumo ASC"
+    )
+    .bind(movimiento_id)
+    .fetch_all(executor)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Costo unitario del lote más reciente de la ubicación, agotado o no.
+pub async fn ultimo_costo(
+    conn: &mut SqliteConnection,
+    prod_prov_id: i32,
+    presentacion_id: i32,
+    almacen_id: i32,
+) -> Result<Option<f64>> {
+    sqlx::query_scalar::<_, f64>(
+        "SELECT costo_unitario FROM lote
+         WHERE id_prod_prov = ? AND id_presentacion = ? AND id_almacen = ?
+         ORDER BY fecha_entrada DESC, id_lote DESC
+         LIMIT 1"
+    )
+    .bind(prod_prov_id)
+    .bind(presentacion_id)
+    .bind(almacen_id)
+    .fetch_optional(conn)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Costo promedio ponderado del saldo de los lotes de la ubicación; nulo si
+/// no queda saldo.
+pub async fn costo_promedio(
+    conn: &mut SqliteConnection,
+    prod_prov_id: i32,
+    presentacion_id: i32,
+    almacen_id: i32,
+) -> Result<Option<f64>> {
+    sqlx::query_scalar::<_, Option<f64>>(
+        "SELECT SUM(cantidad_actual * costo_unitario) / SUM(cantidad_actual) FROM lote
+         WHERE id_prod_prov = ? AND id_presentacion = ? AND id_almacen = ? AND cantidad_actual > 0"
+    )
+    .bind(prod_prov_id)
+    .bind(presentacion_id)
+    .bind(almacen_id)
+    .fetch_one(conn)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Abre un lote con la cantidad completa de un movimiento que suma stock.
+pub async fn abrir(
+    conn: &mut SqliteConnection,
+    movimiento: &Movimiento,
+    almacen_id: i32,
+    costo_unitario: f64,
+) -> Result<i64> {
+    let result = sqlx::query!(
+        "INSERT INTO lote (id_movimiento, id_prod_prov, id_presentacion, id_almacen, numero_lote,
+                           fecha_entrada, fecha_venc, cantidad_inicial, cantidad_actual, costo_unitario)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        movimiento.id_movimiento,
+        movimiento.id_prod_prov,
+        movimiento.id_presentacion,
+        almacen_id,
+        movimiento.lote,
+        movimiento.fecha,
+        movimiento.fecha_venc,
+        movimiento.cantidad,
+        movimiento.cantidad,
+        costo_unitario
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// Abre, para la entrada de una transferencia o conversión, un lote con parte
+/// de `origen` en otra ubicación, conservando su número y fechas.
+pub async fn trasladar(
+    conn: &mut SqliteConnection,
+    origen: &Lote,
+    movimiento_id: i32,
+    presentacion_id: i32,
+    almacen_id: i32,
+    cantidad: f64,
+    costo_unitario: f64,
+) -> Result<i64> {
+    let result = sqlx::query!(
+        "INSERT INTO lote (id_movimiento, id_prod_prov, id_presentacion, id_almacen, numero_lote,
+                           fecha_entrada, fecha_venc, cantidad_inicial, cantidad_actual, costo_unitario)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        movimiento_id,
+        origen.id_prod_prov,
+        presentacion_id,
+        almacen_id,
+        origen.numero_lote,
+        origen.fecha_entrada,
+        origen.fecha_venc,
+        cantidad,
+        cantidad,
+        costo_unitario
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// Consume la cantidad del movimiento desde los lotes más antiguos, o desde
+/// los que vencen antes si el almacén usa FEFO, y devuelve su costo total. Lo
+/// que no cubran los lotes se costea al último costo conocido de la ubicación.
+pub async fn consumir(conn: &mut SqliteConnection, movimiento: &Movimiento, almacen_id: i32) -> Result<f64> {
+    let mut lotes = get_disponibles(
+        &mut *conn,
+        movimiento.id_prod_prov,
+        movimiento.id_presentacion,
+        almacen_id,
+    )
+    .await?;
+    if configuracion_service::get_estrategia_salida(&mut *conn, Some(almacen_id)).await? == EstrategiaSalida::Fefo {
+        // El orden es estable: a igual vencimiento se mantiene el PEPS
+        lotes.sort_by_key(|l| (l.fecha_venc.is_none(), l.fecha_venc.clone()));
+    }
+
+    let mut pendiente = movimiento.cantidad;
+    let mut costo_total = 0.0;
+
+    for lote in lotes {
+        if pendiente <= EPSILON {
+            break;
+        }
+        let tomado = pendiente.min(lote.cantidad_actual);
+        tomar(&mut *conn, movimiento.id_movimiento, &lote, tomado).await?;
+
+        costo_total += tomado * lote.costo_unitario;
+        pendiente -= tomado;
+    }
+
+    if pendiente > EPSILON {
+        let costo = ultimo_costo(
+            &mut *conn,
+            movimiento.id_prod_prov,
+            movimiento.id_presentacion,
+            almacen_id,
+        )
+        .await?
+        .unwrap_or(0.0);
+        costo_total += pendiente * costo;
+    }
+
+    Ok(costo_total)
+}
+
+/// Consume todo el saldo de los lotes dados para la salida `movimiento_id`,
+/// sin seguir el orden PEPS, y devuelve su costo total.
+pub async fn vaciar(conn: &mut SqliteConnection, movimiento_id: i32, lotes: &[Lote]) -> Result<f64> {
+    let mut costo_total = 0.0;
+    for lote in lotes {
+        tomar(&mut *conn, movimiento_id, lote, lote.cantidad_actual).await?;
+        costo_total += lote.cantidad_actual * lote.costo_unitario;
+    }
+
+    Ok(costo_total)
+}
+
+/// Consume `cantidad` de un lote dado para la salida `movimiento_id`, sin
+/// seguir el orden PEPS, y devuelve su costo al costo original del lote.
+pub async fn retirar(conn: &mut SqliteConnection, movimiento_id: i32, lote: &Lote, cantidad: f64) -> Result<f64> {
+    tomar(conn, movimiento_id, lote, cantidad).await?;
+
+    Ok(cantidad * lote.costo_unitario)
+}
+
+/// Registra que la salida `movimiento_id` tomó `cantidad` del lote
+async fn tomar(conn: &mut SqliteConnection, movimiento_id: i32, lote: &Lote, cantidad: f64) -> Result<()> {
+    sqlx::query!(
+        "INSERT INTO lote_consumo (id_movimiento, id_lote, cantidad, costo_unitario)
+         VALUES (?, ?, ?, ?)",
+        movimiento_id,
+        lote.id_lote,
+        cantidad,
+        lote.costo_unitario
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE lote SET cantidad_actual = cantidad_actual - ? WHERE id_lote = ?",
+        cantidad,
+        lote.id_lote
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Deshace el efecto de un movimiento sobre los lotes: devuelve a cada lote lo
+/// que consumió y elimina los lotes que abrió.
+pub async fn revertir(conn: &mut SqliteConnection, movimiento_id: i32) -> Result<()> {
+    sqlx::query!(
+        "UPDATE lote SET cantidad_actual = cantidad_actual + (
+            SELECT SUM(c.cantidad) FROM lote_consumo c
+            WHERE c.id_lote = lote.id_lote AND c.id_movimiento = ?
+         )
+         WHERE id_lote IN (SELECT id_lote FROM lote_consumo WHERE id_movimiento = ?)",
+        movimiento_id,
+        movimiento_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!("DELETE FROM lote_consumo WHERE id_movimiento = ?", movimiento_id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query!("DELETE FROM lote WHERE id_movimiento = ?", movimiento_id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
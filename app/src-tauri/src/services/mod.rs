@@ -6,6 +6,25 @@ pub mod presentacion_service;
 pub mod producto_proveedor_service;
 pub mod movimiento_service;
 pub mod stock_almacen_service;
+pub mod posting_service;
+pub mod lote_service;
+pub mod configuracion_service;
+pub mod dependencia_service;
+pub mod sesion_service;
+pub mod auditoria_service;
+pub mod transferencia_service;
+pub mod conteo_service;
+pub mod conversion_service;
+pub mod stock_parametro_service;
+pub mod orden_compra_service;
+pub mod pago_service;
+pub mod devolucion_service;
+pub mod kardex_service;
+pub mod valuacion_service;
+pub mod stock_historico_service;
+pub mod integridad_service;
+pub mod periodo_service;
+pub mod vencimiento_service;
 
 #[cfg(test)]
 mod tests;
\ No newline at end of file
@@ -1,17 +1,29 @@
-use sqlx::{SqlitePool, Result};
+use sqlx::{SqlitePool, SqliteConnection, SqliteExecutor};
+use crate::error::{AppError, Result};
 use crate::models::movimiento::{Movimiento, CreateMovimiento, UpdateMovimiento};
 
+/// Cantidad de `m` con el signo con que afecta el stock; la misma regla que
+/// `TipoMovimiento::signo` para usar en consultas
+pub(crate) const CANTIDAD_CON_SIGNO: &str = "
+    CASE WHEN LOWER(TRIM(m.tipo)) = 'salida'
+              OR (LOWER(TRIM(m.tipo)) = 'ajuste' AND LOWER(TRIM(COALESCE(m.subtipo, ''))) = 'faltante')
+         THEN -m.cantidad
+         ELSE m.cantidad
+    END";
+
 pub async fn get_all(pool: &SqlitePool) -> Result<Vec<Movimiento>> {
     sqlx::query_as::<_, Movimiento>("SELECT * FROM movimiento ORDER BY id_movimiento ASC")
         .fetch_all(pool)
         .await
+        .map_err(AppError::from)
 }
 
-pub async fn get_by_id(pool: &SqlitePool, id: i32) -> Result<Option<Movimiento>> {
+pub async fn get_by_id<'e, E: SqliteExecutor<'e>>(executor: E, id: i32) -> Result<Option<Movimiento>> {
     sqlx::query_as::<_, Movimiento>("SELECT * FROM movimiento WHERE id_movimiento = ?")
         .bind(id)
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await
+        .map_err(AppError::from)
 }
 
 pub async fn get_by_factura(pool: &SqlitePool, factura_id: i32) -> Result<Vec<Movimiento>> {
@@ -21,6 +33,7 @@ pub async fn get_by_factura(pool: &SqlitePool, factura_id: i32) -> Result<Vec<Mo
     .bind(factura_id)
     .fetch_all(pool)
     .await
+    .map_err(AppError::from)
 }
 
 pub async fn get_by_producto_proveedor(pool: &SqlitePool, prod_prov_id: i32) -> Result<Vec<Movimiento>> {
@@ -30,13 +43,41 @@ pub async fn get_by_producto_proveedor(pool: &SqlitePool, prod_prov_id: i32) ->
     .bind(prod_prov_id)
     .fetch_all(pool)
     .await
+    .map_err(AppError::from)
+}
+
+/// Movimientos de un documento, como la salida y la entrada de una transferencia
+pub async fn get_by_documento<'e, E: SqliteExecutor<'e>>(executor: E, documento: &str) -> Result<Vec<Movimiento>> {
+    sqlx::query_as::<_, Movimiento>(
+        "SELECT * FROM movimiento WHERE documento = ? ORDER BY id_movimiento ASC"
+    )
+    .bind(documento)
+    .fetch_all(executor)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Siguiente número de documento con el prefijo dado, como `TR-000001`
+pub async fn siguiente_documento(conn: &mut SqliteConnection, prefijo: &str) -> Result<String> {
+    let ultimo: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(CAST(SUBSTR(documento, ?) AS INTEGER)), 0) FROM movimiento
+         WHERE documento LIKE ? || '%'"
+    )
+    .bind(prefijo.len() as i64 + 1)
+    .bind(prefijo)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(format!("{}{:06}", prefijo, ultimo + 1))
 }
 
-pub async fn create(pool: &SqlitePool, data: CreateMovimiento) -> Result<i64> {
+/// Inserta la fila del movimiento sin tocar el stock. Usar
+/// `posting_service::post` para registrar movimientos desde la aplicación.
+pub async fn create(conn: &mut SqliteConnection, data: &CreateMovimiento) -> Result<i64> {
     let result = sqlx::query!(
         "INSERT INTO movimiento (fecha, tipo, subtipo, id_prod_prov, id_presentacion, cantidad, 
-                                 precio_unit, monto_total, lote, fecha_venc, obs, id_factura) 
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                                 precio_unit, monto_total, lote, fecha_venc, obs, id_factura, id_almacen, documento, id_linea_orden) 
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         data.fecha,
         data.tipo,
         data.subtipo,
@@ -48,15 +89,18 @@ pub async fn create(pool: &SqlitePool, data: CreateMovimiento) -> Result<i64> {
         data.lote,
         data.fecha_venc,
         data.obs,
-        data.id_factura
+        data.id_factura,
+        data.id_almacen,
+        data.documento,
+        data.id_linea_orden
     )
-    .execute(pool)
+    .execute(conn)
     .await?;
     
     Ok(result.last_insert_rowid())
 }
 
-pub async fn update(pool: &SqlitePool, id: i32, data: UpdateMovimiento) -> Result<u64> {
+pub async fn update(conn: &mut SqliteConnection, id: i32, data: &UpdateMovimiento) -> Result<u64> {
     let result = sqlx::query!(
         "UPDATE movimiento SET
             fecha = COALESCE(?, fecha),
@@ -70,7 +114,8 @@ pub async fn update(pool: &SqlitePool, id: i32, data: UpdateMovimiento) -> Resul
             lote = COALESCE(?, lote),
             fecha_venc = COALESCE(?, fecha_venc),
             obs = COALESCE(?, obs),
-            id_factura = COALESCE(?, id_factura)
+            id_factura = COALESCE(?, id_factura),
+            id_almacen = COALESCE(?, id_almacen)
          WHERE id_movimiento = ?",
         data.fecha,
         data.tipo,
@@ -84,17 +129,31 @@ pub async fn update(pool: &SqlitePool, id: i32, data: UpdateMovimiento) -> Resul
         data.fecha_venc,
         data.obs,
         data.id_factura,
+        data.id_almacen,
         id
     )
-    .execute(pool)
+    .execute(conn)
     .await?;
     
     Ok(result.rows_affected())
 }
 
-pub async fn delete(pool: &SqlitePool, id: i32) -> Result<u64> {
+/// Guarda el costo calculado por el motor de lotes.
+pub async fn update_costo(conn: &mut SqliteConnection, id: i32, monto_total: f64) -> Result<u64> {
+    let result = sqlx::query!(
+        "UPDATE movimiento SET monto_total = ? WHERE id_movimiento = ?",
+        monto_total,
+        id
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn delete(conn: &mut SqliteConnection, id: i32) -> Result<u64> {
     let result = sqlx::query!("DELETE FROM movimiento WHERE id_movimiento = ?", id)
-        .execute(pool)
+        .execute(conn)
         .await?;
     
     Ok(result.rows_affected())
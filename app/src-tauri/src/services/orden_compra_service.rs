@@ -0,0 +1,342 @@
+use sqlx::{SqlitePool, SqliteConnection, SqliteExecutor};
+use crate::error::{AppError, Result};
+use crate::models::movimiento::CreateMovimiento;
+use crate::models::orden_compra::{
+    OrdenCompra, OrdenCompraInput, OrdenCompraLinea, RecepcionOrden, ESTADOS_ORDEN_ABIERTA, ORDEN_BORRADOR,
+    ORDEN_CERRADA, ORDEN_ENVIADA, ORDEN_RECIBIDA, ORDEN_RECIBIDA_PARCIALMENTE,
+};
+use crate::services::{almacen_service, posting_service, producto_proveedor_service};
+
+/// Prefijo de los números de orden asignados automáticamente
+const PREFIJO: &str = "OC-";
+
+/// Tolerancia para comparar cantidades
+const EPSILON: f64 = 1e-9;
+
+pub async fn get_all(pool: &SqlitePool) -> Result<Vec<OrdenCompra>> {
+    sqlx::query_as::<_, OrdenCompra>("SELECT * FROM orden_compra ORDER BY id_orden ASC")
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_by_id<'e, E: SqliteExecutor<'e>>(executor: E, id: i32) -> Result<Option<OrdenCompra>> {
+    sqlx::query_as::<_, OrdenCompra>("SELECT * FROM orden_compra WHERE id_orden = ?")
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_by_proveedor(pool: &SqlitePool, proveedor_id: i32) -> Result<Vec<OrdenCompra>> {
+    sqlx::query_as::<_, OrdenCompra>("SELECT * FROM orden_compra WHERE id_proveedor = ? ORDER BY fecha DESC")
+        .bind(proveedor_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Líneas de la orden con la cantidad recibida de cada una
+pub async fn get_lineas<'e, E: SqliteExecutor<'e>>(executor: E, id: i32) -> Result<Vec<OrdenCompraLinea>> {
+    sqlx::query_as::<_, OrdenCompraLinea>(
+        "SELECT * FROM orden_compra_linea_saldo WHERE id_orden = ? ORDER BY id_linea ASC"
+    )
+    .bind(id)
+    .fetch_all(executor)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Crea la orden en borrador con sus líneas
+pub async fn create(pool: &SqlitePool, data: OrdenCompraInput) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    almacen_service::verificar_activo(&mut tx, data.id_almacen).await?;
+    let numero = match data.numero.clone() {
+        Some(numero) => numero,
+        None => siguiente_numero(&mut tx).await?,
+    };
+
+    let id = sqlx::query!(
+        "INSERT INTO orden_compra (numero, fecha, id_proveedor, id_almacen, estado, fecha_entrega, obs)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        numero,
+        data.fecha,
+        data.id_proveedor,
+        data.id_almacen,
+        ORDEN_BORRADOR,
+        data.fecha_entrega,
+        data.obs
+    )
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    insertar_lineas(&mut tx, id, &data).await?;
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+/// Reemplaza los datos y las líneas de una orden en borrador. Sin número se
+/// conserva el actual.
+pub async fn update(pool: &SqlitePool, id: i32, data: OrdenCompraInput) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    if get_by_id(&mut *tx, id).await?.is_none() {
+        return Ok(0);
+    }
+    verificar_estado(&mut tx, id, &[ORDEN_BORRADOR], "modificarse").await?;
+    almacen_service::verificar_activo(&mut tx, data.id_almacen).await?;
+
+    let rows = sqlx::query!(
+        "UPDATE orden_compra SET
+            numero = COALESCE(?, numero),
+            fecha = ?,
+            id_proveedor = ?,
+            id_almacen = ?,
+            fecha_entrega = ?,
+            obs = ?
+         WHERE id_orden = ?",
+        data.numero,
+        data.fecha,
+        data.id_proveedor,
+        data.id_almacen,
+        data.fecha_entrega,
+        data.obs,
+        id
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    sqlx::query!("DELETE FROM orden_compra_linea WHERE id_orden = ?", id)
+        .execute(&mut *tx)
+        .await?;
+    insertar_lineas(&mut tx, id as i64, &data).await?;
+
+    tx.commit().await?;
+    Ok(rows)
+}
+
+/// Elimina una orden en borrador con sus líneas
+pub async fn delete(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    if get_by_id(&mut *tx, id).await?.is_none() {
+        return Ok(0);
+    }
+    verificar_estado(&mut tx, id, &[ORDEN_BORRADOR], "eliminarse").await?;
+
+    sqlx::query!("DELETE FROM orden_compra_linea WHERE id_orden = ?", id)
+        .execute(&mut *tx)
+        .await?;
+    let result = sqlx::query!("DELETE FROM orden_compra WHERE id_orden = ?", id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// Marca la orden como enviada al proveedor; desde entonces lo pedido cuenta
+/// como stock en tránsito.
+pub async fn enviar(pool: &SqlitePool, id: i32) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    verificar_estado(&mut tx, id, &[ORDEN_BORRADOR], "enviarse").await?;
+
+    sqlx::query!("UPDATE orden_compra SET estado = ? WHERE id_orden = ?", ORDEN_ENVIADA, id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Cierra la orden. Lo que no se recibió deja de estar en tránsito.
+pub async fn cerrar(pool: &SqlitePool, id: i32) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    verificar_estado(
+        &mut tx,
+        id,
+        &[ORDEN_ENVIADA, ORDEN_RECIBIDA_PARCIALMENTE, ORDEN_RECIBIDA],
+        "cerrarse",
+    )
+    .await?;
+
+    sqlx::query!("UPDATE orden_compra SET estado = ? WHERE id_orden = ?", ORDEN_CERRADA, id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Registra una entrada en el almacén de la orden por cada línea recibida,
+/// con el precio acordado salvo que se indique otro, el número de la orden
+/// como documento y la factura del proveedor, si llegó. Devuelve los ids de
+/// las entradas.
+pub async fn recibir(pool: &SqlitePool, id: i32, data: RecepcionOrden) -> Result<Vec<i64>> {
+    let mut tx = pool.begin().await?;
+
+    let orden = verificar_estado(&mut tx, id, ESTADOS_ORDEN_ABIERTA, "recibirse").await?;
+    if let Some(factura_id) = data.id_factura {
+        let proveedor: Option<i32> = sqlx::query_scalar("SELECT id_proveedor FROM factura WHERE id_factura = ?")
+            .bind(factura_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        match proveedor {
+            None => return Err(AppError::not_found("la factura", factura_id)),
+            Some(proveedor) if proveedor != orden.id_proveedor => {
+                return Err(AppError::validation("id_factura", "Debe ser una factura del proveedor de la orden"));
+            }
+            Some(_) => {}
+        }
+    }
+
+    let lineas = get_lineas(&mut *tx, id).await?;
+    let mut entradas = Vec::new();
+
+    for (i, recibida) in data.lineas.iter().enumerate() {
+        let Some(linea) = lineas.iter().find(|l| l.id_linea == recibida.id_linea) else {
+            return Err(AppError::validation(
+                &format!("lineas[{}].id_linea", i),
+                "No es una línea de la orden",
+            ));
+        };
+        // Lo recibido incluye las líneas anteriores de esta misma recepción
+        let recibido: f64 = sqlx::query_scalar(
+            "SELECT cantidad_recibida FROM orden_compra_linea_saldo WHERE id_linea = ?"
+        )
+        .bind(linea.id_linea)
+        .fetch_one(&mut *tx)
+        .await?;
+        let pendiente = linea.cantidad - recibido;
+        if recibida.cantidad > pendiente + EPSILON {
+            return Err(AppError::validation(
+                &format!("lineas[{}].cantidad", i),
+                format!("Supera lo pendiente de la línea ({})", pendiente),
+            ));
+        }
+
+        let entrada = CreateMovimiento {
+            fecha: data.fecha.clone(),
+            tipo: "entrada".to_string(),
+            id_prod_prov: linea.id_prod_prov,
+            id_presentacion: linea.id_presentacion,
+            cantidad: recibida.cantidad,
+            precio_unit: Some(recibida.precio_unit.unwrap_or(linea.precio_unit)),
+            lote: recibida.lote.clone(),
+            fecha_venc: recibida.fecha_venc.clone(),
+            id_factura: data.id_factura,
+            id_almacen: orden.id_almacen,
+            documento: Some(orden.numero.clone()),
+            id_linea_orden: Some(linea.id_linea),
+            ..Default::default()
+        };
+        entradas.push(posting_service::post_in(&mut tx, &entrada).await?.id_movimiento);
+    }
+
+    actualizar_estado(&mut tx, id).await?;
+
+    tx.commit().await?;
+    Ok(entradas)
+}
+
+/// Recalcula el estado de una orden abierta o recibida a partir de lo
+/// recibido en sus líneas. Se llama también cuando se modifica o elimina una
+/// entrada registrada contra la orden.
+pub(crate) async fn actualizar_estado(conn: &mut SqliteConnection, id: i32) -> Result<()> {
+    let Some(orden) = get_by_id(&mut *conn, id).await? else {
+        return Ok(());
+    };
+    if orden.estado == ORDEN_BORRADOR || orden.estado == ORDEN_CERRADA {
+        return Ok(());
+    }
+
+    let lineas = get_lineas(&mut *conn, id).await?;
+    let estado = if lineas.iter().all(|l| l.cantidad_recibida >= l.cantidad - EPSILON) {
+        ORDEN_RECIBIDA
+    } else if lineas.iter().any(|l| l.cantidad_recibida > EPSILON) {
+        ORDEN_RECIBIDA_PARCIALMENTE
+    } else {
+        ORDEN_ENVIADA
+    };
+
+    sqlx::query!("UPDATE orden_compra SET estado = ? WHERE id_orden = ?", estado, id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Orden de la línea `linea_id`
+pub(crate) async fn get_orden_de_linea(conn: &mut SqliteConnection, linea_id: i32) -> Result<Option<i32>> {
+    sqlx::query_scalar::<_, i32>("SELECT id_orden FROM orden_compra_linea WHERE id_linea = ?")
+        .bind(linea_id)
+        .fetch_optional(conn)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Devuelve la orden si está en uno de los estados `desde`
+async fn verificar_estado(
+    conn: &mut SqliteConnection,
+    id: i32,
+    desde: &[&str],
+    accion: &str,
+) -> Result<OrdenCompra> {
+    let orden = get_by_id(conn, id).await?.ok_or(AppError::not_found("la orden de compra", id))?;
+    if !desde.contains(&orden.estado.as_str()) {
+        return Err(AppError::Conflict(format!(
+            "La orden de compra {} está {}; no puede {}",
+            orden.numero,
+            orden.estado.to_lowercase(),
+            accion
+        )));
+    }
+
+    Ok(orden)
+}
+
+/// Inserta las líneas verificando que cada producto sea del proveedor de la
+/// orden y cada presentación, de ese producto
+async fn insertar_lineas(conn: &mut SqliteConnection, id: i64, data: &OrdenCompraInput) -> Result<()> {
+    for (i, linea) in data.lineas.iter().enumerate() {
+        if !producto_proveedor_service::corresponde(&mut *conn, linea.id_prod_prov, data.id_proveedor, linea.id_presentacion)
+            .await?
+        {
+            return Err(AppError::validation(
+                &format!("lineas[{}].id_prod_prov", i),
+                "Debe ser un producto del proveedor de la orden, en una de sus presentaciones",
+            ));
+        }
+
+        sqlx::query!(
+            "INSERT INTO orden_compra_linea (id_orden, id_prod_prov, id_presentacion, cantidad, precio_unit)
+             VALUES (?, ?, ?, ?, ?)",
+            id,
+            linea.id_prod_prov,
+            linea.id_presentacion,
+            linea.cantidad,
+            linea.precio_unit
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn siguiente_numero(conn: &mut SqliteConnection) -> Result<String> {
+    let ultimo: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(CAST(SUBSTR(numero, ?) AS INTEGER)), 0) FROM orden_compra
+         WHERE numero LIKE ? || '%'"
+    )
+    .bind(PREFIJO.len() as i64 + 1)
+    .bind(PREFIJO)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(format!("{}{:06}", PREFIJO, ultimo + 1))
+}
@@ -0,0 +1,335 @@
+use sqlx::{SqlitePool, SqliteConnection, SqliteExecutor};
+use crate::error::{AppError, Result};
+use crate::models::factura::{FACTURA_ACTIVA, FACTURA_PAGADA};
+use crate::models::pago::{
+    AntiguedadSaldo, AplicacionPago, CreatePago, MovimientoCuenta, Pago, PagoAplicacion, SaldoFactura, SaldoProveedor,
+};
+use crate::services::factura_service;
+
+/// Tolerancia para considerar saldada una factura
+const EPSILON: f64 = 0.005;
+
+/// Fecha de corte que incluye todos los pagos y facturas
+const SIN_CORTE: &str = "9999-12-31";
+
+/// Total, pagado y acreditado de cada factura activa o pagada con fecha hasta
+/// el corte, contando los pagos y las notas de crédito hasta esa misma fecha.
+/// El total es el impreso por el proveedor o, si no lo tiene, el calculado
+/// con sus entradas. El corte es el primer parámetro.
+fn saldos() -> String {
+    format!(
+        "WITH totales AS ({}),
+        saldos AS (
+            SELECT f.id_factura, f.numero, f.id_proveedor, f.fecha, f.fecha_vencimiento,
+                   COALESCE(f.total, t.total_calculado) AS total,
+                   COALESCE((SELECT SUM(a.monto) FROM pago_aplicacion a
+                             JOIN pago p ON p.id_pago = a.id_pago
+                             WHERE a.id_factura = f.id_factura AND p.fecha <= ?1), 0.0) AS pagado,
+                   COALESCE((SELECT SUM(n.monto) FROM nota_credito n
+                             WHERE n.id_factura = f.id_factura AND n.fecha <= ?1), 0.0) AS acreditado
+            FROM factura f
+            JOIN totales t ON t.id_factura = f.id_factura
+            WHERE f.estado IN ('{}', '{}') AND f.fecha <= ?1
+        )",
+        factura_service::TOTALES,
+        FACTURA_ACTIVA,
+        FACTURA_PAGADA
+    )
+}
+
+pub async fn get_all(pool: &SqlitePool) -> Result<Vec<Pago>> {
+    sqlx::query_as::<_, Pago>("SELECT * FROM pago ORDER BY fecha DESC, id_pago DESC")
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_by_id<'e, E: SqliteExecutor<'e>>(executor: E, id: i32) -> Result<Option<Pago>> {
+    sqlx::query_as::<_, Pago>("SELECT * FROM pago WHERE id_pago = ?")
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_by_proveedor(pool: &SqlitePool, proveedor_id: i32) -> Result<Vec<Pago>> {
+    sqlx::query_as::<_, Pago>("SELECT * FROM pago WHERE id_proveedor = ? ORDER BY fecha DESC, id_pago DESC")
+        .bind(proveedor_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_aplicaciones<'e, E: SqliteExecutor<'e>>(executor: E, pago_id: i32) -> Result<Vec<PagoAplicacion>> {
+    sqlx::query_as::<_, PagoAplicacion>("SELECT * FROM pago_aplicacion WHERE id_pago = ? ORDER BY id_aplicacion ASC")
+        .bind(pago_id)
+        .fetch_all(executor)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_aplicaciones_by_factura(pool: &SqlitePool, factura_id: i32) -> Result<Vec<PagoAplicacion>> {
+    sqlx::query_as::<_, PagoAplicacion>(
+        "SELECT * FROM pago_aplicacion WHERE id_factura = ? ORDER BY id_aplicacion ASC"
+    )
+    .bind(factura_id)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Registra el pago y lo aplica a las facturas indicadas
+pub async fn create(pool: &SqlitePool, data: CreatePago) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    let id = sqlx::query!(
+        "INSERT INTO pago (fecha, id_proveedor, monto, metodo, referencia, obs) VALUES (?, ?, ?, ?, ?, ?)",
+        data.fecha,
+        data.id_proveedor,
+        data.monto,
+        data.metodo,
+        data.referencia,
+        data.obs
+    )
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    aplicar_in(&mut tx, id as i32, &data.aplicaciones).await?;
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+/// Aplica a otras facturas lo que quedó sin aplicar de un pago
+pub async fn aplicar(pool: &SqlitePool, id: i32, aplicaciones: Vec<AplicacionPago>) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    aplicar_in(&mut tx, id, &aplicaciones).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Elimina el pago y sus aplicaciones; las facturas que saldaba vuelven a
+/// quedar activas
+pub async fn delete(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    let aplicaciones = get_aplicaciones(&mut *tx, id).await?;
+    sqlx::query!("DELETE FROM pago_aplicacion WHERE id_pago = ?", id)
+        .execute(&mut *tx)
+        .await?;
+    let result = sqlx::query!("DELETE FROM pago WHERE id_pago = ?", id)
+        .execute(&mut *tx)
+        .await?;
+
+    for aplicacion in aplicaciones {
+        actualizar_estado(&mut tx, aplicacion.id_factura).await?;
+    }
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// Facturas con saldo pendiente, de un proveedor o de todos, por vencimiento
+pub async fn get_saldos_factura(pool: &SqlitePool, proveedor_id: Option<i32>) -> Result<Vec<SaldoFactura>> {
+    let sql = format!(
+        "{} SELECT *, total - pagado - acreditado AS saldo FROM saldos
+         WHERE total - pagado - acreditado >= {} AND (?2 IS NULL OR id_proveedor = ?2)
+         ORDER BY fecha_vencimiento ASC, id_factura ASC",
+        saldos(),
+        EPSILON
+    );
+    sqlx::query_as::<_, SaldoFactura>(&sql)
+        .bind(SIN_CORTE)
+        .bind(proveedor_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Cuentas por pagar de cada proveedor con facturas o pagos. Lo pagado o
+/// acreditado de más en una factura cuenta como saldo a favor.
+pub async fn get_saldos_proveedor(pool: &SqlitePool) -> Result<Vec<SaldoProveedor>> {
+    let sql = format!(
+        "{},
+        cuentas AS (
+            SELECT pv.id_proveedor, pv.nombre,
+                   COUNT(s.id_factura) AS facturas,
+                   COALESCE(SUM(s.total), 0.0) AS facturado,
+                   COALESCE(SUM(s.pagado), 0.0) AS pagado,
+                   COALESCE(SUM(s.acreditado), 0.0) AS acreditado,
+                   COALESCE(SUM(MAX(s.total - s.pagado - s.acreditado, 0.0)), 0.0) AS saldo,
+                   COALESCE((SELECT SUM(p.monto) FROM pago p WHERE p.id_proveedor = pv.id_proveedor), 0.0)
+                     - COALESCE((SELECT SUM(a.monto) FROM pago_aplicacion a
+                                 JOIN pago p ON p.id_pago = a.id_pago
+                                 WHERE p.id_proveedor = pv.id_proveedor), 0.0)
+                     + COALESCE(SUM(MAX(s.pagado + s.acreditado - s.total, 0.0)), 0.0) AS saldo_a_favor
+            FROM proveedor pv
+            LEFT JOIN saldos s ON s.id_proveedor = pv.id_proveedor
+            GROUP BY pv.id_proveedor
+        )
+        SELECT id_proveedor, nombre, facturado, pagado, acreditado, saldo, saldo_a_favor
+        FROM cuentas
+        WHERE facturas > 0 OR saldo_a_favor >= {}
+        ORDER BY nombre ASC",
+        saldos(),
+        EPSILON
+    );
+    sqlx::query_as::<_, SaldoProveedor>(&sql)
+        .bind(SIN_CORTE)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Historial de cuenta del proveedor: sus facturas activas o pagadas, sus
+/// pagos y sus notas de crédito, por fecha y con el saldo acumulado
+pub async fn get_estado_cuenta(pool: &SqlitePool, proveedor_id: i32) -> Result<Vec<MovimientoCuenta>> {
+    let sql = format!(
+        "{},
+        cuenta AS (
+            SELECT fecha, 1 AS orden, 'Factura' AS concepto, id_factura AS id_documento, numero,
+                   total AS cargo, 0.0 AS abono
+            FROM saldos WHERE id_proveedor = ?2
+            UNION ALL
+            SELECT fecha, 2, 'Nota de crédito', id_nota, numero, 0.0, monto
+            FROM nota_credito WHERE id_proveedor = ?2
+            UNION ALL
+            SELECT fecha, 3, 'Pago', id_pago, referencia, 0.0, monto
+            FROM pago WHERE id_proveedor = ?2
+        )
+        SELECT fecha, concepto, id_documento, numero, cargo, abono,
+               SUM(cargo - abono) OVER (ORDER BY fecha, orden, id_documento) AS saldo
+        FROM cuenta
+        ORDER BY fecha, orden, id_documento",
+        saldos()
+    );
+    sqlx::query_as::<_, MovimientoCuenta>(&sql)
+        .bind(SIN_CORTE)
+        .bind(proveedor_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Antigüedad de saldos a la fecha de corte: lo no vencido, y lo vencido
+/// hace 0-30, 31-60, 61-90 o más de 90 días
+pub async fn get_antiguedad(pool: &SqlitePool, fecha_corte: &str) -> Result<Vec<AntiguedadSaldo>> {
+    let sql = format!(
+        "{},
+        pendientes AS (
+            SELECT id_proveedor, total - pagado - acreditado AS saldo,
+                   CAST(julianday(?1) - julianday(COALESCE(fecha_vencimiento, fecha)) AS INTEGER) AS dias
+            FROM saldos
+            WHERE total - pagado - acreditado >= {}
+        )
+        SELECT pv.id_proveedor, pv.nombre,
+               SUM(CASE WHEN p.dias < 0 THEN p.saldo ELSE 0.0 END) AS por_vencer,
+               SUM(CASE WHEN p.dias BETWEEN 0 AND 30 THEN p.saldo ELSE 0.0 END) AS dias_0_30,
+               SUM(CASE WHEN p.dias BETWEEN 31 AND 60 THEN p.saldo ELSE 0.0 END) AS dias_31_60,
+               SUM(CASE WHEN p.dias BETWEEN 61 AND 90 THEN p.saldo ELSE 0.0 END) AS dias_61_90,
+               SUM(CASE WHEN p.dias > 90 THEN p.saldo ELSE 0.0 END) AS dias_mas_90,
+               SUM(p.saldo) AS total
+        FROM pendientes p
+        JOIN proveedor pv ON pv.id_proveedor = p.id_proveedor
+        GROUP BY pv.id_proveedor
+        ORDER BY pv.nombre ASC",
+        saldos(),
+        EPSILON
+    );
+    sqlx::query_as::<_, AntiguedadSaldo>(&sql)
+        .bind(fecha_corte)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Aplica montos del pago a facturas activas de su proveedor, sin superar el
+/// saldo de cada factura ni lo que queda sin aplicar del pago
+async fn aplicar_in(conn: &mut SqliteConnection, id: i32, aplicaciones: &[AplicacionPago]) -> Result<()> {
+    let pago = get_by_id(&mut *conn, id).await?.ok_or(AppError::not_found("el pago", id))?;
+
+    for (i, aplicacion) in aplicaciones.iter().enumerate() {
+        let factura = factura_service::get_factura_by_id(&mut *conn, aplicacion.id_factura)
+            .await?
+            .ok_or(AppError::not_found("la factura", aplicacion.id_factura))?;
+        if factura.id_proveedor != pago.id_proveedor {
+            return Err(AppError::validation(
+                &format!("aplicaciones[{}].id_factura", i),
+                "Debe ser una factura del proveedor del pago",
+            ));
+        }
+        if factura.estado.as_deref() != Some(FACTURA_ACTIVA) {
+            return Err(AppError::Conflict(format!(
+                "La factura {} no está activa; no admite pagos",
+                factura.numero
+            )));
+        }
+
+        let saldo = saldo(&mut *conn, factura.id_factura).await?;
+        if aplicacion.monto > saldo + EPSILON {
+            return Err(AppError::validation(
+                &format!("aplicaciones[{}].monto", i),
+                format!("Supera el saldo de la factura ({:.2})", saldo),
+            ));
+        }
+        let aplicado: f64 = sqlx::query_scalar("SELECT COALESCE(SUM(monto), 0.0) FROM pago_aplicacion WHERE id_pago = ?")
+            .bind(id)
+            .fetch_one(&mut *conn)
+            .await?;
+        if aplicacion.monto > pago.monto - aplicado + EPSILON {
+            return Err(AppError::validation(
+                &format!("aplicaciones[{}].monto", i),
+                format!("Supera lo que queda sin aplicar del pago ({:.2})", pago.monto - aplicado),
+            ));
+        }
+
+        sqlx::query!(
+            "INSERT INTO pago_aplicacion (id_pago, id_factura, monto) VALUES (?, ?, ?)
+             ON CONFLICT (id_pago, id_factura) DO UPDATE SET monto = monto + excluded.monto",
+            id,
+            aplicacion.id_factura,
+            aplicacion.monto
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        actualizar_estado(&mut *conn, factura.id_factura).await?;
+    }
+
+    Ok(())
+}
+
+async fn saldo(conn: &mut SqliteConnection, factura_id: i32) -> Result<f64> {
+    let sql = format!("{} SELECT total - pagado - acreditado FROM saldos WHERE id_factura = ?2", saldos());
+    let saldo: Option<f64> = sqlx::query_scalar(&sql)
+        .bind(SIN_CORTE)
+        .bind(factura_id)
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(saldo.unwrap_or(0.0))
+}
+
+/// Marca pagada la factura activa sin saldo, y vuelve a activa la pagada que
+/// recuperó saldo
+pub(crate) async fn actualizar_estado(conn: &mut SqliteConnection, factura_id: i32) -> Result<()> {
+    let saldo = saldo(&mut *conn, factura_id).await?;
+    let (desde, hacia) = if saldo < EPSILON {
+        (FACTURA_ACTIVA, FACTURA_PAGADA)
+    } else {
+        (FACTURA_PAGADA, FACTURA_ACTIVA)
+    };
+
+    sqlx::query!(
+        "UPDATE factura SET estado = ? WHERE id_factura = ? AND estado = ?",
+        hacia,
+        factura_id,
+        desde
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
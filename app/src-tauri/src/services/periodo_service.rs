@@ -0,0 +1,197 @@
+use sqlx::{SqlitePool, SqliteConnection, SqliteExecutor};
+use crate::error::{AppError, Result};
+use crate::models::periodo::{CerrarPeriodo, CierrePeriodo, Periodo, ReabrirPeriodo, PERIODO_ABIERTO, PERIODO_CERRADO};
+use crate::models::valuacion::{FiltroValuacion, LineaValuacion};
+use crate::services::{stock_historico_service, valuacion_service};
+
+pub async fn get_all(pool: &SqlitePool) -> Result<Vec<Periodo>> {
+    sqlx::query_as::<_, Periodo>("SELECT * FROM periodo ORDER BY periodo DESC")
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn get_by_periodo<'e, E: SqliteExecutor<'e>>(executor: E, periodo: &str) -> Result<Option<Periodo>> {
+    sqlx::query_as::<_, Periodo>("SELECT * FROM periodo WHERE periodo = ?")
+        .bind(periodo)
+        .fetch_optional(executor)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Período con el stock valorizado que se guardó en su último cierre
+pub async fn get_cierre(pool: &SqlitePool, periodo: &str) -> Result<Option<CierrePeriodo>> {
+    let Some(periodo) = get_by_periodo(pool, periodo).await? else {
+        return Ok(None);
+    };
+    let lineas = sqlx::query_as::<_, LineaValuacion>(
+        "SELECT * FROM periodo_valuacion WHERE id_periodo = ?
+         ORDER BY codigo_interno ASC, id_presentacion ASC, id_almacen ASC"
+    )
+    .bind(periodo.id_periodo)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Some(CierrePeriodo { periodo, lineas }))
+}
+
+/// Cierra un mes terminado. Guarda un corte de stock y el stock valorizado al
+/// último día del mes, y desde entonces rechaza movimientos y facturas con
+/// fecha dentro de él. Los meses anteriores con movimientos o facturas deben
+/// estar cerrados.
+pub async fn cerrar(pool: &SqlitePool, data: CerrarPeriodo) -> Result<CierrePeriodo> {
+    let mut tx = pool.begin().await?;
+
+    if get_by_periodo(&mut *tx, &data.periodo).await?.is_some_and(|p| p.estado == PERIODO_CERRADO) {
+        return Err(AppError::Conflict(format!("El período {} ya está cerrado", data.periodo)));
+    }
+    let (fin, terminado): (String, bool) =
+        sqlx::query_as("SELECT date(?1 || '-01', '+1 month', '-1 day'), date(?1 || '-01', '+1 month') <= date('now')")
+            .bind(&data.periodo)
+            .fetch_one(&mut *tx)
+            .await?;
+    if !terminado {
+        return Err(AppError::Conflict(format!("El período {} todavía no terminó", data.periodo)));
+    }
+    let pendiente: Option<String> = sqlx::query_scalar(
+        "SELECT MIN(mes) FROM (
+            SELECT SUBSTR(fecha, 1, 7) AS mes FROM movimiento
+            UNION
+            SELECT SUBSTR(fecha, 1, 7) FROM factura
+         )
+         WHERE mes < ? AND mes NOT IN (SELECT periodo FROM periodo WHERE estado = ?)"
+    )
+    .bind(&data.periodo)
+    .bind(PERIODO_CERRADO)
+    .fetch_one(&mut *tx)
+    .await?;
+    if let Some(pendiente) = pendiente {
+        return Err(AppError::Conflict(format!(
+            "El período {} sigue abierto; debe cerrarse antes que {}",
+            pendiente, data.periodo
+        )));
+    }
+
+    stock_historico_service::crear_corte_in(&mut tx, &fin).await?;
+    let filtro = FiltroValuacion {
+        metodo: data.metodo,
+        fecha_corte: Some(fin),
+        id_almacen: None,
+    };
+    let valuacion = valuacion_service::get_valuacion(&mut *tx, filtro).await?;
+
+    let metodo = data.metodo.as_str();
+    sqlx::query!(
+        "INSERT INTO periodo (periodo, estado, metodo_valuacion, valor_total, cerrado_en)
+         VALUES (?, ?, ?, ?, datetime('now'))
+         ON CONFLICT (periodo) DO UPDATE SET
+            estado = excluded.estado,
+            metodo_valuacion = excluded.metodo_valuacion,
+            valor_total = excluded.valor_total,
+            cerrado_en = excluded.cerrado_en",
+        data.periodo,
+        PERIODO_CERRADO,
+        metodo,
+        valuacion.total
+    )
+    .execute(&mut *tx)
+    .await?;
+    let periodo = sqlx::query_as::<_, Periodo>("SELECT * FROM periodo WHERE periodo = ?")
+        .bind(&data.periodo)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    sqlx::query!("DELETE FROM periodo_valuacion WHERE id_periodo = ?", periodo.id_periodo)
+        .execute(&mut *tx)
+        .await?;
+    for linea in &valuacion.lineas {
+        sqlx::query!(
+            "INSERT INTO periodo_valuacion (id_periodo, id_producto, codigo_interno, descripcion, categoria,
+                                            id_presentacion, unidad, id_almacen, almacen, cantidad,
+                                            costo_unitario, valor)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            periodo.id_periodo,
+            linea.id_producto,
+            linea.codigo_interno,
+            linea.descripcion,
+            linea.categoria,
+            linea.id_presentacion,
+            linea.unidad,
+            linea.id_almacen,
+            linea.almacen,
+            linea.cantidad,
+            linea.costo_unitario,
+            linea.valor
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(CierrePeriodo {
+        periodo,
+        lineas: valuacion.lineas,
+    })
+}
+
+/// Vuelve a abrir un período cerrado. Solo se puede reabrir el último cierre,
+/// para que los posteriores no queden calculados sobre datos que cambian. El
+/// motivo y el usuario quedan en la auditoría; la valuación guardada se
+/// conserva hasta el próximo cierre.
+pub async fn reabrir(pool: &SqlitePool, data: ReabrirPeriodo) -> Result<Periodo> {
+    let mut tx = pool.begin().await?;
+
+    let periodo = get_by_periodo(&mut *tx, &data.periodo)
+        .await?
+        .filter(|p| p.estado == PERIODO_CERRADO)
+        .ok_or(AppError::Conflict(format!("El período {} no está cerrado", data.periodo)))?;
+    let posterior: Option<String> = sqlx::query_scalar(
+        "SELECT MAX(periodo) FROM periodo WHERE periodo > ? AND estado = ?"
+    )
+    .bind(&data.periodo)
+    .bind(PERIODO_CERRADO)
+    .fetch_one(&mut *tx)
+    .await?;
+    if let Some(posterior) = posterior {
+        return Err(AppError::Conflict(format!(
+            "El período {} está cerrado; debe reabrirse antes que {}",
+            posterior, data.periodo
+        )));
+    }
+
+    sqlx::query!(
+        "UPDATE periodo SET estado = ?, reabierto_en = datetime('now'), motivo_reapertura = ?
+         WHERE id_periodo = ?",
+        PERIODO_ABIERTO,
+        data.motivo,
+        periodo.id_periodo
+    )
+    .execute(&mut *tx)
+    .await?;
+    let periodo = sqlx::query_as::<_, Periodo>("SELECT * FROM periodo WHERE id_periodo = ?")
+        .bind(periodo.id_periodo)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(periodo)
+}
+
+/// Falla con `Conflict` si la fecha cae en un período cerrado
+pub(crate) async fn verificar_abierto(conn: &mut SqliteConnection, fecha: &str) -> Result<()> {
+    let cerrado: Option<String> = sqlx::query_scalar(
+        "SELECT periodo FROM periodo WHERE periodo = SUBSTR(?, 1, 7) AND estado = ?"
+    )
+    .bind(fecha)
+    .bind(PERIODO_CERRADO)
+    .fetch_optional(conn)
+    .await?;
+    if let Some(periodo) = cerrado {
+        return Err(AppError::Conflict(format!(
+            "El período {} está cerrado; no admite cambios con fecha {}",
+            periodo, fecha
+        )));
+    }
+
+    Ok(())
+}
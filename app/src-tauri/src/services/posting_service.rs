@@ -0,0 +1,447 @@
+use sqlx::{SqlitePool, SqliteConnection};
+use crate::error::{AppError, Result};
+use crate::models::configuracion::PoliticaStock;
+use crate::models::movimiento::{
+    Movimiento, CreateMovimiento, UpdateMovimiento, TipoMovimiento, MovimientoRegistrado, SUBTIPOS_DOCUMENTO,
+};
+use crate::models::stock_almacen::StockInsuficiente;
+use crate::models::devolucion::SUBTIPO_DEVOLUCION;
+use crate::models::factura::SUBTIPO_ANULACION;
+use crate::models::lote::Lote;
+use crate::services::{
+    configuracion_service, conteo_service, factura_service, lote_service, movimiento_service, orden_compra_service,
+    periodo_service, stock_almacen_service,
+};
+
+/// Tolerancia para comparar cantidades
+const EPSILON: f64 = 1e-9;
+
+/// Registra un movimiento y actualiza el stock del almacén y sus lotes en una
+/// sola transacción.
+pub async fn post(pool: &SqlitePool, data: CreateMovimiento) -> Result<MovimientoRegistrado> {
+    let mut tx = pool.begin().await?;
+    let registrado = post_in(&mut tx, &data).await?;
+    tx.commit().await?;
+
+    Ok(registrado)
+}
+
+/// Igual que `post`, pero dentro de una transacción abierta por el llamador.
+pub async fn post_in(conn: &mut SqliteConnection, data: &CreateMovimiento) -> Result<MovimientoRegistrado> {
+    tipo(&data.tipo)?;
+    periodo_service::verificar_abierto(&mut *conn, &data.fecha).await?;
+    factura_service::verificar_no_anulada(&mut *conn, data.id_factura).await?;
+    let id = movimiento_service::create(&mut *conn, data).await?;
+
+    let movimiento = find(&mut *conn, id as i32).await?.ok_or(AppError::not_found("el movimiento", id))?;
+    let advertencia = contabilizar(conn, &movimiento).await?;
+
+    Ok(MovimientoRegistrado { id_movimiento: id, advertencia })
+}
+
+/// Registra la entrada que recibe lo que sacó la salida `salida_id`, en una
+/// transferencia o una conversión entre presentaciones. La entrada vale lo
+/// mismo que la salida y recibe sus lotes con el número y las fechas
+/// originales; `factor` pasa cantidades de la salida a la entrada y divide el
+/// costo unitario. Lo que la salida no tomó de lotes abre un lote propio al
+/// costo restante.
+pub(crate) async fn post_recepcion_in(
+    conn: &mut SqliteConnection,
+    salida_id: i32,
+    mut entrada: CreateMovimiento,
+    factor: f64,
+) -> Result<i64> {
+    let salida = find(&mut *conn, salida_id).await?.ok_or(AppError::not_found("el movimiento", salida_id))?;
+    let costo_total = salida.monto_total.unwrap_or(0.0);
+    entrada.cantidad = salida.cantidad * factor;
+    entrada.precio_unit = Some(costo_total / entrada.cantidad);
+    entrada.monto_total = Some(costo_total);
+
+    periodo_service::verificar_abierto(&mut *conn, &entrada.fecha).await?;
+    conteo_service::verificar_sin_conteo(&mut *conn, entrada.id_almacen).await?;
+    let id = movimiento_service::create(&mut *conn, &entrada).await?;
+    stock_almacen_service::ajustar(
+        &mut *conn,
+        entrada.id_prod_prov,
+        entrada.id_presentacion,
+        entrada.id_almacen,
+        entrada.cantidad,
+    )
+    .await?;
+
+    let mut pendiente = entrada.cantidad;
+    let mut costo_pendiente = costo_total;
+    for consumo in lote_service::get_consumos_by_movimiento(&mut *conn, salida_id).await? {
+        let lote = lote_service::get_by_id(&mut *conn, consumo.id_lote)
+            .await?
+            .ok_or(AppError::not_found("el lote", consumo.id_lote))?;
+        lote_service::trasladar(
+            &mut *conn,
+            &lote,
+            id as i32,
+            entrada.id_presentacion,
+            entrada.id_almacen,
+            consumo.cantidad * factor,
+            consumo.costo_unitario / factor,
+        )
+        .await?;
+
+        pendiente -= consumo.cantidad * factor;
+        costo_pendiente -= consumo.cantidad * consumo.costo_unitario;
+    }
+
+    if pendiente > EPSILON {
+        let mut resto = find(&mut *conn, id as i32).await?.ok_or(AppError::not_found("el movimiento", id))?;
+        resto.cantidad = pendiente;
+        lote_service::abrir(conn, &resto, entrada.id_almacen, costo_pendiente / pendiente).await?;
+    }
+
+    Ok(id)
+}
+
+/// Registra una salida `anulacion` que revierte la entrada `entrada` tomando
+/// exactamente los lotes que abrió, de modo que stock y lotes vuelvan a como
+/// estaban. Falla si de esos lotes ya salió mercadería.
+pub(crate) async fn post_reverso_in(
+    conn: &mut SqliteConnection,
+    entrada: &Movimiento,
+    fecha: &str,
+    documento: &str,
+    obs: Option<String>,
+) -> Result<i64> {
+    let Some(id_almacen) = entrada.id_almacen else {
+        return Err(AppError::Conflict(format!(
+            "El movimiento {} no tiene almacén; no se puede revertir",
+            entrada.id_movimiento
+        )));
+    };
+    periodo_service::verificar_abierto(&mut *conn, fecha).await?;
+    conteo_service::verificar_sin_conteo(&mut *conn, id_almacen).await?;
+
+    let lotes = lote_service::get_by_movimiento(&mut *conn, entrada.id_movimiento).await?;
+    if let Some(lote) = lotes.iter().find(|l| l.cantidad_actual < l.cantidad_inicial - EPSILON) {
+        return Err(AppError::Conflict(format!(
+            "El lote {} de la entrada {} ya tiene salidas registradas; no se puede revertir",
+            lote.id_lote, entrada.id_movimiento
+        )));
+    }
+
+    let salida = CreateMovimiento {
+        fecha: fecha.to_string(),
+        tipo: "salida".to_string(),
+        subtipo: Some(SUBTIPO_ANULACION.to_string()),
+        id_prod_prov: entrada.id_prod_prov,
+        id_presentacion: entrada.id_presentacion,
+        cantidad: entrada.cantidad,
+        obs,
+        id_factura: entrada.id_factura,
+        id_almacen,
+        documento: Some(documento.to_string()),
+        id_linea_orden: entrada.id_linea_orden,
+        ..Default::default()
+    };
+    let id = movimiento_service::create(&mut *conn, &salida).await?;
+    stock_almacen_service::ajustar(
+        &mut *conn,
+        entrada.id_prod_prov,
+        entrada.id_presentacion,
+        id_almacen,
+        -entrada.cantidad,
+    )
+    .await?;
+
+    let costo = lote_service::vaciar(&mut *conn, id as i32, &lotes).await?;
+    movimiento_service::update_costo(&mut *conn, id as i32, costo).await?;
+
+    let registrada = find(&mut *conn, id as i32).await?.ok_or(AppError::not_found("el movimiento", id))?;
+    actualizar_orden(conn, &registrada).await?;
+
+    Ok(id)
+}
+
+/// Registra una salida `devolucion` de `cantidad` tomada del lote `lote`,
+/// costeada al costo original del lote. `salida` aporta fecha, factura,
+/// documento y observaciones; producto, presentación y almacén son los del
+/// lote.
+pub(crate) async fn post_devolucion_in(
+    conn: &mut SqliteConnection,
+    lote: &Lote,
+    cantidad: f64,
+    salida: CreateMovimiento,
+) -> Result<i64> {
+    periodo_service::verificar_abierto(&mut *conn, &salida.fecha).await?;
+    conteo_service::verificar_sin_conteo(&mut *conn, lote.id_almacen).await?;
+    factura_service::verificar_no_anulada(&mut *conn, salida.id_factura).await?;
+
+    let salida = CreateMovimiento {
+        tipo: "salida".to_string(),
+        subtipo: Some(SUBTIPO_DEVOLUCION.to_string()),
+        id_prod_prov: lote.id_prod_prov,
+        id_presentacion: lote.id_presentacion,
+        cantidad,
+        id_almacen: lote.id_almacen,
+        ..salida
+    };
+    let id = movimiento_service::create(&mut *conn, &salida).await?;
+    stock_almacen_service::ajustar(
+        &mut *conn,
+        lote.id_prod_prov,
+        lote.id_presentacion,
+        lote.id_almacen,
+        -cantidad,
+    )
+    .await?;
+
+    let costo = lote_service::retirar(&mut *conn, id as i32, lote, cantidad).await?;
+    movimiento_service::update_costo(conn, id as i32, costo).await?;
+
+    Ok(id)
+}
+
+/// Revierte y elimina los movimientos de un documento registrados con
+/// `subtipo`. Falla si ya salió mercadería de algún lote que recibieron.
+pub(crate) async fn delete_documento_in(conn: &mut SqliteConnection, documento: &str, subtipo: &str) -> Result<u64> {
+    let movimientos = movimiento_service::get_by_documento(&mut *conn, documento).await?;
+    let mut rows = 0;
+
+    // Cada entrada se revierte antes que la salida que le dio sus lotes
+    for movimiento in movimientos.iter().rev() {
+        if movimiento.subtipo.as_deref() != Some(subtipo) {
+            continue;
+        }
+        periodo_service::verificar_abierto(&mut *conn, &movimiento.fecha).await?;
+        descontabilizar(&mut *conn, movimiento).await?;
+        rows += movimiento_service::delete(&mut *conn, movimiento.id_movimiento).await?;
+    }
+
+    Ok(rows)
+}
+
+/// Modifica un movimiento revirtiendo su efecto anterior en el stock y los
+/// lotes y aplicando el nuevo.
+pub async fn update(pool: &SqlitePool, id: i32, data: UpdateMovimiento) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    let Some(anterior) = find(&mut tx, id).await? else {
+        return Ok(0);
+    };
+    editable(&anterior)?;
+    if let Some(subtipo) = data.subtipo.as_deref().filter(|s| es_de_documento(s)) {
+        return Err(AppError::Conflict(format!(
+            "El subtipo '{}' lo asigna la operación que registra el documento; no se puede asignar a mano",
+            subtipo
+        )));
+    }
+    verificar_linea_orden(&anterior, &data)?;
+    periodo_service::verificar_abierto(&mut tx, &anterior.fecha).await?;
+    if let Some(fecha) = &data.fecha {
+        periodo_service::verificar_abierto(&mut tx, fecha).await?;
+    }
+    factura_service::verificar_no_anulada(&mut tx, anterior.id_factura).await?;
+    factura_service::verificar_no_anulada(&mut tx, data.id_factura).await?;
+    if let Some(nuevo) = &data.tipo {
+        tipo(nuevo)?;
+    }
+    descontabilizar(&mut tx, &anterior).await?;
+
+    let rows = movimiento_service::update(&mut tx, id, &data).await?;
+    let actual = find(&mut tx, id).await?.ok_or(AppError::not_found("el movimiento", id))?;
+    contabilizar(&mut tx, &actual).await?;
+    actualizar_orden(&mut tx, &actual).await?;
+    if anterior.id_linea_orden != actual.id_linea_orden {
+        actualizar_orden(&mut tx, &anterior).await?;
+    }
+
+    tx.commit().await?;
+    Ok(rows)
+}
+
+/// Elimina un movimiento y revierte su efecto en el stock y los lotes.
+pub async fn delete(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    let Some(anterior) = find(&mut tx, id).await? else {
+        return Ok(0);
+    };
+    editable(&anterior)?;
+    periodo_service::verificar_abierto(&mut tx, &anterior.fecha).await?;
+    factura_service::verificar_no_anulada(&mut tx, anterior.id_factura).await?;
+    descontabilizar(&mut tx, &anterior).await?;
+
+    let rows = movimiento_service::delete(&mut tx, id).await?;
+    actualizar_orden(&mut tx, &anterior).await?;
+
+    tx.commit().await?;
+    Ok(rows)
+}
+
+fn tipo(tipo: &str) -> Result<TipoMovimiento> {
+    TipoMovimiento::parse(tipo).ok_or_else(|| {
+        AppError::validation(
+            "tipo",
+            format!("Tipo de movimiento inválido: '{}' (se espera entrada, salida o ajuste)", tipo),
+        )
+    })
+}
+
+/// Los movimientos de una transferencia o conversión solo se revierten junto
+/// con su documento, porque la entrada copia los lotes que consumió la salida.
+fn es_de_documento(subtipo: &str) -> bool {
+    SUBTIPOS_DOCUMENTO.contains(&subtipo.trim().to_lowercase().as_str())
+}
+
+fn editable(movimiento: &Movimiento) -> Result<()> {
+    if movimiento.subtipo.as_deref().is_some_and(es_de_documento) {
+        return Err(AppError::Conflict(format!(
+            "El movimiento pertenece al documento {}; debe anularse el documento completo",
+            movimiento.documento.as_deref().unwrap_or("")
+        )));
+    }
+
+    Ok(())
+}
+
+/// Lo recibido contra una línea de orden de compra ya se validó contra lo
+/// pendiente de la línea; no se le puede cambiar el tipo, la cantidad, el
+/// producto ni la presentación.
+fn verificar_linea_orden(anterior: &Movimiento, data: &UpdateMovimiento) -> Result<()> {
+    let Some(linea_id) = anterior.id_linea_orden else {
+        return Ok(());
+    };
+    let cambios = [
+        ("tipo", data.tipo.as_ref().is_some_and(|t| *t != anterior.tipo)),
+        ("cantidad", data.cantidad.is_some_and(|c| (c - anterior.cantidad).abs() > EPSILON)),
+        ("id_prod_prov", data.id_prod_prov.is_some_and(|id| id != anterior.id_prod_prov)),
+        ("id_presentacion", data.id_presentacion.is_some_and(|id| id != anterior.id_presentacion)),
+    ];
+    if let Some((campo, _)) = cambios.iter().find(|(_, cambia)| *cambia) {
+        return Err(AppError::Conflict(format!(
+            "El movimiento se recibió contra la línea {} de una orden de compra; no se puede cambiar {}",
+            linea_id, campo
+        )));
+    }
+
+    Ok(())
+}
+
+/// Si el movimiento se recibió contra una orden de compra, recalcula el
+/// estado de la orden
+async fn actualizar_orden(conn: &mut SqliteConnection, movimiento: &Movimiento) -> Result<()> {
+    let Some(linea_id) = movimiento.id_linea_orden else {
+        return Ok(());
+    };
+    if let Some(orden_id) = orden_compra_service::get_orden_de_linea(&mut *conn, linea_id).await? {
+        orden_compra_service::actualizar_estado(conn, orden_id).await?;
+    }
+
+    Ok(())
+}
+
+async fn find(conn: &mut SqliteConnection, id: i32) -> Result<Option<Movimiento>> {
+    let movimiento = sqlx::query_as::<_, Movimiento>("SELECT * FROM movimiento WHERE id_movimiento = ?")
+        .bind(id)
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(movimiento)
+}
+
+/// Costo unitario con el que un movimiento que suma stock abre su lote.
+async fn costo_entrada(conn: &mut SqliteConnection, movimiento: &Movimiento, almacen_id: i32) -> Result<f64> {
+    if let Some(precio) = movimiento.precio_unit {
+        return Ok(precio);
+    }
+    if let Some(monto) = movimiento.monto_total.filter(|_| movimiento.cantidad > 0.0) {
+        return Ok(monto / movimiento.cantidad);
+    }
+    let ultimo = lote_service::ultimo_costo(
+        conn,
+        movimiento.id_prod_prov,
+        movimiento.id_presentacion,
+        almacen_id,
+    )
+    .await?;
+
+    Ok(ultimo.unwrap_or(0.0))
+}
+
+/// Aplica un movimiento ya guardado: ajusta el stock, abre su lote o consume
+/// lotes PEPS y guarda el costo de las salidas. Los movimientos sin
+/// `id_almacen` son anteriores al motor y no afectan stock.
+///
+/// Si una salida deja el stock en negativo se aplica la política del almacén:
+/// se rechaza, o se devuelve el faltante como advertencia. Los almacenes con un
+/// conteo en curso no admiten movimientos.
+async fn contabilizar(conn: &mut SqliteConnection, movimiento: &Movimiento) -> Result<Option<StockInsuficiente>> {
+    let Some(id_almacen) = movimiento.id_almacen else {
+        return Ok(None);
+    };
+    conteo_service::verificar_sin_conteo(&mut *conn, id_almacen).await?;
+    let signo = tipo(&movimiento.tipo)?.signo(movimiento.subtipo.as_deref());
+
+    let saldo = stock_almacen_service::ajustar(
+        &mut *conn,
+        movimiento.id_prod_prov,
+        movimiento.id_presentacion,
+        id_almacen,
+        signo * movimiento.cantidad,
+    )
+    .await?;
+
+    let mut advertencia = None;
+    if signo < 0.0 && saldo < -EPSILON {
+        let detalle = StockInsuficiente {
+            id_prod_prov: movimiento.id_prod_prov,
+            id_presentacion: movimiento.id_presentacion,
+            id_almacen,
+            disponible: saldo + movimiento.cantidad,
+            solicitado: movimiento.cantidad,
+            faltante: movimiento.cantidad.min(-saldo),
+        };
+        match configuracion_service::get_politica_stock(&mut *conn, Some(id_almacen)).await? {
+            PoliticaStock::Rechazar => return Err(AppError::InsufficientStock(detalle)),
+            PoliticaStock::Advertir => advertencia = Some(detalle),
+            PoliticaStock::Permitir => {}
+        }
+    }
+
+    if signo > 0.0 {
+        let costo = costo_entrada(&mut *conn, movimiento, id_almacen).await?;
+        lote_service::abrir(conn, movimiento, id_almacen, costo).await?;
+    } else {
+        let costo = lote_service::consumir(&mut *conn, movimiento, id_almacen).await?;
+        movimiento_service::update_costo(conn, movimiento.id_movimiento, costo).await?;
+    }
+
+    Ok(advertencia)
+}
+
+/// Revierte lo hecho por `contabilizar`. Falla si el movimiento abrió un lote
+/// del que ya salió mercadería.
+async fn descontabilizar(conn: &mut SqliteConnection, movimiento: &Movimiento) -> Result<()> {
+    let Some(id_almacen) = movimiento.id_almacen else {
+        return Ok(());
+    };
+    conteo_service::verificar_sin_conteo(&mut *conn, id_almacen).await?;
+    let signo = tipo(&movimiento.tipo)?.signo(movimiento.subtipo.as_deref());
+
+    let lotes = lote_service::get_by_movimiento(&mut *conn, movimiento.id_movimiento).await?;
+    if let Some(lote) = lotes.iter().find(|l| l.cantidad_actual < l.cantidad_inicial - EPSILON) {
+        return Err(AppError::Conflict(format!(
+            "El lote {} ya tiene salidas registradas; no se puede modificar su entrada",
+            lote.id_lote
+        )));
+    }
+
+    stock_almacen_service::ajustar(
+        &mut *conn,
+        movimiento.id_prod_prov,
+        movimiento.id_presentacion,
+        id_almacen,
+        -signo * movimiento.cantidad,
+    )
+    .await?;
+    lote_service::revertir(conn, movimiento.id_movimiento).await?;
+
+    Ok(())
+}
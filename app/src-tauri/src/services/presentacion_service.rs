@@ -1,17 +1,29 @@
-use sqlx::{SqlitePool, Result};
+use sqlx::{SqlitePool, SqliteConnection, SqliteExecutor};
+use crate::error::{AppError, Result};
 use crate::models::presentacion::{Presentacion, CreatePresentacion, UpdatePresentacion};
+use crate::services::dependencia_service::{self, Referencia};
+
+const DEPENDIENTES: &[Referencia] = &[
+    ("movimiento", "id_presentacion = ?"),
+    ("lote", "id_presentacion = ?"),
+    ("stock_almacen", "id_presentacion = ?"),
+    ("conteo_detalle", "id_presentacion = ?"),
+    ("orden_compra_linea", "id_presentacion = ?"),
+];
 
 pub async fn get_all(pool: &SqlitePool) -> Result<Vec<Presentacion>> {
     sqlx::query_as::<_, Presentacion>("SELECT * FROM presentacion ORDER BY id_presentacion ASC")
         .fetch_all(pool)
         .await
+        .map_err(AppError::from)
 }
 
-pub async fn get_by_id(pool: &SqlitePool, id: i32) -> Result<Option<Presentacion>> {
+pub async fn get_by_id<'e, E: SqliteExecutor<'e>>(executor: E, id: i32) -> Result<Option<Presentacion>> {
     sqlx::query_as::<_, Presentacion>("SELECT * FROM presentacion WHERE id_presentacion = ?")
         .bind(id)
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await
+        .map_err(AppError::from)
 }
 
 pub async fn get_by_producto(pool: &SqlitePool, producto_id: i32) -> Result<Vec<Presentacion>> {
@@ -21,6 +33,7 @@ pub async fn get_by_producto(pool: &SqlitePool, producto_id: i32) -> Result<Vec<
     .bind(producto_id)
     .fetch_all(pool)
     .await
+    .map_err(AppError::from)
 }
 
 pub async fn create(pool: &SqlitePool, data: CreatePresentacion) -> Result<i64> {
@@ -58,9 +71,28 @@ pub async fn update(pool: &SqlitePool, id: i32, data: UpdatePresentacion) -> Res
     Ok(result.rows_affected())
 }
 
+/// Elimina la presentación junto con sus filas de stock en cero. Se rechaza si
+/// tiene movimientos, lotes o stock.
 pub async fn delete(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+    let rows = delete_in(&mut tx, id).await?;
+    tx.commit().await?;
+
+    Ok(rows)
+}
+
+pub(crate) async fn delete_in(conn: &mut SqliteConnection, id: i32) -> Result<u64> {
+    sqlx::query!(
+        "DELETE FROM stock_almacen WHERE id_presentacion = ? AND stock_actual = 0",
+        id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    dependencia_service::restringir(conn, DEPENDIENTES, id).await?;
+
     let result = sqlx::query!("DELETE FROM presentacion WHERE id_presentacion = ?", id)
-        .execute(pool)
+        .execute(conn)
         .await?;
     
     Ok(result.rows_affected())
@@ -1,17 +1,65 @@
-use sqlx::{SqlitePool, Result};
+use sqlx::{SqlitePool, SqliteConnection, SqliteExecutor};
+use crate::error::{AppError, Result};
 use crate::models::producto_proveedor::{ProductoProveedor, CreateProductoProveedor, UpdateProductoProveedor};
+use crate::services::dependencia_service::{self, Referencia};
+
+const DEPENDIENTES: &[Referencia] = &[
+    ("movimiento", "id_prod_prov = ?"),
+    ("lote", "id_prod_prov = ?"),
+    ("stock_almacen", "id_prod_prov = ?"),
+    ("conteo_detalle", "id_prod_prov = ?"),
+    ("orden_compra_linea", "id_prod_prov = ?"),
+];
 
 pub async fn get_all(pool: &SqlitePool) -> Result<Vec<ProductoProveedor>> {
     sqlx::query_as::<_, ProductoProveedor>("SELECT * FROM producto_proveedor ORDER BY id_prod_prov ASC")
         .fetch_all(pool)
         .await
+        .map_err(AppError::from)
 }
 
-pub async fn get_by_id(pool: &SqlitePool, id: i32) -> Result<Option<ProductoProveedor>> {
+pub async fn get_by_id<'e, E: SqliteExecutor<'e>>(executor: E, id: i32) -> Result<Option<ProductoProveedor>> {
     sqlx::query_as::<_, ProductoProveedor>("SELECT * FROM producto_proveedor WHERE id_prod_prov = ?")
         .bind(id)
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await
+        .map_err(AppError::from)
+}
+
+/// Indica si la relación es del proveedor y la presentación, de su producto
+pub(crate) async fn corresponde(
+    conn: &mut SqliteConnection,
+    id: i32,
+    proveedor_id: i32,
+    presentacion_id: i32,
+) -> Result<bool> {
+    let corresponde = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM producto_proveedor pp
+         JOIN presentacion pr ON pr.id_producto = pp.id_producto
+         WHERE pp.id_prod_prov = ? AND pp.id_proveedor = ? AND pr.id_presentacion = ?"
+    )
+    .bind(id)
+    .bind(proveedor_id)
+    .bind(presentacion_id)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(corresponde)
+}
+
+/// Indica si la presentación es del producto de la relación
+pub(crate) async fn corresponde_presentacion(conn: &mut SqliteConnection, id: i32, presentacion_id: i32) -> Result<bool> {
+    let corresponde = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM producto_proveedor pp
+         JOIN presentacion pr ON pr.id_producto = pp.id_producto
+         WHERE pp.id_prod_prov = ? AND pr.id_presentacion = ?"
+    )
+    .bind(id)
+    .bind(presentacion_id)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(corresponde)
 }
 
 pub async fn create(pool: &SqlitePool, data: CreateProductoProveedor) -> Result<i64> {
@@ -50,9 +98,28 @@ pub async fn update(pool: &SqlitePool, id: i32, data: UpdateProductoProveedor) -
     Ok(result.rows_affected())
 }
 
+/// Elimina la relación junto con sus filas de stock en cero. Se rechaza si
+/// tiene movimientos, lotes o stock.
 pub async fn delete(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+    let rows = delete_in(&mut tx, id).await?;
+    tx.commit().await?;
+
+    Ok(rows)
+}
+
+pub(crate) async fn delete_in(conn: &mut SqliteConnection, id: i32) -> Result<u64> {
+    sqlx::query!(
+        "DELETE FROM stock_almacen WHERE id_prod_prov = ? AND stock_actual = 0",
+        id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    dependencia_service::restringir(conn, DEPENDIENTES, id).await?;
+
     let result = sqlx::query!("DELETE FROM producto_proveedor WHERE id_prod_prov = ?", id)
-        .execute(pool)
+        .execute(conn)
         .await?;
     
     Ok(result.rows_affected())
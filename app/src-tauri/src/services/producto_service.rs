@@ -1,17 +1,35 @@
-use sqlx::{SqlitePool, Result};
+use sqlx::SqlitePool;
+use crate::error::{AppError, Result};
 use crate::models::producto::{Producto, CreateProducto, UpdateProducto};
+use crate::services::dependencia_service::{self, Referencia};
+use crate::services::{presentacion_service, producto_proveedor_service};
 
-pub async fn get_all(pool: &SqlitePool) -> Result<Vec<Producto>> {
-    sqlx::query_as::<_, Producto>("SELECT * FROM producto ORDER BY id_producto ASC")
-        .fetch_all(pool)
-        .await
+/// Registros que impiden purgar un producto
+const DEPENDIENTES: &[Referencia] = &[(
+    "movimiento",
+    "id_prod_prov IN (SELECT id_prod_prov FROM producto_proveedor WHERE id_producto = ?)",
+)];
+
+/// Los productos archivados solo se incluyen si se piden
+pub async fn get_all(pool: &SqlitePool, incluir_archivados: bool) -> Result<Vec<Producto>> {
+    sqlx::query_as::<_, Producto>(
+        "SELECT * FROM producto WHERE (? OR eliminado_en IS NULL) ORDER BY id_producto ASC"
+    )
+    .bind(incluir_archivados)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::from)
 }
 
-pub async fn get_by_id(pool: &SqlitePool, id: i32) -> Result<Option<Producto>> {
-    sqlx::query_as::<_, Producto>("SELECT * FROM producto WHERE id_producto = ?")
-        .bind(id)
-        .fetch_optional(pool)
-        .await
+pub async fn get_by_id(pool: &SqlitePool, id: i32, incluir_archivados: bool) -> Result<Option<Producto>> {
+    sqlx::query_as::<_, Producto>(
+        "SELECT * FROM producto WHERE id_producto = ? AND (? OR eliminado_en IS NULL)"
+    )
+    .bind(id)
+    .bind(incluir_archivados)
+    .fetch_optional(pool)
+    .await
+    .map_err(AppError::from)
 }
 
 pub async fn create(pool: &SqlitePool, data: CreateProducto) -> Result<i64> {
@@ -53,10 +71,72 @@ pub async fn update(pool: &SqlitePool, id: i32, data: UpdateProducto) -> Result<
     Ok(result.rows_affected())
 }
 
+/// Archiva el producto: deja de listarse pero conserva su historial.
 pub async fn delete(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let result = sqlx::query!(
+        "UPDATE producto SET
+            eliminado_en = datetime('now'),
+            eliminado_por = (SELECT usuario FROM sesion WHERE id_sesion = 1)
+         WHERE id_producto = ? AND eliminado_en IS NULL",
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn restore(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let result = sqlx::query!(
+        "UPDATE producto SET eliminado_en = NULL, eliminado_por = NULL
+         WHERE id_producto = ? AND eliminado_en IS NOT NULL",
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Elimina definitivamente un producto archivado, con sus relaciones con
+/// proveedores, sus presentaciones y sus parámetros de stock. Se rechaza si tiene movimientos.
+pub async fn purge(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    if !dependencia_service::verificar_archivado(&mut tx, "producto", "id_producto", id).await? {
+        return Ok(0);
+    }
+    dependencia_service::restringir(&mut tx, DEPENDIENTES, id).await?;
+
+    let relaciones: Vec<i32> = sqlx::query_scalar(
+        "SELECT id_prod_prov FROM producto_proveedor WHERE id_producto = ?"
+    )
+    .bind(id)
+    .fetch_all(&mut *tx)
+    .await?;
+    for prod_prov_id in relaciones {
+        producto_proveedor_service::delete_in(&mut tx, prod_prov_id).await?;
+    }
+
+    let presentaciones: Vec<i32> = sqlx::query_scalar(
+        "SELECT id_presentacion FROM presentacion WHERE id_producto = ?"
+    )
+    .bind(id)
+    .fetch_all(&mut *tx)
+    .await?;
+    for presentacion_id in presentaciones {
+        presentacion_service::delete_in(&mut tx, presentacion_id).await?;
+    }
+
+    sqlx::query!("DELETE FROM stock_parametro WHERE id_producto = ?", id)
+        .execute(&mut *tx)
+        .await?;
+
     let result = sqlx::query!("DELETE FROM producto WHERE id_producto = ?", id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
+
+    tx.commit().await?;
     
     Ok(result.rows_affected())
 }
@@ -1,30 +1,60 @@
-use sqlx::{SqlitePool, Result};
+use sqlx::SqlitePool;
+use crate::error::{AppError, Result};
+use crate::identificacion::{self, PAIS_PREDETERMINADO};
 use crate::models::proveedor::{Proveedor, CreateProveedor, UpdateProveedor};
+use crate::services::dependencia_service::{self, Referencia};
+use crate::services::producto_proveedor_service;
 
-pub async fn get_all(pool: &SqlitePool) -> Result<Vec<Proveedor>> {
-    sqlx::query_as::<_, Proveedor>("SELECT * FROM proveedor ORDER BY id_proveedor ASC")
-        .fetch_all(pool)
-        .await
+/// Registros que impiden purgar un proveedor
+const DEPENDIENTES: &[Referencia] = &[
+    (
+        "movimiento",
+        "id_prod_prov IN (SELECT id_prod_prov FROM producto_proveedor WHERE id_proveedor = ?)",
+    ),
+    ("factura", "id_proveedor = ?"),
+    ("orden_compra", "id_proveedor = ?"),
+    ("pago", "id_proveedor = ?"),
+    ("nota_credito", "id_proveedor = ?"),
+];
+
+/// Los proveedores archivados solo se incluyen si se piden
+pub async fn get_all(pool: &SqlitePool, incluir_archivados: bool) -> Result<Vec<Proveedor>> {
+    sqlx::query_as::<_, Proveedor>(
+        "SELECT * FROM proveedor WHERE (? OR eliminado_en IS NULL) ORDER BY id_proveedor ASC"
+    )
+    .bind(incluir_archivados)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::from)
 }
 
-pub async fn get_by_id(pool: &SqlitePool, id: i32) -> Result<Option<Proveedor>> {
-    sqlx::query_as::<_, Proveedor>("SELECT * FROM proveedor WHERE id_proveedor = ?")
-        .bind(id)
-        .fetch_optional(pool)
-        .await
+pub async fn get_by_id(pool: &SqlitePool, id: i32, incluir_archivados: bool) -> Result<Option<Proveedor>> {
+    sqlx::query_as::<_, Proveedor>(
+        "SELECT * FROM proveedor WHERE id_proveedor = ? AND (? OR eliminado_en IS NULL)"
+    )
+    .bind(id)
+    .bind(incluir_archivados)
+    .fetch_optional(pool)
+    .await
+    .map_err(AppError::from)
 }
 
 pub async fn create(pool: &SqlitePool, data: CreateProveedor) -> Result<i64> {
     let estado = data.estado.unwrap_or_else(|| "Activo".to_string());
+    let pais = data.pais.unwrap_or_else(|| PAIS_PREDETERMINADO.to_string());
+    let ruc_ci = identificador_unico(pool, &pais, &data.ruc_ci, None).await?;
+
     let result = sqlx::query!(
-        "INSERT INTO proveedor (ruc_ci, nombre, contacto, telefono, email, estado) 
-         VALUES (?, ?, ?, ?, ?, ?)",
-        data.ruc_ci,
+        "INSERT INTO proveedor (ruc_ci, nombre, contacto, telefono, email, estado, pais, dias_credito) 
+         VALUES (?, ?, ?, ?, ?, ?, ?, COALESCE(?, 0))",
+        ruc_ci,
         data.nombre,
         data.contacto,
         data.telefono,
         data.email,
-        estado
+        estado,
+        pais,
+        data.dias_credito
     )
     .execute(pool)
     .await?;
@@ -33,6 +63,19 @@ pub async fn create(pool: &SqlitePool, data: CreateProveedor) -> Result<i64> {
 }
 
 pub async fn update(pool: &SqlitePool, id: i32, data: UpdateProveedor) -> Result<u64> {
+    let Some(actual) = get_by_id(pool, id, true).await? else {
+        return Ok(0);
+    };
+
+    // Un cambio de país obliga a revalidar el identificador guardado
+    let ruc_ci = if data.ruc_ci.is_some() || data.pais.is_some() {
+        let pais = data.pais.as_deref().unwrap_or(&actual.pais);
+        let ruc_ci = data.ruc_ci.as_deref().unwrap_or(&actual.ruc_ci);
+        Some(identificador_unico(pool, pais, ruc_ci, Some(id)).await?)
+    } else {
+        None
+    };
+
     let result = sqlx::query!(
         "UPDATE proveedor SET
             ruc_ci = COALESCE(?, ruc_ci),
@@ -40,14 +83,18 @@ pub async fn update(pool: &SqlitePool, id: i32, data: UpdateProveedor) -> Result
             contacto = COALESCE(?, contacto),
             telefono = COALESCE(?, telefono),
             email = COALESCE(?, email),
-            estado = COALESCE(?, estado)
+            estado = COALESCE(?, estado),
+            pais = COALESCE(?, pais),
+            dias_credito = COALESCE(?, dias_credito)
          WHERE id_proveedor = ?",
-        data.ruc_ci,
+        ruc_ci,
         data.nombre,
         data.contacto,
         data.telefono,
         data.email,
         data.estado,
+        data.pais,
+        data.dias_credito,
         id
     )
     .execute(pool)
@@ -56,10 +103,90 @@ pub async fn update(pool: &SqlitePool, id: i32, data: UpdateProveedor) -> Result
     Ok(result.rows_affected())
 }
 
+/// Valida `ruc_ci` según el país y verifica que ningún otro proveedor tenga el
+/// mismo identificador una vez normalizado. Devuelve la forma normalizada.
+async fn identificador_unico(
+    pool: &SqlitePool,
+    pais: &str,
+    ruc_ci: &str,
+    excluir_id: Option<i32>,
+) -> Result<String> {
+    let normalizado = identificacion::validar(pais, ruc_ci)
+        .map_err(|mensaje| AppError::validation("ruc_ci", mensaje))?;
+
+    // Los registros anteriores pueden estar guardados con guiones o espacios
+    let existente: Option<i32> = sqlx::query_scalar(
+        "SELECT id_proveedor FROM proveedor
+         WHERE UPPER(REPLACE(REPLACE(ruc_ci, '-', ''), ' ', '')) = ? AND id_proveedor IS NOT ?
+         LIMIT 1"
+    )
+    .bind(&normalizado)
+    .bind(excluir_id)
+    .fetch_optional(pool)
+    .await?;
+
+    if existente.is_some() {
+        return Err(AppError::Duplicate {
+            tabla: "proveedor".to_string(),
+            field: "ruc_ci".to_string(),
+        });
+    }
+
+    Ok(normalizado)
+}
+
+/// Archiva el proveedor: deja de listarse pero conserva su historial.
 pub async fn delete(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let result = sqlx::query!(
+        "UPDATE proveedor SET
+            eliminado_en = datetime('now'),
+            eliminado_por = (SELECT usuario FROM sesion WHERE id_sesion = 1)
+         WHERE id_proveedor = ? AND eliminado_en IS NULL",
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn restore(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let result = sqlx::query!(
+        "UPDATE proveedor SET eliminado_en = NULL, eliminado_por = NULL
+         WHERE id_proveedor = ? AND eliminado_en IS NOT NULL",
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Elimina definitivamente un proveedor archivado y sus relaciones con
+/// productos. Se rechaza si tiene facturas o movimientos.
+pub async fn purge(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    if !dependencia_service::verificar_archivado(&mut tx, "proveedor", "id_proveedor", id).await? {
+        return Ok(0);
+    }
+    dependencia_service::restringir(&mut tx, DEPENDIENTES, id).await?;
+
+    let relaciones: Vec<i32> = sqlx::query_scalar(
+        "SELECT id_prod_prov FROM producto_proveedor WHERE id_proveedor = ?"
+    )
+    .bind(id)
+    .fetch_all(&mut *tx)
+    .await?;
+    for prod_prov_id in relaciones {
+        producto_proveedor_service::delete_in(&mut tx, prod_prov_id).await?;
+    }
+
     let result = sqlx::query!("DELETE FROM proveedor WHERE id_proveedor = ?", id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
+
+    tx.commit().await?;
     
     Ok(result.rows_affected())
 }
@@ -0,0 +1,34 @@
+use sqlx::{SqlitePool, SqliteExecutor};
+use crate::error::{AppError, Result};
+
+/// Usuario que opera la aplicación, si se registró alguno
+pub async fn get_usuario<'e, E: SqliteExecutor<'e>>(executor: E) -> Result<Option<String>> {
+    sqlx::query_scalar::<_, String>("SELECT usuario FROM sesion WHERE id_sesion = 1")
+        .fetch_optional(executor)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Registra el usuario que opera la aplicación; con `None` se cierra la sesión.
+/// Queda como responsable de los archivados y cambios posteriores.
+pub async fn set_usuario(pool: &SqlitePool, usuario: Option<&str>) -> Result<()> {
+    match usuario.map(str::trim) {
+        Some("") => return Err(AppError::validation("usuario", "Campo obligatorio")),
+        Some(usuario) => {
+            sqlx::query!(
+                "INSERT INTO sesion (id_sesion, usuario) VALUES (1, ?)
+                 ON CONFLICT (id_sesion) DO UPDATE SET usuario = excluded.usuario",
+                usuario
+            )
+            .execute(pool)
+            .await?;
+        }
+        None => {
+            sqlx::query!("DELETE FROM sesion")
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
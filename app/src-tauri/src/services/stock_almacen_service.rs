@@ -1,10 +1,12 @@
-use sqlx::{SqlitePool, Result};
+use sqlx::{SqlitePool, SqliteConnection, SqliteExecutor};
+use crate::error::{AppError, Result};
 use crate::models::stock_almacen::{StockAlmacen, CreateStockAlmacen, UpdateStockAlmacen};
 
 pub async fn get_all(pool: &SqlitePool) -> Result<Vec<StockAlmacen>> {
     sqlx::query_as::<_, StockAlmacen>("SELECT * FROM stock_almacen ORDER BY id_stock ASC")
         .fetch_all(pool)
         .await
+        .map_err(AppError::from)
 }
 
 pub async fn get_by_id(pool: &SqlitePool, id: i32) -> Result<Option<StockAlmacen>> {
@@ -12,6 +14,7 @@ pub async fn get_by_id(pool: &SqlitePool, id: i32) -> Result<Option<StockAlmacen
         .bind(id)
         .fetch_optional(pool)
         .await
+        .map_err(AppError::from)
 }
 
 pub async fn get_by_almacen(pool: &SqlitePool, almacen_id: i32) -> Result<Vec<StockAlmacen>> {
@@ -21,6 +24,7 @@ pub async fn get_by_almacen(pool: &SqlitePool, almacen_id: i32) -> Result<Vec<St
     .bind(almacen_id)
     .fetch_all(pool)
     .await
+    .map_err(AppError::from)
 }
 
 pub async fn get_by_producto_proveedor(pool: &SqlitePool, prod_prov_id: i32) -> Result<Vec<StockAlmacen>> {
@@ -30,6 +34,63 @@ pub async fn get_by_producto_proveedor(pool: &SqlitePool, prod_prov_id: i32) ->
     .bind(prod_prov_id)
     .fetch_all(pool)
     .await
+    .map_err(AppError::from)
+}
+
+/// Busca la fila de stock de un producto/presentación en un almacén.
+pub async fn get_by_ubicacion<'e, E: SqliteExecutor<'e>>(
+    executor: E,
+    prod_prov_id: i32,
+    presentacion_id: i32,
+    almacen_id: i32,
+) -> Result<Option<StockAlmacen>> {
+    sqlx::query_as::<_, StockAlmacen>(
+        "SELECT * FROM stock_almacen WHERE id_prod_prov = ? AND id_presentacion = ? AND id_almacen = ?"
+    )
+    .bind(prod_prov_id)
+    .bind(presentacion_id)
+    .bind(almacen_id)
+    .fetch_optional(executor)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Suma `delta` al stock de la ubicación, creando la fila si no existe.
+/// Devuelve el stock resultante.
+pub async fn ajustar(
+    conn: &mut SqliteConnection,
+    prod_prov_id: i32,
+    presentacion_id: i32,
+    almacen_id: i32,
+    delta: f64,
+) -> Result<f64> {
+    match get_by_ubicacion(&mut *conn, prod_prov_id, presentacion_id, almacen_id).await? {
+        Some(stock) => {
+            sqlx::query!(
+                "UPDATE stock_almacen SET stock_actual = stock_actual + ? WHERE id_stock = ?",
+                delta,
+                stock.id_stock
+            )
+            .execute(conn)
+            .await?;
+
+            Ok(stock.stock_actual + delta)
+        }
+        None => {
+            sqlx::query!(
+                "INSERT INTO stock_almacen (id_prod_prov, id_presentacion, id_almacen, stock_actual) 
+                 VALUES (?, ?, ?, ?)",
+                prod_prov_id,
+                presentacion_id,
+                almacen_id,
+                delta
+            )
+            .execute(conn)
+            .await?;
+
+            Ok(delta)
+        }
+    }
 }
 
 pub async fn create(pool: &SqlitePool, data: CreateStockAlmacen) -> Result<i64> {
@@ -0,0 +1,137 @@
+use sqlx::{SqlitePool, SqliteConnection};
+use crate::error::{AppError, Result};
+use crate::models::stock_historico::{CorteStock, FiltroStockHistorico, SaldoHistorico, StockHistorico};
+use crate::services::movimiento_service;
+
+pub async fn get_cortes(pool: &SqlitePool) -> Result<Vec<CorteStock>> {
+    sqlx::query_as::<_, CorteStock>("SELECT * FROM corte_stock ORDER BY fecha DESC")
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Stock de cada ubicación al final del día pedido. Parte del último corte
+/// hasta esa fecha y suma los movimientos posteriores al corte; sin cortes
+/// repasa todos los movimientos. Solo cuentan los movimientos con almacén.
+pub async fn get_stock_a_fecha(pool: &SqlitePool, filtro: FiltroStockHistorico) -> Result<StockHistorico> {
+    let mut conn = pool.acquire().await?;
+    let (base, saldos) = saldos(&mut conn, &filtro).await?;
+
+    Ok(StockHistorico {
+        fecha: filtro.fecha,
+        id_corte_base: base.map(|c| c.id_corte),
+        saldos,
+    })
+}
+
+/// Guarda el stock de todas las ubicaciones a la fecha, reemplazando el corte
+/// que ya hubiera en ese día
+pub async fn crear_corte(pool: &SqlitePool, fecha: &str) -> Result<CorteStock> {
+    let mut tx = pool.begin().await?;
+    let corte = crear_corte_in(&mut tx, fecha).await?;
+    tx.commit().await?;
+
+    Ok(corte)
+}
+
+/// Igual que `crear_corte`, pero dentro de una transacción abierta por el
+/// llamador
+pub(crate) async fn crear_corte_in(conn: &mut SqliteConnection, fecha: &str) -> Result<CorteStock> {
+    let filtro = FiltroStockHistorico {
+        fecha: fecha.to_string(),
+        ..Default::default()
+    };
+    let (_, saldos) = saldos(&mut *conn, &filtro).await?;
+
+    let anterior: Option<i32> = sqlx::query_scalar("SELECT id_corte FROM corte_stock WHERE fecha = ?")
+        .bind(fecha)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if let Some(anterior) = anterior {
+        borrar(&mut *conn, anterior).await?;
+    }
+    let id = sqlx::query!("INSERT INTO corte_stock (fecha) VALUES (?)", fecha)
+        .execute(&mut *conn)
+        .await?
+        .last_insert_rowid();
+
+    for saldo in &saldos {
+        sqlx::query!(
+            "INSERT INTO corte_stock_detalle (id_corte, id_prod_prov, id_presentacion, id_almacen, cantidad)
+             VALUES (?, ?, ?, ?, ?)",
+            id,
+            saldo.id_prod_prov,
+            saldo.id_presentacion,
+            saldo.id_almacen,
+            saldo.cantidad
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    sqlx::query_as::<_, CorteStock>("SELECT * FROM corte_stock WHERE id_corte = ?")
+        .bind(id)
+        .fetch_one(conn)
+        .await
+        .map_err(AppError::from)
+}
+
+pub async fn delete_corte(pool: &SqlitePool, id: i32) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+    let rows = borrar(&mut tx, id).await?;
+    tx.commit().await?;
+
+    Ok(rows)
+}
+
+/// Último corte hasta la fecha del filtro y los saldos a esa fecha
+async fn saldos(
+    conn: &mut SqliteConnection,
+    filtro: &FiltroStockHistorico,
+) -> Result<(Option<CorteStock>, Vec<SaldoHistorico>)> {
+    let base = sqlx::query_as::<_, CorteStock>(
+        "SELECT * FROM corte_stock WHERE fecha <= ? ORDER BY fecha DESC LIMIT 1"
+    )
+    .bind(&filtro.fecha)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let sql = format!(
+        "WITH saldos AS (
+            SELECT id_prod_prov, id_presentacion, id_almacen, cantidad
+            FROM corte_stock_detalle WHERE id_corte = ?1
+            UNION ALL
+            SELECT m.id_prod_prov, m.id_presentacion, m.id_almacen, {}
+            FROM movimiento m
+            WHERE m.id_almacen IS NOT NULL AND m.fecha > ?2 AND m.fecha <= ?3
+        )
+        SELECT id_prod_prov, id_presentacion, id_almacen, SUM(cantidad) AS cantidad
+        FROM saldos
+        WHERE (?4 IS NULL OR id_almacen = ?4) AND (?5 IS NULL OR id_prod_prov = ?5)
+        GROUP BY id_prod_prov, id_presentacion, id_almacen
+        HAVING ABS(SUM(cantidad)) > 1e-9
+        ORDER BY id_almacen ASC, id_prod_prov ASC, id_presentacion ASC",
+        movimiento_service::CANTIDAD_CON_SIGNO
+    );
+    let saldos = sqlx::query_as::<_, SaldoHistorico>(&sql)
+        .bind(base.as_ref().map(|c| c.id_corte))
+        .bind(base.as_ref().map_or("", |c| c.fecha.as_str()))
+        .bind(&filtro.fecha)
+        .bind(filtro.id_almacen)
+        .bind(filtro.id_prod_prov)
+        .fetch_all(conn)
+        .await?;
+
+    Ok((base, saldos))
+}
+
+async fn borrar(conn: &mut SqliteConnection, id: i32) -> Result<u64> {
+    sqlx::query!("DELETE FROM corte_stock_detalle WHERE id_corte = ?", id)
+        .execute(&mut *conn)
+        .await?;
+    let result = sqlx::query!("DELETE FROM corte_stock WHERE id_corte = ?", id)
+        .execute(conn)
+        .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,172 @@
+use sqlx::SqlitePool;
+use crate::error::{AppError, Result};
+use crate::models::producto::Producto;
+use crate::models::stock_parametro::{NivelStock, SetStockParametro, StockParametro, SugerenciaReorden};
+
+/// Stock en unidad base de cada producto con parámetros, por almacén no
+/// archivado, junto con sus parámetros efectivos: los del almacén y, para los
+/// campos que este no define, los generales. `en_transito` es lo pendiente de
+/// recibir en las órdenes de compra enviadas al almacén.
+const NIVELES: &str = "
+    WITH parametros AS (
+        SELECT p.id_producto, p.codigo_interno, p.descripcion, a.id_almacen,
+               COALESCE(e.stock_minimo, g.stock_minimo) AS stock_minimo,
+               COALESCE(e.stock_maximo, g.stock_maximo) AS stock_maximo,
+               COALESCE(e.punto_reorden, g.punto_reorden) AS punto_reorden
+        FROM producto p
+        CROSS JOIN almacen a
+        LEFT JOIN stock_parametro g ON g.id_producto = p.id_producto AND g.id_almacen IS NULL
+        LEFT JOIN stock_parametro e ON e.id_producto = p.id_producto AND e.id_almacen = a.id_almacen
+        WHERE p.eliminado_en IS NULL AND a.eliminado_en IS NULL
+          AND (g.id_parametro IS NOT NULL OR e.id_parametro IS NOT NULL)
+    ),
+    existencias AS (
+        SELECT pr.id_producto, s.id_almacen, SUM(s.stock_actual * pr.cantidad) AS stock_base
+        FROM stock_almacen s
+        JOIN presentacion pr ON pr.id_presentacion = s.id_presentacion
+        GROUP BY pr.id_producto, s.id_almacen
+    ),
+    transito AS (
+        SELECT pr.id_producto, o.id_almacen,
+               SUM((l.cantidad - l.cantidad_recibida) * pr.cantidad) AS en_transito
+        FROM orden_compra_linea_saldo l
+        JOIN orden_compra o ON o.id_orden = l.id_orden
+        JOIN presentacion pr ON pr.id_presentacion = l.id_presentacion
+        WHERE o.estado IN ('Enviada', 'Recibida parcialmente') AND l.cantidad > l.cantidad_recibida
+        GROUP BY pr.id_producto, o.id_almacen
+    ),
+    niveles AS (
+        SELECT par.*, COALESCE(ex.stock_base, 0.0) AS stock_base, COALESCE(tr.en_transito, 0.0) AS en_transito
+        FROM parametros par
+        LEFT JOIN existencias ex ON ex.id_producto = par.id_producto AND ex.id_almacen = par.id_almacen
+        LEFT JOIN transito tr ON tr.id_producto = par.id_producto AND tr.id_almacen = par.id_almacen
+    )";
+
+/// Un nivel está bajo si, contando lo que está en tránsito, no alcanza el
+/// mínimo o llegó al punto de reorden
+const BAJO: &str = "(n.stock_base + n.en_transito < n.stock_minimo
+                      OR n.stock_base + n.en_transito <= n.punto_reorden)";
+
+pub async fn get_by_producto(pool: &SqlitePool, producto_id: i32) -> Result<Vec<StockParametro>> {
+    sqlx::query_as::<_, StockParametro>(
+        "SELECT * FROM stock_parametro WHERE id_producto = ? ORDER BY id_almacen IS NOT NULL, id_almacen"
+    )
+    .bind(producto_id)
+    .fetch_all(pool)
+    .await
+    .map_err(AppError::from)
+}
+
+/// Guarda los parámetros generales o los de un almacén. Sin ningún valor se
+/// elimina la fila, y el almacén vuelve a usar los generales.
+pub async fn set(pool: &SqlitePool, data: SetStockParametro) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        "DELETE FROM stock_parametro WHERE id_producto = ? AND id_almacen IS ?",
+        data.id_producto,
+        data.id_almacen
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    if data.stock_minimo.is_some() || data.stock_maximo.is_some() || data.punto_reorden.is_some() {
+        sqlx::query!(
+            "INSERT INTO stock_parametro (id_producto, id_almacen, stock_minimo, stock_maximo, punto_reorden)
+             VALUES (?, ?, ?, ?, ?)",
+            data.id_producto,
+            data.id_almacen,
+            data.stock_minimo,
+            data.stock_maximo,
+            data.punto_reorden
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Niveles de stock de los productos con parámetros, en un almacén o en todos
+pub async fn get_niveles(pool: &SqlitePool, almacen_id: Option<i32>) -> Result<Vec<NivelStock>> {
+    let sql = format!(
+        "{} SELECT * FROM niveles n
+         WHERE (? IS NULL OR n.id_almacen = ?)
+         ORDER BY n.id_producto ASC, n.id_almacen ASC",
+        NIVELES
+    );
+    sqlx::query_as::<_, NivelStock>(&sql)
+        .bind(almacen_id)
+        .bind(almacen_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Niveles bajo el mínimo o en el punto de reorden
+pub async fn get_stock_bajo(pool: &SqlitePool, almacen_id: Option<i32>) -> Result<Vec<NivelStock>> {
+    let sql = format!(
+        "{} SELECT * FROM niveles n
+         WHERE {} AND (? IS NULL OR n.id_almacen = ?)
+         ORDER BY n.id_producto ASC, n.id_almacen ASC",
+        NIVELES, BAJO
+    );
+    sqlx::query_as::<_, NivelStock>(&sql)
+        .bind(almacen_id)
+        .bind(almacen_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Productos con stock bajo en al menos un almacén
+pub async fn get_productos_stock_bajo(pool: &SqlitePool) -> Result<Vec<Producto>> {
+    let sql = format!(
+        "{} SELECT * FROM producto
+         WHERE id_producto IN (SELECT n.id_producto FROM niveles n WHERE {})
+         ORDER BY id_producto ASC",
+        NIVELES, BAJO
+    );
+    sqlx::query_as::<_, Producto>(&sql)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Cantidad a pedir para cada producto con stock bajo que tenga stock máximo
+pub async fn get_sugerencias(pool: &SqlitePool, almacen_id: Option<i32>) -> Result<Vec<SugerenciaReorden>> {
+    let sql = format!(
+        "{},
+        sugerencias AS (
+            SELECT n.*,
+                   COALESCE(
+                       (SELECT m.id_prod_prov FROM movimiento m
+                        JOIN producto_proveedor ult ON ult.id_prod_prov = m.id_prod_prov
+                        WHERE ult.id_producto = n.id_producto AND m.tipo = 'entrada'
+                          AND COALESCE(m.subtipo, '') NOT IN ('transferencia', 'conversion')
+                        ORDER BY m.fecha DESC, m.id_movimiento DESC
+                        LIMIT 1),
+                       (SELECT MIN(act.id_prod_prov) FROM producto_proveedor act
+                        WHERE act.id_producto = n.id_producto AND act.estado = 'Activo')
+                   ) AS id_prod_prov
+            FROM niveles n
+            WHERE {} AND n.stock_maximo IS NOT NULL AND (? IS NULL OR n.id_almacen = ?)
+        )
+        SELECT s.id_producto, s.codigo_interno, s.descripcion, s.id_almacen, s.id_prod_prov,
+               pp.id_proveedor, pv.nombre AS proveedor, s.stock_base, s.en_transito, s.stock_maximo,
+               s.stock_maximo - s.stock_base - s.en_transito AS cantidad_sugerida
+        FROM sugerencias s
+        LEFT JOIN producto_proveedor pp ON pp.id_prod_prov = s.id_prod_prov
+        LEFT JOIN proveedor pv ON pv.id_proveedor = pp.id_proveedor
+        WHERE s.stock_maximo - s.stock_base - s.en_transito > 0
+        ORDER BY s.id_producto ASC, s.id_almacen ASC",
+        NIVELES, BAJO
+    );
+    sqlx::query_as::<_, SugerenciaReorden>(&sql)
+        .bind(almacen_id)
+        .bind(almacen_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
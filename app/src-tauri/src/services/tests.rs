@@ -1,3 +1,19 @@
+/// Catálogo mínimo para registrar movimientos: producto, proveedor,
+/// producto_proveedor y presentación con id 1, y los almacenes 1 y 2.
+#[cfg(test)]
+async fn seed_catalogo(pool: &sqlx::SqlitePool) {
+    for sql in [
+        "INSERT INTO producto (codigo_interno, descripcion) VALUES ('P-001', 'Producto Test')",
+        "INSERT INTO proveedor (ruc_ci, nombre) VALUES ('0012505900001A', 'Proveedor Test')",
+        "INSERT INTO producto_proveedor (id_producto, id_proveedor, codigo_proveedor) VALUES (1, 1, 'PROV-001')",
+        "INSERT INTO presentacion (id_producto, unidad, cantidad) VALUES (1, 'Unidad', 1)",
+        "INSERT INTO almacen (nombre) VALUES ('Almacén 1')",
+        "INSERT INTO almacen (nombre) VALUES ('Almacén 2')",
+    ] {
+        sqlx::query(sql).execute(pool).await.unwrap();
+    }
+}
+
 #[cfg(test)]
 mod almacen_service_tests {
     use crate::models::almacen::{CreateAlmacen, UpdateAlmacen};
@@ -5,22 +21,7 @@ mod almacen_service_tests {
     use sqlx::SqlitePool;
 
     async fn setup_test_db() -> SqlitePool {
-        let pool = SqlitePool::connect(":memory:").await.unwrap();
-        
-        // Crear tabla almacen
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS almacen (
-                id_almacen INTEGER PRIMARY KEY AUTOINCREMENT,
-                nombre TEXT NOT NULL,
-                ubicacion TEXT,
-                responsable TEXT
-            )"
-        )
-        .execute(&pool)
-        .await
-        .unwrap();
-
-        pool
+        crate::db::test_pool().await
     }
 
     #[tokio::test]
@@ -57,7 +58,7 @@ mod almacen_service_tests {
         almacen_service::create(&pool, data1).await.unwrap();
         almacen_service::create(&pool, data2).await.unwrap();
 
-        let result = almacen_service::get_all(&pool).await;
+        let result = almacen_service::get_all(&pool, false).await;
         assert!(result.is_ok());
         
         let almacenes = result.unwrap();
@@ -77,7 +78,7 @@ mod almacen_service_tests {
         };
 
         let id = almacen_service::create(&pool, data).await.unwrap();
-        let result = almacen_service::get_by_id(&pool, id as i32).await;
+        let result = almacen_service::get_by_id(&pool, id as i32, false).await;
         
         assert!(result.is_ok());
         let almacen = result.unwrap();
@@ -93,7 +94,7 @@ mod almacen_service_tests {
     async fn test_get_almacen_by_id_not_found() {
         let pool = setup_test_db().await;
         
-        let result = almacen_service::get_by_id(&pool, 999).await;
+        let result = almacen_service::get_by_id(&pool, 999, false).await;
         assert!(result.is_ok());
         assert!(result.unwrap().is_none());
     }
@@ -122,7 +123,7 @@ mod almacen_service_tests {
         assert_eq!(result.unwrap(), 1);
 
         // Verificar actualización
-        let almacen = almacen_service::get_by_id(&pool, id).await.unwrap().unwrap();
+        let almacen = almacen_service::get_by_id(&pool, id, false).await.unwrap().unwrap();
         assert_eq!(almacen.nombre, "Almacén Actualizado");
         assert_eq!(almacen.ubicacion, Some("Nueva Ubicación".to_string()));
         assert_eq!(almacen.responsable, Some("Responsable Original".to_string()));
@@ -148,7 +149,7 @@ mod almacen_service_tests {
         
         almacen_service::update(&pool, id, update_data).await.unwrap();
 
-        let almacen = almacen_service::get_by_id(&pool, id).await.unwrap().unwrap();
+        let almacen = almacen_service::get_by_id(&pool, id, false).await.unwrap().unwrap();
         assert_eq!(almacen.nombre, "Nuevo Nombre");
         assert_eq!(almacen.ubicacion, Some("Ubicación Test".to_string()));
         assert_eq!(almacen.responsable, Some("Manager Test".to_string()));
@@ -166,7 +167,7 @@ mod almacen_service_tests {
         let id = almacen_service::create(&pool, data).await.unwrap() as i32;
 
         // Verificar que existe
-        let almacen = almacen_service::get_by_id(&pool, id).await.unwrap();
+        let almacen = almacen_service::get_by_id(&pool, id, false).await.unwrap();
         assert!(almacen.is_some());
 
         // Eliminar
@@ -175,7 +176,7 @@ mod almacen_service_tests {
         assert_eq!(result.unwrap(), 1);
 
         // Verificar que ya no existe
-        let almacen = almacen_service::get_by_id(&pool, id).await.unwrap();
+        let almacen = almacen_service::get_by_id(&pool, id, false).await.unwrap();
         assert!(almacen.is_none());
     }
 
@@ -189,6 +190,103 @@ mod almacen_service_tests {
     }
 }
 
+#[cfg(test)]
+mod proveedor_service_tests {
+    use crate::error::AppError;
+    use crate::identificacion;
+    use crate::models::proveedor::{CreateProveedor, UpdateProveedor};
+    use crate::services::proveedor_service;
+
+    fn proveedor(ruc_ci: &str, pais: Option<&str>) -> CreateProveedor {
+        CreateProveedor {
+            ruc_ci: ruc_ci.to_string(),
+            nombre: "Proveedor Test".to_string(),
+            contacto: None,
+            telefono: None,
+            email: None,
+            estado: None,
+            pais: pais.map(str::to_string),
+            dias_credito: None,
+        }
+    }
+
+    #[test]
+    fn test_validadores_por_pais() {
+        assert_eq!(identificacion::validar("NI", "001-250590-0001r"), Ok("0012505900001R".to_string()));
+        assert!(identificacion::validar("NI", "001-250590-0001A").is_err());
+        assert!(identificacion::validar("NI", "J0310000000001").is_ok());
+        assert!(identificacion::validar("GT", "576937-K").is_ok());
+        assert!(identificacion::validar("GT", "576937-5").is_err());
+        assert!(identificacion::validar("SV", "00016297-5").is_ok());
+        assert!(identificacion::validar("SV", "00016297-4").is_err());
+        assert!(identificacion::validar("CR", "3-101-123456").is_ok());
+        assert!(identificacion::validar("HN", "0801-1990-12345").is_ok());
+        // Sin validador registrado solo se normaliza
+        assert_eq!(identificacion::validar("MX", "abc 123"), Ok("ABC123".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_create_guarda_identificador_normalizado() {
+        let pool = crate::db::test_pool().await;
+
+        let id = proveedor_service::create(&pool, proveedor("001-250590-0001R", None)).await.unwrap();
+
+        let guardado = proveedor_service::get_by_id(&pool, id as i32, false).await.unwrap().unwrap();
+        assert_eq!(guardado.ruc_ci, "0012505900001R");
+        assert_eq!(guardado.pais, "NI");
+    }
+
+    #[tokio::test]
+    async fn test_create_rechaza_invalido_y_duplicado() {
+        let pool = crate::db::test_pool().await;
+
+        let error = proveedor_service::create(&pool, proveedor("0012505900001A", None)).await.unwrap_err();
+        assert_eq!(error.field(), Some("ruc_ci"));
+        assert_eq!(error.code(), "VALIDATION");
+
+        // Registro anterior guardado con guiones
+        sqlx::query("INSERT INTO proveedor (ruc_ci, nombre) VALUES ('001-250590-0001R', 'Proveedor Antiguo')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let error = proveedor_service::create(&pool, proveedor("0012505900001R", Some("NI"))).await.unwrap_err();
+        assert!(matches!(error, AppError::Duplicate { ref field, .. } if field == "ruc_ci"));
+    }
+
+    #[tokio::test]
+    async fn test_update_revalida_al_cambiar_pais() {
+        let pool = crate::db::test_pool().await;
+        let id = proveedor_service::create(&pool, proveedor("576937-K", Some("GT"))).await.unwrap() as i32;
+
+        let data = UpdateProveedor {
+            ruc_ci: None,
+            nombre: None,
+            contacto: None,
+            telefono: None,
+            email: None,
+            estado: None,
+            pais: Some("SV".to_string()),
+            dias_credito: None,
+        };
+        let error = proveedor_service::update(&pool, id, data).await.unwrap_err();
+        assert_eq!(error.field(), Some("ruc_ci"));
+
+        let data = UpdateProveedor {
+            ruc_ci: Some("00016297-5".to_string()),
+            nombre: None,
+            contacto: None,
+            telefono: None,
+            email: None,
+            estado: None,
+            pais: Some("SV".to_string()),
+            dias_credito: None,
+        };
+        assert_eq!(proveedor_service::update(&pool, id, data).await.unwrap(), 1);
+        let guardado = proveedor_service::get_by_id(&pool, id, false).await.unwrap().unwrap();
+        assert_eq!(guardado.ruc_ci, "000162975");
+    }
+}
+
 #[cfg(test)]
 mod factura_service_tests {
     use crate::models::factura::{FacturaInput, FacturaUpdate};
@@ -196,44 +294,14 @@ mod factura_service_tests {
     use sqlx::SqlitePool;
 
     async fn setup_test_db() -> SqlitePool {
-        let pool = SqlitePool::connect(":memory:").await.unwrap();
-        
-        // Crear tabla proveedor (necesaria para foreign key)
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS proveedor (
-                id_proveedor INTEGER PRIMARY KEY AUTOINCREMENT,
-                nombre TEXT NOT NULL,
-                contacto TEXT,
-                telefono TEXT,
-                email TEXT
-            )"
-        )
-        .execute(&pool)
-        .await
-        .unwrap();
+        let pool = crate::db::test_pool().await;
 
         // Insertar proveedor de prueba
-        sqlx::query("INSERT INTO proveedor (nombre) VALUES ('Proveedor Test')")
+        sqlx::query("INSERT INTO proveedor (ruc_ci, nombre) VALUES ('0012505900001A', 'Proveedor Test')")
             .execute(&pool)
             .await
             .unwrap();
 
-        // Crear tabla factura
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS factura (
-                id_factura INTEGER PRIMARY KEY AUTOINCREMENT,
-                numero TEXT NOT NULL,
-                fecha TEXT NOT NULL,
-                id_proveedor INTEGER NOT NULL,
-                total REAL,
-                estado TEXT,
-                FOREIGN KEY (id_proveedor) REFERENCES proveedor(id_proveedor)
-            )"
-        )
-        .execute(&pool)
-        .await
-        .unwrap();
-
         pool
     }
 
@@ -246,7 +314,9 @@ mod factura_service_tests {
             fecha: "2025-01-15".to_string(),
             id_proveedor: 1,
             total: Some(1500.50),
-            estado: Some("Pendiente".to_string()),
+            estado: Some("Activa".to_string()),
+            descuento: None,
+            porcentaje_impuesto: None,
         };
 
         let result = factura_service::create_factura(&pool, data).await;
@@ -264,6 +334,8 @@ mod factura_service_tests {
             id_proveedor: 1,
             total: None,
             estado: None,
+            descuento: None,
+            porcentaje_impuesto: None,
         };
 
         let result = factura_service::create_factura(&pool, data).await;
@@ -280,7 +352,9 @@ mod factura_service_tests {
             fecha: "2025-01-17".to_string(),
             id_proveedor: 1,
             total: Some(1000.0),
-            estado: Some("Pendiente".to_string()),
+            estado: Some("Activa".to_string()),
+            descuento: None,
+            porcentaje_impuesto: None,
         };
         let id = factura_service::create_factura(&pool, data).await.unwrap() as i32;
 
@@ -290,7 +364,9 @@ mod factura_service_tests {
             fecha: None,
             id_proveedor: None,
             total: Some(1200.0),
-            estado: Some("Pagada".to_string()),
+            estado: None,
+            descuento: None,
+            porcentaje_impuesto: None,
         };
         
         let result = factura_service::update_factura(&pool, id, update_data).await;
@@ -309,7 +385,7 @@ mod factura_service_tests {
         assert_eq!(factura.numero, "F-003-MOD");
         assert_eq!(factura.fecha, "2025-01-17");
         assert_eq!(factura.total, Some(1200.0));
-        assert_eq!(factura.estado, Some("Pagada".to_string()));
+        assert_eq!(factura.estado, Some("Activa".to_string()));
     }
 
     #[tokio::test]
@@ -321,13 +397,15 @@ mod factura_service_tests {
             fecha: "2025-01-18".to_string(),
             id_proveedor: 1,
             total: Some(500.0),
-            estado: Some("Pendiente".to_string()),
+            estado: Some("Activa".to_string()),
+            descuento: None,
+            porcentaje_impuesto: None,
         };
         let id = factura_service::create_factura(&pool, data).await.unwrap() as i32;
 
-        // Actualizar solo el estado
+        // Actualizar solo el número
         let update_data = FacturaUpdate {
-            estado: Some("Pagada".to_string()),
+            numero: Some("F-004-MOD".to_string()),
             ..Default::default()
         };
         
@@ -341,9 +419,9 @@ mod factura_service_tests {
         .await
         .unwrap();
 
-        assert_eq!(factura.numero, "F-004");
+        assert_eq!(factura.numero, "F-004-MOD");
         assert_eq!(factura.total, Some(500.0));
-        assert_eq!(factura.estado, Some("Pagada".to_string()));
+        assert_eq!(factura.estado, Some("Activa".to_string()));
     }
 
     #[tokio::test]
@@ -360,3 +438,2740 @@ mod factura_service_tests {
         assert_eq!(result.unwrap(), 0);
     }
 }
+
+#[cfg(test)]
+mod posting_service_tests {
+    use crate::models::configuracion::PoliticaStock;
+    use crate::models::movimiento::{CreateMovimiento, UpdateMovimiento};
+    use crate::error::AppError;
+    use crate::services::{configuracion_service, posting_service, stock_almacen_service};
+    use sqlx::SqlitePool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        pool
+    }
+
+    fn movimiento(tipo: &str, cantidad: f64) -> CreateMovimiento {
+        CreateMovimiento {
+            fecha: "2025-01-15".to_string(),
+            tipo: tipo.to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad,
+            id_almacen: 1,
+            ..Default::default()
+        }
+    }
+
+    async fn stock(pool: &SqlitePool) -> Option<f64> {
+        stock_almacen_service::get_by_ubicacion(pool, 1, 1, 1)
+            .await
+            .unwrap()
+            .map(|s| s.stock_actual)
+    }
+
+    #[tokio::test]
+    async fn test_update_no_asigna_subtipos_de_documento() {
+        let pool = setup_test_db().await;
+        posting_service::post(&pool, movimiento("entrada", 10.0)).await.unwrap();
+
+        let data = UpdateMovimiento {
+            subtipo: Some("devolucion".to_string()),
+            ..Default::default()
+        };
+        let error = posting_service::update(&pool, 1, data).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+
+        // Sigue siendo un movimiento común que se puede eliminar
+        assert_eq!(posting_service::delete(&pool, 1).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_entrada_crea_stock() {
+        let pool = setup_test_db().await;
+
+        let registrado = posting_service::post(&pool, movimiento("entrada", 50.0)).await.unwrap();
+        assert_eq!(registrado.id_movimiento, 1);
+        assert_eq!(stock(&pool).await, Some(50.0));
+    }
+
+    #[tokio::test]
+    async fn test_salida_y_ajuste_actualizan_stock() {
+        let pool = setup_test_db().await;
+
+        posting_service::post(&pool, movimiento("entrada", 50.0)).await.unwrap();
+        posting_service::post(&pool, movimiento("Salida", 20.0)).await.unwrap();
+
+        let mut faltante = movimiento("ajuste", 5.0);
+        faltante.subtipo = Some("faltante".to_string());
+        posting_service::post(&pool, faltante).await.unwrap();
+        assert_eq!(stock(&pool).await, Some(25.0));
+
+        // Solo debe existir una fila de stock para la ubicación
+        let filas: i32 = sqlx::query_scalar("SELECT COUNT(*) FROM stock_almacen")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(filas, 1);
+    }
+
+    #[tokio::test]
+    async fn test_tipo_invalido_no_registra_nada() {
+        let pool = setup_test_db().await;
+
+        let result = posting_service::post(&pool, movimiento("traslado", 10.0)).await;
+        assert!(result.is_err());
+
+        let movimientos: i32 = sqlx::query_scalar("SELECT COUNT(*) FROM movimiento")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(movimientos, 0);
+        assert_eq!(stock(&pool).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_update_y_delete_revierten_stock() {
+        let pool = setup_test_db().await;
+
+        let id = posting_service::post(&pool, movimiento("entrada", 50.0)).await.unwrap().id_movimiento as i32;
+
+        let update_data = UpdateMovimiento {
+            cantidad: Some(30.0),
+            ..Default::default()
+        };
+        assert_eq!(posting_service::update(&pool, id, update_data).await.unwrap(), 1);
+        assert_eq!(stock(&pool).await, Some(30.0));
+
+        assert_eq!(posting_service::delete(&pool, id).await.unwrap(), 1);
+        assert_eq!(stock(&pool).await, Some(0.0));
+    }
+
+    #[tokio::test]
+    async fn test_salida_sin_stock_se_rechaza_por_defecto() {
+        let pool = setup_test_db().await;
+
+        posting_service::post(&pool, movimiento("entrada", 5.0)).await.unwrap();
+        let result = posting_service::post(&pool, movimiento("salida", 8.0)).await;
+
+        match result {
+            Err(AppError::InsufficientStock(detalle)) => {
+                assert_eq!(detalle.disponible, 5.0);
+                assert_eq!(detalle.solicitado, 8.0);
+                assert_eq!(detalle.faltante, 3.0);
+            }
+            other => panic!("se esperaba StockInsuficiente, se obtuvo {:?}", other),
+        }
+        assert_eq!(stock(&pool).await, Some(5.0));
+    }
+
+    #[tokio::test]
+    async fn test_politica_advertir_y_permitir() {
+        let pool = setup_test_db().await;
+
+        configuracion_service::set_politica_stock(&pool, None, Some(PoliticaStock::Advertir))
+            .await
+            .unwrap();
+        let registrado = posting_service::post(&pool, movimiento("salida", 4.0)).await.unwrap();
+        assert_eq!(registrado.advertencia.map(|a| a.faltante), Some(4.0));
+        assert_eq!(stock(&pool).await, Some(-4.0));
+
+        // La política del almacén tiene prioridad sobre la global
+        configuracion_service::set_politica_stock(&pool, Some(1), Some(PoliticaStock::Permitir))
+            .await
+            .unwrap();
+        let registrado = posting_service::post(&pool, movimiento("salida", 1.0)).await.unwrap();
+        assert!(registrado.advertencia.is_none());
+        assert_eq!(stock(&pool).await, Some(-5.0));
+
+        let politica = configuracion_service::get_politica_stock(&pool, Some(2)).await.unwrap();
+        assert_eq!(politica, PoliticaStock::Advertir);
+    }
+
+    #[tokio::test]
+    async fn test_configuracion_una_fila_por_clave_y_almacen() {
+        let pool = setup_test_db().await;
+
+        for politica in [PoliticaStock::Advertir, PoliticaStock::Permitir] {
+            configuracion_service::set_politica_stock(&pool, None, Some(politica)).await.unwrap();
+            configuracion_service::set_politica_stock(&pool, Some(1), Some(politica)).await.unwrap();
+        }
+        let filas: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM configuracion")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(filas, 2);
+        assert_eq!(
+            configuracion_service::get_politica_stock(&pool, None).await.unwrap(),
+            PoliticaStock::Permitir
+        );
+
+        // La global tampoco admite una segunda fila
+        let repetida = sqlx::query(
+            "INSERT INTO configuracion (clave, id_almacen, valor) VALUES ('politica_stock_negativo', NULL, 'rechazar')"
+        )
+        .execute(&pool)
+        .await;
+        assert!(repetida.is_err());
+    }
+}
+
+#[cfg(test)]
+mod app_error_tests {
+    use crate::error::AppError;
+    use crate::models::producto::CreateProducto;
+    use crate::models::producto_proveedor::CreateProductoProveedor;
+    use crate::services::{producto_proveedor_service, producto_service};
+
+    fn producto(codigo: &str) -> CreateProducto {
+        CreateProducto {
+            codigo_interno: codigo.to_string(),
+            descripcion: "Producto Test".to_string(),
+            categoria: None,
+            subcategoria: None,
+            estado: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_codigo_duplicado_indica_campo() {
+        let pool = crate::db::test_pool().await;
+
+        producto_service::create(&pool, producto("P-001")).await.unwrap();
+        let error = producto_service::create(&pool, producto("P-001")).await.unwrap_err();
+
+        match &error {
+            AppError::Duplicate { tabla, field } => {
+                assert_eq!(tabla, "producto");
+                assert_eq!(field, "codigo_interno");
+            }
+            other => panic!("se esperaba Duplicate, se obtuvo {:?}", other),
+        }
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "DUPLICATE");
+        assert_eq!(json["field"], "codigo_interno");
+        assert_eq!(json["details"]["tabla"], "producto");
+        assert!(!json["message"].as_str().unwrap().contains("UNIQUE"));
+    }
+
+    #[tokio::test]
+    async fn test_referencia_inexistente() {
+        let pool = crate::db::test_pool().await;
+
+        let data = CreateProductoProveedor {
+            id_producto: 99,
+            id_proveedor: 99,
+            codigo_proveedor: "PROV-099".to_string(),
+            estado: None,
+        };
+        let error = producto_proveedor_service::create(&pool, data).await.unwrap_err();
+
+        assert_eq!(error.code(), "FOREIGN_KEY_VIOLATION");
+    }
+
+    #[tokio::test]
+    async fn test_stock_insuficiente_se_serializa_con_detalle() {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+
+        let data = crate::models::movimiento::CreateMovimiento {
+            fecha: "2025-01-15".to_string(),
+            tipo: "salida".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad: 3.0,
+            id_almacen: 1,
+            ..Default::default()
+        };
+        let error = crate::services::posting_service::post(&pool, data).await.unwrap_err();
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "INSUFFICIENT_STOCK");
+        assert_eq!(json["field"], serde_json::Value::Null);
+        assert_eq!(json["details"]["faltante"], 3.0);
+    }
+}
+
+#[cfg(test)]
+mod eliminacion_tests {
+    use crate::error::{AppError, Dependiente};
+    use crate::models::movimiento::CreateMovimiento;
+    use crate::services::{posting_service, presentacion_service, producto_service, proveedor_service};
+
+    fn dependientes(error: AppError) -> Vec<(String, i64)> {
+        match error {
+            AppError::ForeignKeyViolation { dependientes, .. } => dependientes
+                .into_iter()
+                .map(|Dependiente { tabla, cantidad }| (tabla, cantidad))
+                .collect(),
+            other => panic!("se esperaba ForeignKeyViolation, se obtuvo {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_claves_foraneas_activas() {
+        let pool = crate::db::test_pool().await;
+
+        let activas: i32 = sqlx::query_scalar("PRAGMA foreign_keys")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(activas, 1);
+    }
+
+    fn entrada() -> CreateMovimiento {
+        CreateMovimiento {
+            fecha: "2025-01-15".to_string(),
+            tipo: "entrada".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad: 10.0,
+            id_almacen: 1,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_purga_producto_con_movimientos_se_restringe() {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        posting_service::post(&pool, entrada()).await.unwrap();
+
+        assert_eq!(producto_service::delete(&pool, 1).await.unwrap(), 1);
+        let error = producto_service::purge(&pool, 1).await.unwrap_err();
+        assert_eq!(dependientes(error), vec![("movimiento".to_string(), 1)]);
+        assert!(producto_service::get_by_id(&pool, 1, true).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_producto_sin_movimientos_elimina_presentaciones() {
+        let pool = crate::db::test_pool().await;
+        sqlx::query("INSERT INTO producto (codigo_interno, descripcion) VALUES ('P-002', 'Sin uso')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO presentacion (id_producto, unidad, cantidad) VALUES (1, 'Caja', 12)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        producto_service::delete(&pool, 1).await.unwrap();
+        assert_eq!(producto_service::purge(&pool, 1).await.unwrap(), 1);
+        assert!(presentacion_service::get_all(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_presentacion_con_movimientos_lista_dependientes() {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+
+        posting_service::post(&pool, entrada()).await.unwrap();
+
+        let error = presentacion_service::delete(&pool, 1).await.unwrap_err();
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "FOREIGN_KEY_VIOLATION");
+        assert_eq!(
+            dependientes(error),
+            vec![
+                ("movimiento".to_string(), 1),
+                ("lote".to_string(), 1),
+                ("stock_almacen".to_string(), 1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_proveedor_con_facturas_se_restringe() {
+        let pool = crate::db::test_pool().await;
+        sqlx::query("INSERT INTO proveedor (ruc_ci, nombre) VALUES ('0012505900001R', 'Proveedor Test')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO factura (numero, fecha, id_proveedor) VALUES ('F-001', '2025-01-15', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        proveedor_service::delete(&pool, 1).await.unwrap();
+        let error = proveedor_service::purge(&pool, 1).await.unwrap_err();
+        assert_eq!(dependientes(error), vec![("factura".to_string(), 1)]);
+    }
+}
+
+#[cfg(test)]
+mod archivado_tests {
+    use crate::error::AppError;
+    use crate::services::{almacen_service, producto_service, proveedor_service, sesion_service};
+
+    #[tokio::test]
+    async fn test_archivar_oculta_y_registra_usuario() {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        sesion_service::set_usuario(&pool, Some("mlopez")).await.unwrap();
+
+        assert_eq!(proveedor_service::delete(&pool, 1).await.unwrap(), 1);
+        // Archivar dos veces no tiene efecto
+        assert_eq!(proveedor_service::delete(&pool, 1).await.unwrap(), 0);
+
+        assert!(proveedor_service::get_all(&pool, false).await.unwrap().is_empty());
+        assert!(proveedor_service::get_by_id(&pool, 1, false).await.unwrap().is_none());
+
+        let archivado = proveedor_service::get_by_id(&pool, 1, true).await.unwrap().unwrap();
+        assert!(archivado.eliminado_en.is_some());
+        assert_eq!(archivado.eliminado_por, Some("mlopez".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_restaurar() {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+
+        almacen_service::delete(&pool, 2).await.unwrap();
+        assert_eq!(almacen_service::get_all(&pool, false).await.unwrap().len(), 1);
+        assert_eq!(almacen_service::get_all(&pool, true).await.unwrap().len(), 2);
+
+        assert_eq!(almacen_service::restore(&pool, 2).await.unwrap(), 1);
+        let almacen = almacen_service::get_by_id(&pool, 2, false).await.unwrap().unwrap();
+        assert_eq!(almacen.eliminado_en, None);
+        assert_eq!(almacen.eliminado_por, None);
+    }
+
+    #[tokio::test]
+    async fn test_purga_requiere_archivado() {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+
+        let error = producto_service::purge(&pool, 1).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+        assert_eq!(producto_service::purge(&pool, 999).await.unwrap(), 0);
+
+        // Sin movimientos, la purga arrastra sus relaciones con proveedores
+        producto_service::delete(&pool, 1).await.unwrap();
+        assert_eq!(producto_service::purge(&pool, 1).await.unwrap(), 1);
+        let relaciones: i32 = sqlx::query_scalar("SELECT COUNT(*) FROM producto_proveedor")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(relaciones, 0);
+    }
+}
+
+#[cfg(test)]
+mod lote_service_tests {
+    use crate::models::movimiento::CreateMovimiento;
+    use crate::services::{lote_service, movimiento_service, posting_service};
+    use sqlx::SqlitePool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        pool
+    }
+
+    fn entrada(fecha: &str, cantidad: f64, precio: f64) -> CreateMovimiento {
+        CreateMovimiento {
+            fecha: fecha.to_string(),
+            tipo: "entrada".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad,
+            precio_unit: Some(precio),
+            lote: Some(format!("L-{}", fecha)),
+            id_almacen: 1,
+            ..Default::default()
+        }
+    }
+
+    fn salida(cantidad: f64) -> CreateMovimiento {
+        CreateMovimiento {
+            fecha: "2025-03-01".to_string(),
+            tipo: "salida".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad,
+            id_almacen: 1,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entrada_abre_lote() {
+        let pool = setup_test_db().await;
+
+        posting_service::post(&pool, entrada("2025-01-10", 10.0, 5.0)).await.unwrap();
+
+        let lotes = lote_service::get_all(&pool).await.unwrap();
+        assert_eq!(lotes.len(), 1);
+        assert_eq!(lotes[0].numero_lote, Some("L-2025-01-10".to_string()));
+        assert_eq!(lotes[0].cantidad_inicial, 10.0);
+        assert_eq!(lotes[0].cantidad_actual, 10.0);
+        assert_eq!(lotes[0].costo_unitario, 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_salida_consume_lotes_mas_antiguos() {
+        let pool = setup_test_db().await;
+
+        // Se registra primero el lote más nuevo para comprobar el orden por fecha
+        posting_service::post(&pool, entrada("2025-02-10", 10.0, 7.0)).await.unwrap();
+        posting_service::post(&pool, entrada("2025-01-10", 10.0, 5.0)).await.unwrap();
+
+        let id = posting_service::post(&pool, salida(15.0)).await.unwrap().id_movimiento as i32;
+
+        // 10 a 5.0 del lote de enero + 5 a 7.0 del lote de febrero
+        let movimiento = movimiento_service::get_by_id(&pool, id).await.unwrap().unwrap();
+        assert_eq!(movimiento.monto_total, Some(85.0));
+
+        let consumos = lote_service::get_consumos_by_movimiento(&pool, id).await.unwrap();
+        assert_eq!(consumos.len(), 2);
+        assert_eq!(consumos[0].id_lote, 2);
+        assert_eq!(consumos[0].cantidad, 10.0);
+        assert_eq!(consumos[1].id_lote, 1);
+        assert_eq!(consumos[1].cantidad, 5.0);
+
+        let disponibles = lote_service::get_disponibles(&pool, 1, 1, 1).await.unwrap();
+        assert_eq!(disponibles.len(), 1);
+        assert_eq!(disponibles[0].cantidad_actual, 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_eliminar_salida_restaura_lotes() {
+        let pool = setup_test_db().await;
+
+        posting_service::post(&pool, entrada("2025-01-10", 10.0, 5.0)).await.unwrap();
+        let id = posting_service::post(&pool, salida(4.0)).await.unwrap().id_movimiento as i32;
+
+        posting_service::delete(&pool, id).await.unwrap();
+
+        let lote = lote_service::get_by_id(&pool, 1).await.unwrap().unwrap();
+        assert_eq!(lote.cantidad_actual, 10.0);
+        assert!(lote_service::get_consumos_by_movimiento(&pool, id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_no_elimina_entrada_con_lote_consumido() {
+        let pool = setup_test_db().await;
+
+        let id = posting_service::post(&pool, entrada("2025-01-10", 10.0, 5.0)).await.unwrap().id_movimiento as i32;
+        posting_service::post(&pool, salida(4.0)).await.unwrap();
+
+        let result = posting_service::delete(&pool, id).await;
+        assert!(result.is_err());
+        assert!(movimiento_service::get_by_id(&pool, id).await.unwrap().is_some());
+    }
+}
+
+#[cfg(test)]
+mod migraciones_tests {
+    use crate::db::{self, DbError};
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+    use std::path::{Path, PathBuf};
+
+    async fn base_en_archivo(nombre: &str) -> (SqlitePool, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("amphora-{}-{}", nombre, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let ruta = dir.join("inventory.db");
+
+        let options = SqliteConnectOptions::new().filename(&ruta).create_if_missing(true);
+        (SqlitePool::connect_with(options).await.unwrap(), ruta)
+    }
+
+    fn respaldos(ruta: &Path) -> usize {
+        std::fs::read_dir(ruta.parent().unwrap())
+            .unwrap()
+            .filter(|e| e.as_ref().unwrap().file_name().to_string_lossy().ends_with(".bak"))
+            .count()
+    }
+
+    #[tokio::test]
+    async fn test_base_nueva_no_genera_respaldo() {
+        let (pool, ruta) = base_en_archivo("nueva").await;
+
+        db::migrar(&pool, Some(&ruta)).await.unwrap();
+
+        assert_eq!(respaldos(&ruta), 0);
+        let versiones: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(versiones as usize, db::MIGRATOR.iter().count());
+
+        pool.close().await;
+        std::fs::remove_dir_all(ruta.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_base_anterior_se_respalda_y_actualiza() {
+        let (pool, ruta) = base_en_archivo("anterior").await;
+
+        // Base creada por la versión sin migraciones versionadas
+        sqlx::raw_sql(include_str!("../../migrations/0001_esquema_inicial.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO producto (codigo_interno, descripcion) VALUES ('P-001', 'Existente')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        db::migrar(&pool, Some(&ruta)).await.unwrap();
+
+        assert_eq!(respaldos(&ruta), 1);
+        let productos: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM producto")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(productos, 1);
+        sqlx::query("SELECT id_almacen FROM movimiento").fetch_all(&pool).await.unwrap();
+
+        pool.close().await;
+        std::fs::remove_dir_all(ruta.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rechaza_base_de_version_posterior() {
+        let pool = db::test_pool().await;
+
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+             VALUES (9999, 'futura', 1, x'00', 0)"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let result = db::migrar(&pool, None).await;
+        assert!(matches!(result, Err(DbError::VersionPosterior { version_bd: 9999, .. })));
+    }
+}
+
+#[cfg(test)]
+mod auditoria_tests {
+    use crate::error::AppError;
+    use crate::models::auditoria::FiltroAuditoria;
+    use crate::models::movimiento::CreateMovimiento;
+    use crate::models::producto::UpdateProducto;
+    use crate::services::{auditoria_service, posting_service, producto_service, sesion_service};
+    use crate::validation::Validate;
+
+    #[tokio::test]
+    async fn test_registra_antes_despues_y_usuario() {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        sesion_service::set_usuario(&pool, Some("mlopez")).await.unwrap();
+
+        let data = UpdateProducto {
+            codigo_interno: None,
+            descripcion: Some("Producto Renombrado".to_string()),
+            categoria: None,
+            subcategoria: None,
+            estado: None,
+        };
+        producto_service::update(&pool, 1, data).await.unwrap();
+        producto_service::delete(&pool, 1).await.unwrap();
+
+        let historial = auditoria_service::get_by_registro(&pool, "producto", 1).await.unwrap();
+        let operaciones: Vec<&str> = historial.iter().map(|a| a.operacion.as_str()).collect();
+        assert_eq!(operaciones, vec!["UPDATE", "UPDATE", "INSERT"]);
+
+        let cambio = &historial[1];
+        assert_eq!(cambio.usuario, Some("mlopez".to_string()));
+        let antes: serde_json::Value = serde_json::from_str(cambio.antes.as_ref().unwrap()).unwrap();
+        let despues: serde_json::Value = serde_json::from_str(cambio.despues.as_ref().unwrap()).unwrap();
+        assert_eq!(antes["descripcion"], "Producto Test");
+        assert_eq!(despues["descripcion"], "Producto Renombrado");
+
+        // El alta se hizo antes de iniciar sesión
+        assert_eq!(historial[2].usuario, None);
+        assert_eq!(historial[2].antes, None);
+    }
+
+    #[tokio::test]
+    async fn test_cambio_revertido_no_deja_registro() {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+
+        let salida = CreateMovimiento {
+            fecha: "2025-01-15".to_string(),
+            tipo: "salida".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad: 5.0,
+            id_almacen: 1,
+            ..Default::default()
+        };
+        assert!(posting_service::post(&pool, salida).await.is_err());
+
+        let filtro = FiltroAuditoria {
+            entidad: Some("movimiento".to_string()),
+            ..Default::default()
+        };
+        assert!(auditoria_service::buscar(&pool, &filtro).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_filtro_por_fecha() {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+
+        let hoy: String = sqlx::query_scalar("SELECT date('now')").fetch_one(&pool).await.unwrap();
+        let filtro = FiltroAuditoria {
+            entidad: Some("almacen".to_string()),
+            desde: Some(hoy.clone()),
+            hasta: Some(hoy),
+            ..Default::default()
+        };
+        assert_eq!(auditoria_service::buscar(&pool, &filtro).await.unwrap().len(), 2);
+
+        let filtro = FiltroAuditoria {
+            desde: Some("2000-01-01".to_string()),
+            hasta: Some("2000-01-31".to_string()),
+            ..Default::default()
+        };
+        assert!(auditoria_service::buscar(&pool, &filtro).await.unwrap().is_empty());
+
+        let invertido = FiltroAuditoria {
+            desde: Some("2025-02-01".to_string()),
+            hasta: Some("2025-01-01".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(invertido.validate(), Err(AppError::Validation(_))));
+    }
+}
+
+#[cfg(test)]
+mod transferencia_tests {
+    use crate::error::AppError;
+    use crate::models::movimiento::CreateMovimiento;
+    use crate::models::transferencia::{CreateTransferencia, LineaTransferencia};
+    use crate::services::{lote_service, posting_service, stock_almacen_service, transferencia_service};
+    use sqlx::SqlitePool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+
+        for (fecha, cantidad, precio) in [("2025-01-10", 10.0, 5.0), ("2025-02-10", 10.0, 7.0)] {
+            let entrada = CreateMovimiento {
+                fecha: fecha.to_string(),
+                tipo: "entrada".to_string(),
+                id_prod_prov: 1,
+                id_presentacion: 1,
+                cantidad,
+                precio_unit: Some(precio),
+                lote: Some(format!("L-{}", fecha)),
+                fecha_venc: Some("2026-01-01".to_string()),
+                id_almacen: 1,
+                ..Default::default()
+            };
+            posting_service::post(&pool, entrada).await.unwrap();
+        }
+        pool
+    }
+
+    fn transferencia(cantidad: f64) -> CreateTransferencia {
+        CreateTransferencia {
+            fecha: "2025-03-01".to_string(),
+            id_almacen_origen: 1,
+            id_almacen_destino: 2,
+            obs: None,
+            lineas: vec![LineaTransferencia {
+                id_prod_prov: 1,
+                id_presentacion: 1,
+                cantidad,
+            }],
+        }
+    }
+
+    async fn stock(pool: &SqlitePool, almacen_id: i32) -> f64 {
+        stock_almacen_service::get_by_ubicacion(pool, 1, 1, almacen_id)
+            .await
+            .unwrap()
+            .map_or(0.0, |s| s.stock_actual)
+    }
+
+    #[tokio::test]
+    async fn test_transfiere_stock_y_lotes_con_costo_original() {
+        let pool = setup_test_db().await;
+
+        let registrada = transferencia_service::create(&pool, transferencia(15.0)).await.unwrap();
+        assert_eq!(registrada.documento, "TR-000001");
+        assert!(registrada.advertencias.is_empty());
+
+        assert_eq!(stock(&pool, 1).await, 5.0);
+        assert_eq!(stock(&pool, 2).await, 15.0);
+
+        let lotes = lote_service::get_disponibles(&pool, 1, 1, 2).await.unwrap();
+        assert_eq!(lotes.len(), 2);
+        assert_eq!(lotes[0].numero_lote, Some("L-2025-01-10".to_string()));
+        assert_eq!(lotes[0].fecha_entrada, "2025-01-10");
+        assert_eq!(lotes[0].fecha_venc, Some("2026-01-01".to_string()));
+        assert_eq!((lotes[0].cantidad_actual, lotes[0].costo_unitario), (10.0, 5.0));
+        assert_eq!((lotes[1].cantidad_actual, lotes[1].costo_unitario), (5.0, 7.0));
+
+        let movimientos = transferencia_service::get_by_documento(&pool, "TR-000001")
+            .await
+            .unwrap()
+            .unwrap()
+            .movimientos;
+        let tipos: Vec<&str> = movimientos.iter().map(|m| m.tipo.as_str()).collect();
+        assert_eq!(tipos, vec!["salida", "entrada"]);
+        assert_eq!(movimientos[1].monto_total, Some(85.0));
+
+        let siguiente = transferencia_service::create(&pool, transferencia(1.0)).await.unwrap();
+        assert_eq!(siguiente.documento, "TR-000002");
+    }
+
+    #[tokio::test]
+    async fn test_stock_insuficiente_no_deja_cambios() {
+        let pool = setup_test_db().await;
+
+        let mut data = transferencia(5.0);
+        data.lineas.push(LineaTransferencia {
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad: 50.0,
+        });
+        let error = transferencia_service::create(&pool, data).await.unwrap_err();
+        assert!(matches!(error, AppError::InsufficientStock(_)));
+
+        assert_eq!(stock(&pool, 1).await, 20.0);
+        assert_eq!(stock(&pool, 2).await, 0.0);
+        assert!(transferencia_service::get_by_documento(&pool, "TR-000001").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_anular_transferencia() {
+        let pool = setup_test_db().await;
+        transferencia_service::create(&pool, transferencia(12.0)).await.unwrap();
+
+        let movimientos = transferencia_service::get_by_documento(&pool, "TR-000001")
+            .await
+            .unwrap()
+            .unwrap()
+            .movimientos;
+        let error = posting_service::delete(&pool, movimientos[1].id_movimiento).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+
+        assert_eq!(transferencia_service::delete(&pool, "TR-000001").await.unwrap(), 2);
+        assert_eq!(stock(&pool, 1).await, 20.0);
+        assert_eq!(stock(&pool, 2).await, 0.0);
+        let lotes = lote_service::get_disponibles(&pool, 1, 1, 1).await.unwrap();
+        assert_eq!(lotes.iter().map(|l| l.cantidad_actual).sum::<f64>(), 20.0);
+        assert!(lote_service::get_disponibles(&pool, 1, 1, 2).await.unwrap().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod conteo_tests {
+    use crate::error::AppError;
+    use crate::models::conteo::{CantidadContada, CreateConteo};
+    use crate::models::movimiento::CreateMovimiento;
+    use crate::services::{conteo_service, movimiento_service, posting_service, stock_almacen_service};
+    use sqlx::SqlitePool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        sqlx::query("INSERT INTO presentacion (id_producto, unidad, cantidad) VALUES (1, 'Caja', 12)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        for (presentacion, cantidad, precio) in [(1, 10.0, 5.0), (2, 4.0, 50.0)] {
+            let entrada = CreateMovimiento {
+                fecha: "2025-01-10".to_string(),
+                tipo: "entrada".to_string(),
+                id_prod_prov: 1,
+                id_presentacion: presentacion,
+                cantidad,
+                precio_unit: Some(precio),
+                id_almacen: 1,
+                ..Default::default()
+            };
+            posting_service::post(&pool, entrada).await.unwrap();
+        }
+        pool
+    }
+
+    fn nuevo_conteo() -> CreateConteo {
+        CreateConteo {
+            id_almacen: 1,
+            fecha: "2025-01-31".to_string(),
+            obs: None,
+        }
+    }
+
+    fn contada(presentacion: i32, cantidad: f64) -> CantidadContada {
+        CantidadContada {
+            id_prod_prov: 1,
+            id_presentacion: presentacion,
+            cantidad_contada: cantidad,
+        }
+    }
+
+    async fn stock(pool: &SqlitePool, presentacion: i32) -> f64 {
+        stock_almacen_service::get_by_ubicacion(pool, 1, presentacion, 1)
+            .await
+            .unwrap()
+            .map_or(0.0, |s| s.stock_actual)
+    }
+
+    #[tokio::test]
+    async fn test_aprobar_ajusta_stock_a_lo_contado() {
+        let pool = setup_test_db().await;
+
+        let id = conteo_service::create(&pool, nuevo_conteo()).await.unwrap() as i32;
+        let detalle = conteo_service::get_detalle(&pool, id).await.unwrap();
+        assert_eq!(detalle.len(), 2);
+        assert_eq!((detalle[0].cantidad_esperada, detalle[0].costo_unitario), (10.0, 5.0));
+
+        conteo_service::registrar(&pool, id, vec![contada(1, 12.0), contada(2, 3.0)]).await.unwrap();
+        assert_eq!(conteo_service::get_by_id(&pool, id).await.unwrap().unwrap().estado, "En conteo");
+
+        let detalle = conteo_service::get_detalle(&pool, id).await.unwrap();
+        assert_eq!((detalle[0].diferencia, detalle[0].impacto), (Some(2.0), Some(10.0)));
+        assert_eq!((detalle[1].diferencia, detalle[1].impacto), (Some(-1.0), Some(-50.0)));
+
+        let ajustes = conteo_service::aprobar(&pool, id).await.unwrap();
+        assert_eq!(ajustes.len(), 2);
+        assert_eq!(stock(&pool, 1).await, 12.0);
+        assert_eq!(stock(&pool, 2).await, 3.0);
+
+        let movimientos = movimiento_service::get_by_documento(&pool, &conteo_service::documento(id)).await.unwrap();
+        let subtipos: Vec<_> = movimientos.iter().map(|m| m.subtipo.clone().unwrap()).collect();
+        assert_eq!(subtipos, vec!["sobrante", "faltante"]);
+        assert_eq!(conteo_service::get_by_id(&pool, id).await.unwrap().unwrap().estado, "Aprobado");
+    }
+
+    #[tokio::test]
+    async fn test_conteo_en_curso_bloquea_movimientos() {
+        let pool = setup_test_db().await;
+        let id = conteo_service::create(&pool, nuevo_conteo()).await.unwrap() as i32;
+
+        let salida = CreateMovimiento {
+            fecha: "2025-01-31".to_string(),
+            tipo: "salida".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad: 1.0,
+            id_almacen: 1,
+            ..Default::default()
+        };
+        let error = posting_service::post(&pool, salida.clone()).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+        assert!(matches!(
+            conteo_service::create(&pool, nuevo_conteo()).await.unwrap_err(),
+            AppError::Conflict(_)
+        ));
+
+        // Otro almacén no se ve afectado
+        let mut entrada = salida.clone();
+        entrada.tipo = "entrada".to_string();
+        entrada.id_almacen = 2;
+        posting_service::post(&pool, entrada).await.unwrap();
+
+        conteo_service::cancelar(&pool, id).await.unwrap();
+        posting_service::post(&pool, salida).await.unwrap();
+        assert_eq!(stock(&pool, 1).await, 9.0);
+    }
+
+    #[tokio::test]
+    async fn test_aprobar_requiere_todas_las_lineas_contadas() {
+        let pool = setup_test_db().await;
+        let id = conteo_service::create(&pool, nuevo_conteo()).await.unwrap() as i32;
+
+        // Sin cantidades el conteo sigue abierto y no se puede aprobar
+        assert!(matches!(conteo_service::aprobar(&pool, id).await.unwrap_err(), AppError::Conflict(_)));
+
+        conteo_service::registrar(&pool, id, vec![contada(1, 10.0)]).await.unwrap();
+        assert!(matches!(conteo_service::aprobar(&pool, id).await.unwrap_err(), AppError::Conflict(_)));
+        assert_eq!(conteo_service::get_by_id(&pool, id).await.unwrap().unwrap().estado, "En conteo");
+
+        conteo_service::registrar(&pool, id, vec![contada(2, 4.0)]).await.unwrap();
+        assert!(conteo_service::aprobar(&pool, id).await.unwrap().is_empty());
+
+        let error = conteo_service::registrar(&pool, id, vec![contada(1, 1.0)]).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn test_registrar_rechaza_presentacion_de_otro_producto() {
+        let pool = setup_test_db().await;
+        for sql in [
+            "INSERT INTO producto (codigo_interno, descripcion) VALUES ('P-002', 'Otro Producto')",
+            "INSERT INTO presentacion (id_producto, unidad, cantidad) VALUES (2, 'Unidad', 1)",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+        let id = conteo_service::create(&pool, nuevo_conteo()).await.unwrap() as i32;
+
+        let error = conteo_service::registrar(&pool, id, vec![contada(1, 10.0), contada(3, 1.0)]).await.unwrap_err();
+        assert!(matches!(error, AppError::Validation(ref errores) if errores[0].field == "lineas[1].id_presentacion"));
+        assert_eq!(conteo_service::get_detalle(&pool, id).await.unwrap().len(), 2);
+        assert_eq!(conteo_service::get_by_id(&pool, id).await.unwrap().unwrap().estado, "Abierto");
+    }
+}
+
+#[cfg(test)]
+mod conversion_tests {
+    use crate::error::AppError;
+    use crate::models::conversion::CreateConversion;
+    use crate::models::movimiento::CreateMovimiento;
+    use crate::services::{conversion_service, lote_service, posting_service};
+    use sqlx::SqlitePool;
+
+    /// Presentación 1 es la unidad y la 2 una caja de 24
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        for sql in [
+            "INSERT INTO presentacion (id_producto, unidad, cantidad) VALUES (1, 'Caja', 24)",
+            "INSERT INTO producto (codigo_interno, descripcion) VALUES ('P-002', 'Otro Producto')",
+            "INSERT INTO presentacion (id_producto, unidad, cantidad) VALUES (2, 'Unidad', 1)",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+
+        let cajas = CreateMovimiento {
+            fecha: "2025-01-10".to_string(),
+            tipo: "entrada".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 2,
+            cantidad: 3.0,
+            precio_unit: Some(48.0),
+            lote: Some("L-001".to_string()),
+            fecha_venc: Some("2026-01-01".to_string()),
+            id_almacen: 1,
+            ..Default::default()
+        };
+        posting_service::post(&pool, cajas).await.unwrap();
+        pool
+    }
+
+    fn abrir_caja(cantidad: f64) -> CreateConversion {
+        CreateConversion {
+            fecha: "2025-02-01".to_string(),
+            id_prod_prov: 1,
+            id_almacen: 1,
+            id_presentacion_origen: 2,
+            id_presentacion_destino: 1,
+            cantidad,
+            obs: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_convierte_cantidades() {
+        let pool = setup_test_db().await;
+
+        assert_eq!(conversion_service::a_unidad_base(&pool, 2, 2.5).await.unwrap(), 60.0);
+        assert_eq!(conversion_service::convertir(&pool, 1, 2, 12.0).await.unwrap(), 0.5);
+
+        let error = conversion_service::convertir(&pool, 1, 3, 1.0).await.unwrap_err();
+        assert_eq!(error.field(), Some("id_presentacion_destino"));
+    }
+
+    #[tokio::test]
+    async fn test_abrir_caja_conserva_costo_y_lote() {
+        let pool = setup_test_db().await;
+
+        let registrada = conversion_service::create(&pool, abrir_caja(1.0)).await.unwrap();
+        assert_eq!(registrada.documento, "CV-000001");
+        assert_eq!(registrada.cantidad_destino, 24.0);
+
+        let unidades = lote_service::get_disponibles(&pool, 1, 1, 1).await.unwrap();
+        assert_eq!(unidades.len(), 1);
+        assert_eq!(unidades[0].cantidad_actual, 24.0);
+        assert_eq!(unidades[0].costo_unitario, 2.0);
+        assert_eq!(unidades[0].numero_lote, Some("L-001".to_string()));
+        assert_eq!(unidades[0].fecha_venc, Some("2026-01-01".to_string()));
+
+        let stock = conversion_service::get_stock_base(&pool, Some(1)).await.unwrap();
+        assert_eq!(stock.len(), 2);
+        assert_eq!(stock[0].unidad_base, Some("Unidad".to_string()));
+        assert_eq!(stock[0].stock_base, 72.0);
+
+        // Los productos sin stock en el almacén figuran con cero
+        assert_eq!(stock[1].stock_base, 0.0);
+        let stock = conversion_service::get_stock_base(&pool, Some(2)).await.unwrap();
+        let base: Vec<_> = stock.iter().map(|s| (s.id_producto, s.stock_base)).collect();
+        assert_eq!(base, vec![(1, 0.0), (2, 0.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_anular_conversion() {
+        let pool = setup_test_db().await;
+        conversion_service::create(&pool, abrir_caja(2.0)).await.unwrap();
+
+        assert_eq!(conversion_service::delete(&pool, "CV-000001").await.unwrap(), 2);
+        let cajas = lote_service::get_disponibles(&pool, 1, 2, 1).await.unwrap();
+        assert_eq!(cajas[0].cantidad_actual, 3.0);
+        assert!(lote_service::get_disponibles(&pool, 1, 1, 1).await.unwrap().is_empty());
+
+        let mut ajena = abrir_caja(1.0);
+        ajena.id_presentacion_destino = 3;
+        assert!(matches!(
+            conversion_service::create(&pool, ajena).await.unwrap_err(),
+            AppError::Validation(_)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod stock_parametro_tests {
+    use crate::error::AppError;
+    use crate::models::movimiento::CreateMovimiento;
+    use crate::models::stock_parametro::SetStockParametro;
+    use crate::services::{posting_service, stock_parametro_service};
+    use crate::validation::Validate;
+    use sqlx::SqlitePool;
+
+    /// 2 cajas de 12 (24 unidades) y 6 unidades sueltas en el almacén 1
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        sqlx::query("INSERT INTO presentacion (id_producto, unidad, cantidad) VALUES (1, 'Caja', 12)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        for (presentacion, cantidad) in [(2, 2.0), (1, 6.0)] {
+            let entrada = CreateMovimiento {
+                fecha: "2025-01-10".to_string(),
+                tipo: "entrada".to_string(),
+                id_prod_prov: 1,
+                id_presentacion: presentacion,
+                cantidad,
+                id_almacen: 1,
+                ..Default::default()
+            };
+            posting_service::post(&pool, entrada).await.unwrap();
+        }
+        pool
+    }
+
+    fn parametros(almacen_id: Option<i32>, minimo: f64, reorden: f64, maximo: f64) -> SetStockParametro {
+        SetStockParametro {
+            id_producto: 1,
+            id_almacen: almacen_id,
+            stock_minimo: Some(minimo),
+            stock_maximo: Some(maximo),
+            punto_reorden: Some(reorden),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sin_parametros_no_hay_stock_bajo() {
+        let pool = setup_test_db().await;
+        assert!(stock_parametro_service::get_productos_stock_bajo(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_compara_en_unidad_base_con_sobrescritura_por_almacen() {
+        let pool = setup_test_db().await;
+
+        // 30 unidades en el almacén 1 superan el punto de reorden general
+        stock_parametro_service::set(&pool, parametros(None, 10.0, 20.0, 100.0)).await.unwrap();
+        let niveles = stock_parametro_service::get_niveles(&pool, Some(1)).await.unwrap();
+        assert_eq!(niveles[0].stock_base, 30.0);
+
+        // El almacén 2 no tiene stock y queda bajo el mínimo general
+        let bajo = stock_parametro_service::get_stock_bajo(&pool, None).await.unwrap();
+        let almacenes: Vec<i32> = bajo.iter().map(|n| n.id_almacen).collect();
+        assert_eq!(almacenes, vec![2]);
+
+        // El almacén 1 sube su punto de reorden; conserva mínimo y máximo generales
+        let sobrescrito = SetStockParametro {
+            id_producto: 1,
+            id_almacen: Some(1),
+            punto_reorden: Some(40.0),
+            ..Default::default()
+        };
+        stock_parametro_service::set(&pool, sobrescrito).await.unwrap();
+        let bajo = stock_parametro_service::get_stock_bajo(&pool, Some(1)).await.unwrap();
+        assert_eq!(bajo.len(), 1);
+        assert_eq!((bajo[0].stock_minimo, bajo[0].punto_reorden), (Some(10.0), Some(40.0)));
+
+        let productos = stock_parametro_service::get_productos_stock_bajo(&pool).await.unwrap();
+        assert_eq!(productos.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sugerencia_de_reorden() {
+        let pool = setup_test_db().await;
+        stock_parametro_service::set(&pool, parametros(Some(1), 10.0, 40.0, 100.0)).await.unwrap();
+
+        let sugerencias = stock_parametro_service::get_sugerencias(&pool, None).await.unwrap();
+        assert_eq!(sugerencias.len(), 1);
+        assert_eq!(sugerencias[0].cantidad_sugerida, 70.0);
+        assert_eq!(sugerencias[0].id_prod_prov, Some(1));
+        assert_eq!(sugerencias[0].proveedor, Some("Proveedor Test".to_string()));
+
+        // Sin valores se elimina la fila
+        let vacio = SetStockParametro {
+            id_producto: 1,
+            id_almacen: Some(1),
+            ..Default::default()
+        };
+        stock_parametro_service::set(&pool, vacio).await.unwrap();
+        assert!(stock_parametro_service::get_by_producto(&pool, 1).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_valida_orden_de_parametros() {
+        let error = parametros(None, 10.0, 5.0, 8.0).validate().unwrap_err();
+        let AppError::Validation(errores) = error else {
+            panic!("se esperaba Validation");
+        };
+        let campos: Vec<&str> = errores.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(campos, vec!["stock_maximo", "punto_reorden"]);
+    }
+}
+
+#[cfg(test)]
+mod orden_compra_tests {
+    use crate::error::AppError;
+    use crate::models::movimiento::{Movimiento, UpdateMovimiento};
+    use crate::models::orden_compra::{
+        LineaOrdenInput, LineaRecepcion, OrdenCompraInput, RecepcionOrden, ORDEN_BORRADOR, ORDEN_CERRADA,
+        ORDEN_RECIBIDA, ORDEN_RECIBIDA_PARCIALMENTE,
+    };
+    use crate::models::stock_parametro::SetStockParametro;
+    use crate::services::{orden_compra_service, posting_service, stock_parametro_service};
+    use sqlx::SqlitePool;
+
+    /// Catálogo con una caja de 12 (presentación 2) y una factura del proveedor 1
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        for sql in [
+            "INSERT INTO presentacion (id_producto, unidad, cantidad) VALUES (1, 'Caja', 12)",
+            "INSERT INTO factura (numero, fecha, id_proveedor) VALUES ('F-001', '2025-01-20', 1)",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    fn orden(cantidad: f64) -> OrdenCompraInput {
+        OrdenCompraInput {
+            numero: None,
+            fecha: "2025-01-15".to_string(),
+            id_proveedor: 1,
+            id_almacen: 1,
+            fecha_entrega: None,
+            obs: None,
+            lineas: vec![LineaOrdenInput {
+                id_prod_prov: 1,
+                id_presentacion: 2,
+                cantidad,
+                precio_unit: 60.0,
+            }],
+        }
+    }
+
+    fn recepcion(linea_id: i32, cantidad: f64, factura_id: Option<i32>) -> RecepcionOrden {
+        RecepcionOrden {
+            fecha: "2025-01-20".to_string(),
+            id_factura: factura_id,
+            lineas: vec![LineaRecepcion {
+                id_linea: linea_id,
+                cantidad,
+                precio_unit: None,
+                lote: None,
+                fecha_venc: None,
+            }],
+        }
+    }
+
+    async fn estado(pool: &SqlitePool, id: i32) -> String {
+        orden_compra_service::get_by_id(pool, id).await.unwrap().unwrap().estado
+    }
+
+    #[tokio::test]
+    async fn test_recepcion_parcial_y_total() {
+        let pool = setup_test_db().await;
+        let id = orden_compra_service::create(&pool, orden(10.0)).await.unwrap() as i32;
+        let creada = orden_compra_service::get_by_id(&pool, id).await.unwrap().unwrap();
+        assert_eq!((creada.numero.as_str(), creada.estado.as_str()), ("OC-000001", ORDEN_BORRADOR));
+        let linea = orden_compra_service::get_lineas(&pool, id).await.unwrap()[0].id_linea;
+
+        // Un borrador todavía no se puede recibir
+        let error = orden_compra_service::recibir(&pool, id, recepcion(linea, 4.0, Some(1))).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+
+        orden_compra_service::enviar(&pool, id).await.unwrap();
+        let entradas = orden_compra_service::recibir(&pool, id, recepcion(linea, 4.0, Some(1))).await.unwrap();
+        assert_eq!(estado(&pool, id).await, ORDEN_RECIBIDA_PARCIALMENTE);
+
+        let entrada = sqlx::query_as::<_, Movimiento>("SELECT * FROM movimiento WHERE id_movimiento = ?")
+            .bind(entradas[0])
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(entrada.id_factura, Some(1));
+        assert_eq!(entrada.precio_unit, Some(60.0));
+        assert_eq!(entrada.documento.as_deref(), Some("OC-000001"));
+
+        // No se recibe más de lo pendiente
+        let error = orden_compra_service::recibir(&pool, id, recepcion(linea, 7.0, None)).await.unwrap_err();
+        let AppError::Validation(errores) = error else {
+            panic!("se esperaba Validation");
+        };
+        assert_eq!(errores[0].field, "lineas[0].cantidad");
+
+        orden_compra_service::recibir(&pool, id, recepcion(linea, 6.0, None)).await.unwrap();
+        assert_eq!(estado(&pool, id).await, ORDEN_RECIBIDA);
+
+        // Eliminar una entrada vuelve a dejar pendiente su cantidad
+        posting_service::delete(&pool, entradas[0] as i32).await.unwrap();
+        assert_eq!(estado(&pool, id).await, ORDEN_RECIBIDA_PARCIALMENTE);
+        assert_eq!(orden_compra_service::get_lineas(&pool, id).await.unwrap()[0].cantidad_recibida, 6.0);
+
+        orden_compra_service::cerrar(&pool, id).await.unwrap();
+        assert_eq!(estado(&pool, id).await, ORDEN_CERRADA);
+    }
+
+    #[tokio::test]
+    async fn test_update_no_cambia_cantidad_recibida() {
+        let pool = setup_test_db().await;
+        let id = orden_compra_service::create(&pool, orden(10.0)).await.unwrap() as i32;
+        let linea = orden_compra_service::get_lineas(&pool, id).await.unwrap()[0].id_linea;
+        orden_compra_service::enviar(&pool, id).await.unwrap();
+        let entradas = orden_compra_service::recibir(&pool, id, recepcion(linea, 4.0, None)).await.unwrap();
+        let entrada = entradas[0] as i32;
+
+        for data in [
+            UpdateMovimiento {
+                cantidad: Some(40.0),
+                ..Default::default()
+            },
+            UpdateMovimiento {
+                id_presentacion: Some(1),
+                ..Default::default()
+            },
+        ] {
+            let error = posting_service::update(&pool, entrada, data).await.unwrap_err();
+            assert!(matches!(error, AppError::Conflict(_)));
+        }
+
+        // Los demás datos se pueden corregir y la orden sigue al día
+        let data = UpdateMovimiento {
+            cantidad: Some(4.0),
+            lote: Some("L-1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(posting_service::update(&pool, entrada, data).await.unwrap(), 1);
+        assert_eq!(estado(&pool, id).await, ORDEN_RECIBIDA_PARCIALMENTE);
+        assert_eq!(orden_compra_service::get_lineas(&pool, id).await.unwrap()[0].cantidad_recibida, 4.0);
+    }
+
+    #[tokio::test]
+    async fn test_productos_y_factura_deben_ser_del_proveedor() {
+        let pool = setup_test_db().await;
+        for sql in [
+            "INSERT INTO proveedor (ruc_ci, nombre) VALUES ('0012505900002B', 'Otro Proveedor')",
+            "INSERT INTO factura (numero, fecha, id_proveedor) VALUES ('F-002', '2025-01-20', 2)",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+
+        let ajena = OrdenCompraInput { id_proveedor: 2, ..orden(5.0) };
+        let error = orden_compra_service::create(&pool, ajena).await.unwrap_err();
+        assert!(matches!(error, AppError::Validation(_)));
+        assert!(orden_compra_service::get_all(&pool).await.unwrap().is_empty());
+
+        let id = orden_compra_service::create(&pool, orden(5.0)).await.unwrap() as i32;
+        orden_compra_service::enviar(&pool, id).await.unwrap();
+        let linea = orden_compra_service::get_lineas(&pool, id).await.unwrap()[0].id_linea;
+
+        let error = orden_compra_service::recibir(&pool, id, recepcion(linea, 1.0, Some(2))).await.unwrap_err();
+        let AppError::Validation(errores) = error else {
+            panic!("se esperaba Validation");
+        };
+        assert_eq!(errores[0].field, "id_factura");
+
+        // Una orden enviada ya no se modifica ni se elimina
+        let error = orden_compra_service::delete(&pool, id).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn test_pendiente_cuenta_como_en_transito() {
+        let pool = setup_test_db().await;
+        let parametros = SetStockParametro {
+            id_producto: 1,
+            id_almacen: Some(1),
+            stock_minimo: Some(10.0),
+            stock_maximo: Some(100.0),
+            punto_reorden: Some(30.0),
+        };
+        stock_parametro_service::set(&pool, parametros).await.unwrap();
+
+        // El borrador no cuenta; 2 cajas enviadas son 24 unidades en tránsito
+        let id = orden_compra_service::create(&pool, orden(2.0)).await.unwrap() as i32;
+        let sugerencias = stock_parametro_service::get_sugerencias(&pool, Some(1)).await.unwrap();
+        assert_eq!(sugerencias[0].cantidad_sugerida, 100.0);
+
+        orden_compra_service::enviar(&pool, id).await.unwrap();
+        let niveles = stock_parametro_service::get_niveles(&pool, Some(1)).await.unwrap();
+        assert_eq!((niveles[0].stock_base, niveles[0].en_transito), (0.0, 24.0));
+        let sugerencias = stock_parametro_service::get_sugerencias(&pool, Some(1)).await.unwrap();
+        assert_eq!(sugerencias[0].cantidad_sugerida, 76.0);
+
+        // Recibida una caja, pasa de tránsito a stock
+        let linea = orden_compra_service::get_lineas(&pool, id).await.unwrap()[0].id_linea;
+        orden_compra_service::recibir(&pool, id, recepcion(linea, 1.0, None)).await.unwrap();
+        let niveles = stock_parametro_service::get_niveles(&pool, Some(1)).await.unwrap();
+        assert_eq!((niveles[0].stock_base, niveles[0].en_transito), (12.0, 12.0));
+
+        // Con lo pedido alcanza el punto de reorden: deja de estar bajo
+        let otra = orden_compra_service::create(&pool, orden(1.0)).await.unwrap() as i32;
+        orden_compra_service::enviar(&pool, otra).await.unwrap();
+        assert!(stock_parametro_service::get_stock_bajo(&pool, Some(1)).await.unwrap().is_empty());
+
+        // Al cerrar, lo que no llegó deja de esperarse
+        orden_compra_service::cerrar(&pool, id).await.unwrap();
+        let niveles = stock_parametro_service::get_niveles(&pool, Some(1)).await.unwrap();
+        assert_eq!(niveles[0].en_transito, 12.0);
+    }
+}
+
+#[cfg(test)]
+mod factura_totales_tests {
+    use crate::error::AppError;
+    use crate::models::factura::{CreateFacturaConLineas, FacturaInput, FacturaUpdate, LineaFactura};
+    use crate::services::{factura_service, stock_almacen_service};
+    use sqlx::SqlitePool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        pool
+    }
+
+    fn linea(cantidad: f64, precio_unit: f64) -> LineaFactura {
+        LineaFactura {
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad,
+            precio_unit,
+            lote: None,
+            fecha_venc: None,
+            obs: None,
+        }
+    }
+
+    /// Subtotal 100, descuento 10 e IVA 15 %: total 103.50
+    fn factura(total: Option<f64>, lineas: Vec<LineaFactura>) -> CreateFacturaConLineas {
+        CreateFacturaConLineas {
+            factura: FacturaInput {
+                numero: "F-100".to_string(),
+                fecha: "2025-02-01".to_string(),
+                id_proveedor: 1,
+                total,
+                estado: None,
+                descuento: Some(10.0),
+                porcentaje_impuesto: Some(15.0),
+            },
+            id_almacen: 1,
+            lineas,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_factura_con_lineas_calcula_totales() {
+        let pool = setup_test_db().await;
+
+        let data = factura(Some(103.5), vec![linea(10.0, 5.0), linea(4.0, 12.5)]);
+        let registrada = factura_service::create_con_lineas(&pool, data).await.unwrap();
+        assert_eq!(registrada.movimientos.len(), 2);
+        let totales = &registrada.totales;
+        assert_eq!((totales.subtotal, totales.impuesto, totales.total_calculado), (100.0, 13.5, 103.5));
+        assert!(!totales.descuadrada);
+
+        let stock = stock_almacen_service::get_by_almacen(&pool, 1).await.unwrap();
+        assert_eq!(stock[0].stock_actual, 14.0);
+
+        // Un total impreso distinto marca la factura como descuadrada
+        let impreso = FacturaUpdate {
+            total: Some(110.0),
+            ..Default::default()
+        };
+        factura_service::update_factura(&pool, registrada.id_factura as i32, impreso).await.unwrap();
+        let descuadradas = factura_service::get_descuadradas(&pool).await.unwrap();
+        assert_eq!(descuadradas.len(), 1);
+        assert_eq!(descuadradas[0].diferencia, Some(6.5));
+    }
+
+    #[tokio::test]
+    async fn test_factura_con_lineas_es_atomica() {
+        let pool = setup_test_db().await;
+        for sql in [
+            "INSERT INTO producto (codigo_interno, descripcion) VALUES ('P-002', 'Otro Producto')",
+            "INSERT INTO presentacion (id_producto, unidad, cantidad) VALUES (2, 'Unidad', 1)",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+
+        // La segunda línea usa una presentación de otro producto
+        let ajena = LineaFactura { id_presentacion: 2, ..linea(1.0, 5.0) };
+        let error = factura_service::create_con_lineas(&pool, factura(None, vec![linea(10.0, 5.0), ajena]))
+            .await
+            .unwrap_err();
+        let AppError::Validation(errores) = error else {
+            panic!("se esperaba Validation");
+        };
+        assert_eq!(errores[0].field, "lineas[1].id_prod_prov");
+
+        assert!(factura_service::get_facturas(&pool).await.unwrap().is_empty());
+        let movimientos: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM movimiento")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(movimientos, 0);
+    }
+}
+
+#[cfg(test)]
+mod factura_anulacion_tests {
+    use crate::error::AppError;
+    use crate::models::factura::{
+        AnularFactura, CreateFacturaConLineas, FacturaInput, FacturaUpdate, LineaFactura, FACTURA_ACTIVA,
+        FACTURA_ANULADA, FACTURA_BORRADOR, FACTURA_PAGADA, SUBTIPO_ANULACION,
+    };
+    use crate::models::movimiento::{CreateMovimiento, Movimiento};
+    use crate::models::pago::{AplicacionPago, CreatePago};
+    use crate::services::{factura_service, lote_service, pago_service, posting_service, stock_almacen_service};
+    use sqlx::SqlitePool;
+
+    /// Factura activa con una entrada de 10 unidades en el almacén 1
+    async fn setup_test_db() -> (SqlitePool, i32) {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+
+        let data = CreateFacturaConLineas {
+            factura: FacturaInput {
+                numero: "F-200".to_string(),
+                fecha: "2025-03-01".to_string(),
+                id_proveedor: 1,
+                total: Some(50.0),
+                estado: None,
+                descuento: None,
+                porcentaje_impuesto: None,
+            },
+            id_almacen: 1,
+            lineas: vec![LineaFactura {
+                id_prod_prov: 1,
+                id_presentacion: 1,
+                cantidad: 10.0,
+                precio_unit: 5.0,
+                lote: Some("L-1".to_string()),
+                fecha_venc: None,
+                obs: None,
+            }],
+        };
+        let registrada = factura_service::create_con_lineas(&pool, data).await.unwrap();
+        (pool, registrada.id_factura as i32)
+    }
+
+    fn anulacion() -> AnularFactura {
+        AnularFactura {
+            fecha: "2025-03-05".to_string(),
+            obs: Some("Mercadería rechazada".to_string()),
+        }
+    }
+
+    async fn estado(pool: &SqlitePool, id: i32) -> Option<String> {
+        factura_service::get_factura_by_id(pool, id).await.unwrap().unwrap().estado
+    }
+
+    async fn stock(pool: &SqlitePool) -> f64 {
+        stock_almacen_service::get_by_almacen(pool, 1).await.unwrap()[0].stock_actual
+    }
+
+    #[tokio::test]
+    async fn test_anular_revierte_entradas_y_lotes() {
+        let (pool, id) = setup_test_db().await;
+
+        let reversos = factura_service::anular(&pool, id, anulacion()).await.unwrap();
+        assert_eq!(reversos.len(), 1);
+        assert_eq!(estado(&pool, id).await.as_deref(), Some(FACTURA_ANULADA));
+        assert_eq!(stock(&pool).await, 0.0);
+
+        let reverso = sqlx::query_as::<_, Movimiento>("SELECT * FROM movimiento WHERE id_movimiento = ?")
+            .bind(reversos[0])
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(reverso.tipo, "salida");
+        assert_eq!(reverso.subtipo.as_deref(), Some(SUBTIPO_ANULACION));
+        assert_eq!(reverso.documento, Some(factura_service::documento_anulacion(id)));
+        assert_eq!(reverso.monto_total, Some(50.0));
+
+        let lotes = lote_service::get_all(&pool).await.unwrap();
+        assert_eq!(lotes[0].cantidad_actual, 0.0);
+
+        // Los movimientos de una factura anulada quedan congelados
+        let error = posting_service::delete(&pool, 1).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+        let error = posting_service::delete(&pool, reversos[0] as i32).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+        let nueva = CreateMovimiento {
+            fecha: "2025-03-06".to_string(),
+            tipo: "entrada".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad: 1.0,
+            id_factura: Some(id),
+            id_almacen: 1,
+            ..Default::default()
+        };
+        let error = posting_service::post(&pool, nueva).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn test_anular_con_mercaderia_consumida_se_rechaza() {
+        let (pool, id) = setup_test_db().await;
+        let salida = CreateMovimiento {
+            fecha: "2025-03-02".to_string(),
+            tipo: "salida".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad: 3.0,
+            id_almacen: 1,
+            ..Default::default()
+        };
+        posting_service::post(&pool, salida).await.unwrap();
+
+        let error = factura_service::anular(&pool, id, anulacion()).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+        assert_eq!(estado(&pool, id).await.as_deref(), Some(FACTURA_ACTIVA));
+        assert_eq!(stock(&pool).await, 7.0);
+    }
+
+    #[tokio::test]
+    async fn test_transiciones_de_estado() {
+        let (pool, _) = setup_test_db().await;
+        let borrador = FacturaInput {
+            numero: "F-201".to_string(),
+            fecha: "2025-03-01".to_string(),
+            id_proveedor: 1,
+            total: None,
+            estado: Some(FACTURA_BORRADOR.to_string()),
+            descuento: None,
+            porcentaje_impuesto: None,
+        };
+        let id = factura_service::create_factura(&pool, borrador).await.unwrap() as i32;
+
+        let pagar = || FacturaUpdate {
+            estado: Some(FACTURA_PAGADA.to_string()),
+            ..Default::default()
+        };
+        let error = factura_service::update_factura(&pool, id, pagar()).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+
+        factura_service::activar(&pool, id).await.unwrap();
+        let anular = FacturaUpdate {
+            estado: Some(FACTURA_ANULADA.to_string()),
+            ..Default::default()
+        };
+        let error = factura_service::update_factura(&pool, id, anular).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+
+        // Pagada no se marca a mano: la factura queda pagada cuando el total
+        // baja a lo que ya se pagó
+        let error = factura_service::update_factura(&pool, id, pagar()).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+        let importe = |total| FacturaUpdate {
+            total: Some(total),
+            ..Default::default()
+        };
+        factura_service::update_factura(&pool, id, importe(15.0)).await.unwrap();
+        let pago = CreatePago {
+            fecha: "2025-03-05".to_string(),
+            id_proveedor: 1,
+            monto: 10.0,
+            metodo: "Efectivo".to_string(),
+            referencia: None,
+            obs: None,
+            aplicaciones: vec![AplicacionPago { id_factura: id, monto: 10.0 }],
+        };
+        pago_service::create(&pool, pago).await.unwrap();
+        assert_eq!(estado(&pool, id).await.as_deref(), Some(FACTURA_ACTIVA));
+        factura_service::update_factura(&pool, id, importe(10.0)).await.unwrap();
+        assert_eq!(estado(&pool, id).await.as_deref(), Some(FACTURA_PAGADA));
+
+        // Una factura pagada no cambia ni se anula
+        let total = FacturaUpdate {
+            total: Some(10.0),
+            ..Default::default()
+        };
+        let error = factura_service::update_factura(&pool, id, total).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+        let error = factura_service::anular(&pool, id, anulacion()).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+    }
+}
+
+#[cfg(test)]
+mod pago_tests {
+    use crate::error::AppError;
+    use crate::models::factura::{FacturaInput, FACTURA_ACTIVA, FACTURA_BORRADOR, FACTURA_PAGADA};
+    use crate::models::pago::{AplicacionPago, CreatePago};
+    use crate::services::{factura_service, pago_service};
+    use sqlx::SqlitePool;
+
+    /// Proveedor 1 con 30 días de crédito y un segundo proveedor de contado
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        for sql in [
+            "UPDATE proveedor SET dias_credito = 30 WHERE id_proveedor = 1",
+            "INSERT INTO proveedor (ruc_ci, nombre) VALUES ('0012505900002B', 'Otro Proveedor')",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    async fn factura(pool: &SqlitePool, numero: &str, fecha: &str, id_proveedor: i32, total: f64) -> i32 {
+        let data = FacturaInput {
+            numero: numero.to_string(),
+            fecha: fecha.to_string(),
+            id_proveedor,
+            total: Some(total),
+            estado: None,
+            descuento: None,
+            porcentaje_impuesto: None,
+        };
+        factura_service::create_factura(pool, data).await.unwrap() as i32
+    }
+
+    fn pago(monto: f64, aplicaciones: Vec<AplicacionPago>) -> CreatePago {
+        CreatePago {
+            fecha: "2025-04-10".to_string(),
+            id_proveedor: 1,
+            monto,
+            metodo: "Transferencia".to_string(),
+            referencia: Some("TRX-1".to_string()),
+            obs: None,
+            aplicaciones,
+        }
+    }
+
+    async fn estado(pool: &SqlitePool, id: i32) -> Option<String> {
+        factura_service::get_factura_by_id(pool, id).await.unwrap().unwrap().estado
+    }
+
+    #[tokio::test]
+    async fn test_vencimiento_segun_dias_de_credito() {
+        let pool = setup_test_db().await;
+        let credito = factura(&pool, "F-1", "2025-03-01", 1, 100.0).await;
+        let contado = factura(&pool, "F-2", "2025-03-01", 2, 100.0).await;
+
+        let credito = factura_service::get_factura_by_id(&pool, credito).await.unwrap().unwrap();
+        assert_eq!(credito.fecha_vencimiento.as_deref(), Some("2025-03-31"));
+        let contado = factura_service::get_factura_by_id(&pool, contado).await.unwrap().unwrap();
+        assert_eq!(contado.fecha_vencimiento.as_deref(), Some("2025-03-01"));
+    }
+
+    #[tokio::test]
+    async fn test_pagos_parciales_saldan_la_factura() {
+        let pool = setup_test_db().await;
+        let id = factura(&pool, "F-1", "2025-03-01", 1, 100.0).await;
+
+        pago_service::create(&pool, pago(40.0, vec![AplicacionPago { id_factura: id, monto: 40.0 }]))
+            .await
+            .unwrap();
+        let saldos = pago_service::get_saldos_factura(&pool, Some(1)).await.unwrap();
+        assert_eq!(saldos.len(), 1);
+        assert_eq!(saldos[0].saldo, 60.0);
+        assert_eq!(estado(&pool, id).await.as_deref(), Some(FACTURA_ACTIVA));
+
+        // Lo no aplicado queda a favor y se aplica después
+        let segundo = pago_service::create(&pool, pago(80.0, vec![])).await.unwrap() as i32;
+        let cuentas = pago_service::get_saldos_proveedor(&pool).await.unwrap();
+        assert_eq!(cuentas[0].saldo, 60.0);
+        assert_eq!(cuentas[0].saldo_a_favor, 80.0);
+
+        pago_service::aplicar(&pool, segundo, vec![AplicacionPago { id_factura: id, monto: 60.0 }])
+            .await
+            .unwrap();
+        assert_eq!(estado(&pool, id).await.as_deref(), Some(FACTURA_PAGADA));
+        assert!(pago_service::get_saldos_factura(&pool, None).await.unwrap().is_empty());
+        let cuentas = pago_service::get_saldos_proveedor(&pool).await.unwrap();
+        assert_eq!(cuentas[0].saldo, 0.0);
+        assert_eq!(cuentas[0].saldo_a_favor, 20.0);
+
+        // Sin el pago la factura vuelve a tener saldo
+        assert_eq!(pago_service::delete(&pool, segundo).await.unwrap(), 1);
+        assert_eq!(estado(&pool, id).await.as_deref(), Some(FACTURA_ACTIVA));
+        assert_eq!(pago_service::get_aplicaciones_by_factura(&pool, id).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_aplicaciones_invalidas_se_rechazan() {
+        let pool = setup_test_db().await;
+        let id = factura(&pool, "F-1", "2025-03-01", 1, 100.0).await;
+        let ajena = factura(&pool, "F-2", "2025-03-01", 2, 100.0).await;
+
+        let error = pago_service::create(&pool, pago(150.0, vec![AplicacionPago { id_factura: id, monto: 150.0 }]))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AppError::Validation(ref errores) if errores[0].field == "aplicaciones[0].monto"));
+
+        let error = pago_service::create(&pool, pago(50.0, vec![AplicacionPago { id_factura: ajena, monto: 50.0 }]))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AppError::Validation(ref errores) if errores[0].field == "aplicaciones[0].id_factura"));
+
+        sqlx::query("UPDATE factura SET estado = ? WHERE id_factura = ?")
+            .bind(FACTURA_BORRADOR)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let error = pago_service::create(&pool, pago(50.0, vec![AplicacionPago { id_factura: id, monto: 50.0 }]))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+
+        // Ningún pago rechazado quedó registrado
+        assert!(pago_service::get_all(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_antiguedad_de_saldos() {
+        let pool = setup_test_db().await;
+        // Vencimientos: 2025-01-31, 2025-03-02, 2025-05-31
+        factura(&pool, "F-1", "2025-01-01", 1, 100.0).await;
+        let parcial = factura(&pool, "F-2", "2025-01-31", 1, 50.0).await;
+        factura(&pool, "F-3", "2025-05-01", 1, 30.0).await;
+        factura(&pool, "F-4", "2025-04-01", 2, 20.0).await;
+        pago_service::create(&pool, pago(10.0, vec![AplicacionPago { id_factura: parcial, monto: 10.0 }]))
+            .await
+            .unwrap();
+
+        let antiguedad = pago_service::get_antiguedad(&pool, "2025-05-15").await.unwrap();
+        assert_eq!(antiguedad.len(), 2);
+        // Ordenado por nombre: "Otro Proveedor" antes que "Proveedor Test"
+        let proveedor = &antiguedad[1];
+        assert_eq!(proveedor.nombre, "Proveedor Test");
+        assert_eq!(proveedor.por_vencer, 30.0);
+        assert_eq!(proveedor.dias_61_90, 40.0);
+        assert_eq!(proveedor.dias_mas_90, 100.0);
+        assert_eq!(proveedor.total, 170.0);
+        let otro = &antiguedad[0];
+        assert_eq!(otro.dias_31_60, 20.0);
+
+        // A una fecha anterior no cuentan las facturas ni los pagos posteriores
+        let antiguedad = pago_service::get_antiguedad(&pool, "2025-02-15").await.unwrap();
+        assert_eq!(antiguedad.len(), 1);
+        assert_eq!(antiguedad[0].dias_0_30, 100.0);
+        assert_eq!(antiguedad[0].por_vencer, 50.0);
+        assert_eq!(antiguedad[0].total, 150.0);
+    }
+}
+
+#[cfg(test)]
+mod devolucion_tests {
+    use crate::error::AppError;
+    use crate::models::devolucion::{CreateDevolucion, LineaDevolucion, SUBTIPO_DEVOLUCION};
+    use crate::models::factura::{
+        AnularFactura, CreateFacturaConLineas, FacturaInput, LineaFactura, FACTURA_ACTIVA, FACTURA_PAGADA,
+    };
+    use crate::models::movimiento::CreateMovimiento;
+    use crate::models::pago::{AplicacionPago, CreatePago};
+    use crate::services::{
+        devolucion_service, factura_service, lote_service, pago_service, posting_service, stock_almacen_service,
+    };
+    use crate::validation::Validate;
+    use sqlx::SqlitePool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        pool
+    }
+
+    /// Factura activa con una entrada de 10 unidades a 5 en el almacén 1
+    async fn factura(pool: &SqlitePool, numero: &str) -> i32 {
+        let data = CreateFacturaConLineas {
+            factura: FacturaInput {
+                numero: numero.to_string(),
+                fecha: "2025-03-01".to_string(),
+                id_proveedor: 1,
+                total: Some(50.0),
+                estado: None,
+                descuento: None,
+                porcentaje_impuesto: None,
+            },
+            id_almacen: 1,
+            lineas: vec![LineaFactura {
+                id_prod_prov: 1,
+                id_presentacion: 1,
+                cantidad: 10.0,
+                precio_unit: 5.0,
+                lote: Some(format!("L-{}", numero)),
+                fecha_venc: None,
+                obs: None,
+            }],
+        };
+        factura_service::create_con_lineas(pool, data).await.unwrap().id_factura as i32
+    }
+
+    fn devolucion(id_factura: i32, id_lote: i32, cantidad: f64) -> CreateDevolucion {
+        CreateDevolucion {
+            fecha: "2025-03-10".to_string(),
+            id_factura,
+            motivo: Some("Envases dañados".to_string()),
+            obs: None,
+            lineas: vec![LineaDevolucion { id_lote, cantidad }],
+        }
+    }
+
+    async fn estado(pool: &SqlitePool, id: i32) -> Option<String> {
+        factura_service::get_factura_by_id(pool, id).await.unwrap().unwrap().estado
+    }
+
+    #[tokio::test]
+    async fn test_devolucion_de_entrada_con_tipo_en_mayusculas() {
+        let pool = setup_test_db().await;
+        let id = factura(&pool, "F-1").await;
+        let entrada = CreateMovimiento {
+            fecha: "2025-03-02".to_string(),
+            tipo: " Entrada ".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad: 2.0,
+            precio_unit: Some(5.0),
+            id_factura: Some(id),
+            id_almacen: 1,
+            ..Default::default()
+        };
+        posting_service::post(&pool, entrada.validate().unwrap()).await.unwrap();
+
+        let registrada = devolucion_service::create(&pool, devolucion(id, 2, 2.0)).await.unwrap();
+        assert_eq!(registrada.nota.monto, 10.0);
+    }
+
+    #[tokio::test]
+    async fn test_devolucion_acredita_la_factura() {
+        let pool = setup_test_db().await;
+        let id = factura(&pool, "F-1").await;
+
+        let registrada = devolucion_service::create(&pool, devolucion(id, 1, 4.0)).await.unwrap();
+        assert_eq!(registrada.nota.numero, "NC-000001");
+        assert_eq!(registrada.nota.id_proveedor, 1);
+        assert_eq!(registrada.nota.monto, 20.0);
+        assert_eq!(registrada.movimientos.len(), 1);
+        let salida = &registrada.movimientos[0];
+        assert_eq!(salida.tipo, "salida");
+        assert_eq!(salida.subtipo.as_deref(), Some(SUBTIPO_DEVOLUCION));
+        assert_eq!(salida.id_factura, Some(id));
+        assert_eq!(salida.monto_total, Some(20.0));
+
+        let lote = lote_service::get_by_id(&pool, 1).await.unwrap().unwrap();
+        assert_eq!(lote.cantidad_actual, 6.0);
+        let stock = stock_almacen_service::get_by_almacen(&pool, 1).await.unwrap();
+        assert_eq!(stock[0].stock_actual, 6.0);
+
+        // Solo se deshace con la nota completa
+        let error = posting_service::delete(&pool, salida.id_movimiento).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+
+        let saldos = pago_service::get_saldos_factura(&pool, Some(1)).await.unwrap();
+        assert_eq!(saldos[0].acreditado, 20.0);
+        assert_eq!(saldos[0].saldo, 30.0);
+
+        let cuenta = pago_service::get_estado_cuenta(&pool, 1).await.unwrap();
+        let conceptos: Vec<&str> = cuenta.iter().map(|c| c.concepto.as_str()).collect();
+        assert_eq!(conceptos, ["Factura", "Nota de crédito"]);
+        assert_eq!(cuenta[1].numero.as_deref(), Some("NC-000001"));
+        assert_eq!(cuenta[1].saldo, 30.0);
+
+        // Con lo acreditado, el pago del resto salda la factura
+        let pago = CreatePago {
+            fecha: "2025-03-15".to_string(),
+            id_proveedor: 1,
+            monto: 30.0,
+            metodo: "Efectivo".to_string(),
+            referencia: None,
+            obs: None,
+            aplicaciones: vec![AplicacionPago { id_factura: id, monto: 30.0 }],
+        };
+        pago_service::create(&pool, pago).await.unwrap();
+        assert_eq!(estado(&pool, id).await.as_deref(), Some(FACTURA_PAGADA));
+
+        // Sin la nota la factura vuelve a deber y la mercadería vuelve al lote
+        assert_eq!(devolucion_service::delete(&pool, registrada.nota.id_nota).await.unwrap(), 1);
+        assert_eq!(estado(&pool, id).await.as_deref(), Some(FACTURA_ACTIVA));
+        let lote = lote_service::get_by_id(&pool, 1).await.unwrap().unwrap();
+        assert_eq!(lote.cantidad_actual, 10.0);
+        let cuentas = pago_service::get_saldos_proveedor(&pool).await.unwrap();
+        assert_eq!(cuentas[0].saldo, 20.0);
+    }
+
+    #[tokio::test]
+    async fn test_devoluciones_invalidas_se_rechazan() {
+        let pool = setup_test_db().await;
+        let id = factura(&pool, "F-1").await;
+        factura(&pool, "F-2").await;
+
+        let error = devolucion_service::create(&pool, devolucion(id, 2, 1.0)).await.unwrap_err();
+        assert!(matches!(error, AppError::Validation(ref errores) if errores[0].field == "lineas[0].id_lote"));
+
+        let error = devolucion_service::create(&pool, devolucion(id, 1, 11.0)).await.unwrap_err();
+        assert!(matches!(error, AppError::Validation(ref errores) if errores[0].field == "lineas[0].cantidad"));
+        assert!(devolucion_service::get_all(&pool).await.unwrap().is_empty());
+
+        // Con una devolución registrada la factura no se puede anular
+        devolucion_service::create(&pool, devolucion(id, 1, 1.0)).await.unwrap();
+        let anulacion = AnularFactura {
+            fecha: "2025-03-20".to_string(),
+            obs: None,
+        };
+        let error = factura_service::anular(&pool, id, anulacion).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+        assert_eq!(devolucion_service::get_by_factura(&pool, id).await.unwrap().len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod kardex_tests {
+    use crate::error::AppError;
+    use crate::models::kardex::{FiltroKardex, SaldoKardex};
+    use crate::models::movimiento::CreateMovimiento;
+    use crate::services::{kardex_service, posting_service};
+    use sqlx::SqlitePool;
+
+    /// Unidades y una caja de 12 en el almacén 1, con salidas PEPS, y una
+    /// entrada en el almacén 2
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        sqlx::query("INSERT INTO presentacion (id_producto, unidad, cantidad) VALUES (1, 'Caja', 12)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        for (fecha, tipo, presentacion, cantidad, precio, almacen) in [
+            ("2025-01-05", "entrada", 1, 10.0, Some(2.0), 1),
+            ("2025-02-01", "entrada", 2, 1.0, Some(24.0), 1),
+            ("2025-02-10", "salida", 1, 5.0, None, 1),
+            ("2025-02-15", "entrada", 1, 3.0, Some(3.0), 2),
+            ("2025-03-01", "salida", 1, 2.0, None, 1),
+        ] {
+            let data = CreateMovimiento {
+                fecha: fecha.to_string(),
+                tipo: tipo.to_string(),
+                id_prod_prov: 1,
+                id_presentacion: presentacion,
+                cantidad,
+                precio_unit: precio,
+                id_almacen: almacen,
+                ..Default::default()
+            };
+            posting_service::post(&pool, data).await.unwrap();
+        }
+        pool
+    }
+
+    fn febrero(id_almacen: Option<i32>, id_presentacion: Option<i32>) -> FiltroKardex {
+        FiltroKardex {
+            id_prod_prov: 1,
+            id_almacen,
+            id_presentacion,
+            desde: Some("2025-02-01".to_string()),
+            hasta: Some("2025-02-28".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kardex_de_un_almacen_en_unidad_base() {
+        let pool = setup_test_db().await;
+
+        let kardex = kardex_service::get_kardex(&pool, febrero(Some(1), None)).await.unwrap();
+        assert_eq!(kardex.saldo_inicial, SaldoKardex { cantidad: 10.0, valor: 20.0 });
+        assert_eq!(kardex.lineas.len(), 2);
+
+        let caja = &kardex.lineas[0];
+        assert_eq!(caja.entrada, 12.0);
+        assert_eq!(caja.salida, 0.0);
+        assert_eq!(caja.valor, 24.0);
+        assert_eq!(caja.costo_unitario, Some(2.0));
+        assert_eq!(caja.saldo, SaldoKardex { cantidad: 22.0, valor: 44.0 });
+
+        let salida = &kardex.lineas[1];
+        assert_eq!(salida.salida, 5.0);
+        assert_eq!(salida.valor, 10.0);
+        assert_eq!(salida.saldo, SaldoKardex { cantidad: 17.0, valor: 34.0 });
+
+        assert_eq!(kardex.saldo_final, SaldoKardex { cantidad: 17.0, valor: 34.0 });
+    }
+
+    #[tokio::test]
+    async fn test_kardex_por_presentacion_y_de_todos_los_almacenes() {
+        let pool = setup_test_db().await;
+
+        let todos = kardex_service::get_kardex(&pool, febrero(None, None)).await.unwrap();
+        assert_eq!(todos.lineas.len(), 3);
+        assert_eq!(todos.lineas[2].id_almacen, 2);
+        assert_eq!(todos.saldo_final, SaldoKardex { cantidad: 20.0, valor: 43.0 });
+
+        let cajas = kardex_service::get_kardex(&pool, febrero(None, Some(2))).await.unwrap();
+        assert_eq!(cajas.saldo_inicial, SaldoKardex::default());
+        assert_eq!(cajas.lineas.len(), 1);
+        assert_eq!(cajas.lineas[0].entrada, 1.0);
+        assert_eq!(cajas.lineas[0].costo_unitario, Some(24.0));
+
+        // Sin rango se incluye toda la historia
+        let filtro = FiltroKardex {
+            id_prod_prov: 1,
+            id_almacen: Some(1),
+            ..Default::default()
+        };
+        let completo = kardex_service::get_kardex(&pool, filtro).await.unwrap();
+        assert_eq!(completo.lineas.len(), 4);
+        assert_eq!(completo.saldo_final, SaldoKardex { cantidad: 15.0, valor: 30.0 });
+
+        let error = kardex_service::get_kardex(&pool, febrero(None, Some(99))).await.unwrap_err();
+        assert!(matches!(error, AppError::Validation(_)));
+    }
+}
+
+#[cfg(test)]
+mod valuacion_tests {
+    use crate::models::movimiento::CreateMovimiento;
+    use crate::models::valuacion::{FiltroValuacion, MetodoValuacion, SubtotalAlmacen, SubtotalCategoria};
+    use crate::services::{posting_service, valuacion_service};
+    use sqlx::SqlitePool;
+
+    /// Almacén 1: 10 a 2 y 10 a 4, de los que salen 15. Almacén 2: 5 a 3.
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        sqlx::query("UPDATE producto SET categoria = 'Bebidas' WHERE id_producto = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        for (fecha, tipo, cantidad, precio, almacen) in [
+            ("2025-01-05", "entrada", 10.0, Some(2.0), 1),
+            ("2025-02-01", "entrada", 10.0, Some(4.0), 1),
+            ("2025-02-10", "salida", 15.0, None, 1),
+            ("2025-02-15", "entrada", 5.0, Some(3.0), 2),
+        ] {
+            let data = CreateMovimiento {
+                fecha: fecha.to_string(),
+                tipo: tipo.to_string(),
+                id_prod_prov: 1,
+                id_presentacion: 1,
+                cantidad,
+                precio_unit: precio,
+                id_almacen: almacen,
+                ..Default::default()
+            };
+            posting_service::post(&pool, data).await.unwrap();
+        }
+        pool
+    }
+
+    fn filtro(metodo: MetodoValuacion, fecha_corte: Option<&str>) -> FiltroValuacion {
+        FiltroValuacion {
+            metodo,
+            fecha_corte: fecha_corte.map(str::to_string),
+            id_almacen: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_valuacion_actual_por_metodo() {
+        let pool = setup_test_db().await;
+
+        let peps = valuacion_service::get_valuacion(&pool, filtro(MetodoValuacion::Peps, None)).await.unwrap();
+        assert_eq!(peps.lineas.len(), 2);
+        assert_eq!(peps.lineas[0].cantidad, 5.0);
+        assert_eq!(peps.lineas[0].costo_unitario, Some(4.0));
+        assert_eq!(peps.lineas[0].valor, 20.0);
+        assert_eq!(peps.lineas[1].valor, 15.0);
+        assert_eq!(peps.total, 35.0);
+        assert_eq!(
+            peps.por_almacen,
+            [
+                SubtotalAlmacen { id_almacen: 1, almacen: "Almacén 1".to_string(), valor: 20.0 },
+                SubtotalAlmacen { id_almacen: 2, almacen: "Almacén 2".to_string(), valor: 15.0 },
+            ]
+        );
+        assert_eq!(peps.por_categoria, [SubtotalCategoria { categoria: Some("Bebidas".to_string()), valor: 35.0 }]);
+
+        let promedio = valuacion_service::get_valuacion(&pool, filtro(MetodoValuacion::Promedio, None)).await.unwrap();
+        assert_eq!(promedio.lineas[0].costo_unitario, Some(3.0));
+        assert_eq!(promedio.total, 30.0);
+
+        let ultimo = valuacion_service::get_valuacion(&pool, filtro(MetodoValuacion::UltimoCosto, None)).await.unwrap();
+        assert_eq!(ultimo.lineas[0].costo_unitario, Some(4.0));
+        assert_eq!(ultimo.total, 35.0);
+    }
+
+    #[tokio::test]
+    async fn test_valuacion_a_una_fecha_pasada() {
+        let pool = setup_test_db().await;
+
+        // Antes de la salida quedaban los dos lotes completos del almacén 1
+        let peps = valuacion_service::get_valuacion(&pool, filtro(MetodoValuacion::Peps, Some("2025-02-05")))
+            .await
+            .unwrap();
+        assert_eq!(peps.lineas.len(), 1);
+        assert_eq!(peps.lineas[0].cantidad, 20.0);
+        assert_eq!(peps.lineas[0].costo_unitario, Some(3.0));
+        assert_eq!(peps.total, 60.0);
+
+        let ultimo = valuacion_service::get_valuacion(&pool, filtro(MetodoValuacion::UltimoCosto, Some("2025-01-31")))
+            .await
+            .unwrap();
+        assert_eq!(ultimo.lineas[0].cantidad, 10.0);
+        assert_eq!(ultimo.total, 20.0);
+
+        let almacen = FiltroValuacion {
+            id_almacen: Some(2),
+            ..filtro(MetodoValuacion::Peps, None)
+        };
+        let solo = valuacion_service::get_valuacion(&pool, almacen).await.unwrap();
+        assert_eq!(solo.lineas.len(), 1);
+        assert_eq!(solo.total, 15.0);
+    }
+}
+
+#[cfg(test)]
+mod stock_historico_tests {
+    use crate::error::AppError;
+    use crate::models::movimiento::CreateMovimiento;
+    use crate::models::stock_historico::{FiltroStockHistorico, SaldoHistorico};
+    use crate::services::{posting_service, stock_historico_service};
+    use crate::validation::Validate;
+    use sqlx::SqlitePool;
+
+    async fn registrar(pool: &SqlitePool, fecha: &str, tipo: &str, cantidad: f64, almacen: i32) {
+        let data = CreateMovimiento {
+            fecha: fecha.to_string(),
+            tipo: tipo.to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad,
+            precio_unit: Some(1.0),
+            id_almacen: almacen,
+            ..Default::default()
+        };
+        posting_service::post(pool, data).await.unwrap();
+    }
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        registrar(&pool, "2025-01-10", "entrada", 10.0, 1).await;
+        registrar(&pool, "2025-02-10", "salida", 3.0, 1).await;
+        registrar(&pool, "2025-03-10", "entrada", 5.0, 2).await;
+        pool
+    }
+
+    fn a_fecha(fecha: &str) -> FiltroStockHistorico {
+        FiltroStockHistorico {
+            fecha: fecha.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn saldo(id_almacen: i32, cantidad: f64) -> SaldoHistorico {
+        SaldoHistorico {
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            id_almacen,
+            cantidad,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stock_a_fecha_repasa_los_movimientos() {
+        let pool = setup_test_db().await;
+
+        let enero = stock_historico_service::get_stock_a_fecha(&pool, a_fecha("2025-01-31")).await.unwrap();
+        assert_eq!(enero.saldos, [saldo(1, 10.0)]);
+        assert_eq!(enero.id_corte_base, None);
+
+        let febrero = stock_historico_service::get_stock_a_fecha(&pool, a_fecha("2025-02-28")).await.unwrap();
+        assert_eq!(febrero.saldos, [saldo(1, 7.0)]);
+
+        // Solo días completos: una hora no se puede cortar con movimientos por día
+        assert!(matches!(a_fecha("2025-03-10 08:30:00").validate(), Err(AppError::Validation(_))));
+        let filtro = a_fecha("2025-03-10").validate().unwrap();
+        let marzo = stock_historico_service::get_stock_a_fecha(&pool, filtro).await.unwrap();
+        assert_eq!(marzo.fecha, "2025-03-10");
+        assert_eq!(marzo.saldos, [saldo(1, 7.0), saldo(2, 5.0)]);
+
+        let filtro = FiltroStockHistorico {
+            id_almacen: Some(2),
+            ..a_fecha("2025-03-31")
+        };
+        let almacen = stock_historico_service::get_stock_a_fecha(&pool, filtro).await.unwrap();
+        assert_eq!(almacen.saldos, [saldo(2, 5.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_cortes_aceleran_y_se_descartan_al_cambiar_el_pasado() {
+        let pool = setup_test_db().await;
+
+        let corte = stock_historico_service::crear_corte(&pool, "2025-02-28").await.unwrap();
+        let marzo = stock_historico_service::get_stock_a_fecha(&pool, a_fecha("2025-03-31")).await.unwrap();
+        assert_eq!(marzo.id_corte_base, Some(corte.id_corte));
+        assert_eq!(marzo.saldos, [saldo(1, 7.0), saldo(2, 5.0)]);
+        let enero = stock_historico_service::get_stock_a_fecha(&pool, a_fecha("2025-01-31")).await.unwrap();
+        assert_eq!(enero.id_corte_base, None);
+
+        // Un movimiento posterior al corte no lo afecta
+        registrar(&pool, "2025-04-01", "salida", 1.0, 2).await;
+        assert_eq!(stock_historico_service::get_cortes(&pool).await.unwrap().len(), 1);
+
+        // Uno con fecha anterior lo descarta
+        registrar(&pool, "2025-02-01", "entrada", 1.0, 1).await;
+        assert!(stock_historico_service::get_cortes(&pool).await.unwrap().is_empty());
+        let febrero = stock_historico_service::get_stock_a_fecha(&pool, a_fecha("2025-02-28")).await.unwrap();
+        assert_eq!(febrero.id_corte_base, None);
+        assert_eq!(febrero.saldos, [saldo(1, 8.0)]);
+
+        let corte = stock_historico_service::crear_corte(&pool, "2025-02-28").await.unwrap();
+        assert_eq!(stock_historico_service::delete_corte(&pool, corte.id_corte).await.unwrap(), 1);
+    }
+}
+
+#[cfg(test)]
+mod integridad_tests {
+    use crate::models::conteo::CreateConteo;
+    use crate::models::movimiento::{CreateMovimiento, Movimiento};
+    use crate::services::{conteo_service, integridad_service, lote_service, posting_service, stock_almacen_service};
+    use sqlx::SqlitePool;
+
+    /// Entrada de 10 a 2 en el almacén 1
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        let data = CreateMovimiento {
+            fecha: "2025-03-01".to_string(),
+            tipo: "entrada".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad: 10.0,
+            precio_unit: Some(2.0),
+            id_almacen: 1,
+            ..Default::default()
+        };
+        posting_service::post(&pool, data).await.unwrap();
+        pool
+    }
+
+    async fn ejecutar(pool: &SqlitePool, sql: &str) {
+        sqlx::query(sql).execute(pool).await.unwrap();
+    }
+
+    async fn stock(pool: &SqlitePool, almacen: i32) -> f64 {
+        stock_almacen_service::get_by_almacen(pool, almacen).await.unwrap()[0].stock_actual
+    }
+
+    #[tokio::test]
+    async fn test_reparar_registra_ajustes_sin_cambiar_el_stock() {
+        let pool = setup_test_db().await;
+        assert!(integridad_service::verificar(&pool).await.unwrap().diferencias.is_empty());
+
+        // Ediciones directas del stock
+        ejecutar(&pool, "UPDATE stock_almacen SET stock_actual = 7 WHERE id_almacen = 1").await;
+        ejecutar(
+            &pool,
+            "INSERT INTO stock_almacen (id_prod_prov, id_presentacion, id_almacen, stock_actual) VALUES (1, 1, 2, 3)",
+        )
+        .await;
+
+        let reporte = integridad_service::verificar(&pool).await.unwrap();
+        assert_eq!(reporte.diferencias.len(), 2);
+        assert_eq!(reporte.diferencias[0].esperado, 10.0);
+        assert_eq!(reporte.diferencias[0].diferencia, -3.0);
+        assert_eq!(reporte.diferencias[1].diferencia, 3.0);
+        assert!(reporte.huerfanos.is_empty());
+
+        let reparado = integridad_service::reparar(&pool, "2025-03-31").await.unwrap();
+        assert_eq!(reparado.ajustes.len(), 2);
+        assert_eq!(stock(&pool, 1).await, 7.0);
+        assert_eq!(stock(&pool, 2).await, 3.0);
+        assert!(integridad_service::verificar(&pool).await.unwrap().diferencias.is_empty());
+
+        let faltante = sqlx::query_as::<_, Movimiento>("SELECT * FROM movimiento WHERE id_movimiento = ?")
+            .bind(reparado.ajustes[0])
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(faltante.tipo, "ajuste");
+        assert_eq!(faltante.subtipo.as_deref(), Some("faltante"));
+        assert_eq!(faltante.documento.as_deref(), Some("IN-000001"));
+        assert_eq!(faltante.monto_total, Some(6.0));
+        let lotes = lote_service::get_all(&pool).await.unwrap();
+        assert_eq!(lotes[0].cantidad_actual, 7.0);
+        assert_eq!(lotes[1].id_almacen, 2);
+        assert_eq!(lotes[1].cantidad_actual, 3.0);
+    }
+
+    #[tokio::test]
+    async fn test_huerfanos_se_informan_y_no_se_reparan() {
+        let pool = setup_test_db().await;
+        ejecutar(&pool, "INSERT INTO producto (codigo_interno, descripcion) VALUES ('P-002', 'Otro')").await;
+        ejecutar(&pool, "INSERT INTO presentacion (id_producto, unidad, cantidad) VALUES (2, 'Caja', 6)").await;
+
+        // Las claves foráneas se desactivan por conexión
+        let mut conn = pool.acquire().await.unwrap();
+        for sql in [
+            "PRAGMA foreign_keys = OFF",
+            "INSERT INTO stock_almacen (id_prod_prov, id_presentacion, id_almacen, stock_actual) VALUES (99, 1, 1, 4)",
+            "PRAGMA foreign_keys = ON",
+        ] {
+            sqlx::query(sql).execute(&mut *conn).await.unwrap();
+        }
+        drop(conn);
+        ejecutar(
+            &pool,
+            "INSERT INTO stock_almacen (id_prod_prov, id_presentacion, id_almacen, stock_actual) VALUES (1, 2, 1, 5)",
+        )
+        .await;
+
+        let reporte = integridad_service::verificar(&pool).await.unwrap();
+        let problemas: Vec<&str> = reporte.huerfanos.iter().map(|h| h.problema.as_str()).collect();
+        assert_eq!(problemas, ["producto_proveedor_inexistente", "presentacion_de_otro_producto"]);
+        assert!(reporte.huerfanos.iter().all(|h| h.tabla == "stock_almacen"));
+        assert_eq!(reporte.diferencias.len(), 2);
+
+        let reparado = integridad_service::reparar(&pool, "2025-03-31").await.unwrap();
+        assert!(reparado.ajustes.is_empty());
+        assert_eq!(integridad_service::verificar(&pool).await.unwrap().diferencias.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reparar_omite_ubicaciones_bloqueadas() {
+        let pool = setup_test_db().await;
+        ejecutar(&pool, "UPDATE stock_almacen SET stock_actual = 7 WHERE id_almacen = 1").await;
+        ejecutar(
+            &pool,
+            "INSERT INTO stock_almacen (id_prod_prov, id_presentacion, id_almacen, stock_actual) VALUES (1, 1, 2, 3)",
+        )
+        .await;
+        let conteo = CreateConteo {
+            id_almacen: 2,
+            fecha: "2025-03-30".to_string(),
+            obs: None,
+        };
+        conteo_service::create(&pool, conteo).await.unwrap();
+
+        let reparado = integridad_service::reparar(&pool, "2025-03-31").await.unwrap();
+        assert_eq!(reparado.ajustes.len(), 1);
+        assert_eq!(reparado.omitidas.len(), 1);
+        assert_eq!(reparado.omitidas[0].id_almacen, 2);
+        assert!(reparado.omitidas[0].motivo.contains("conteo"));
+
+        // Solo queda la diferencia del almacén bloqueado, con su stock intacto
+        let pendientes = integridad_service::verificar(&pool).await.unwrap().diferencias;
+        assert_eq!(pendientes.len(), 1);
+        assert_eq!(pendientes[0].id_almacen, 2);
+        assert_eq!(stock(&pool, 1).await, 7.0);
+        assert_eq!(stock(&pool, 2).await, 3.0);
+    }
+}
+
+#[cfg(test)]
+mod periodo_tests {
+    use crate::error::{AppError, Result};
+    use crate::models::factura::FacturaInput;
+    use crate::models::movimiento::{CreateMovimiento, UpdateMovimiento};
+    use crate::models::periodo::{CerrarPeriodo, CierrePeriodo, ReabrirPeriodo, PERIODO_ABIERTO, PERIODO_CERRADO};
+    use crate::models::valuacion::MetodoValuacion;
+    use crate::services::{
+        auditoria_service, factura_service, periodo_service, posting_service, sesion_service, stock_historico_service,
+    };
+    use sqlx::SqlitePool;
+
+    /// Enero: entrada de 10 a 2. Febrero: entrada de 5 a 3.
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        for (fecha, cantidad, precio) in [("2025-01-05", 10.0, 2.0), ("2025-02-10", 5.0, 3.0)] {
+            posting_service::post(&pool, entrada(fecha, cantidad, precio)).await.unwrap();
+        }
+        pool
+    }
+
+    fn entrada(fecha: &str, cantidad: f64, precio: f64) -> CreateMovimiento {
+        CreateMovimiento {
+            fecha: fecha.to_string(),
+            tipo: "entrada".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad,
+            precio_unit: Some(precio),
+            id_almacen: 1,
+            ..Default::default()
+        }
+    }
+
+    async fn cerrar(pool: &SqlitePool, periodo: &str) -> Result<CierrePeriodo> {
+        let data = CerrarPeriodo {
+            periodo: periodo.to_string(),
+            metodo: MetodoValuacion::Peps,
+        };
+        periodo_service::cerrar(pool, data).await
+    }
+
+    fn reapertura(periodo: &str) -> ReabrirPeriodo {
+        ReabrirPeriodo {
+            periodo: periodo.to_string(),
+            motivo: "Factura de enero recibida tarde".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cierre_guarda_valuacion_y_bloquea_cambios() {
+        let pool = setup_test_db().await;
+
+        // Se cierra en orden y solo lo que ya terminó
+        let error = cerrar(&pool, "2025-02").await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+        let error = cerrar(&pool, "2999-01").await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+
+        let cierre = cerrar(&pool, "2025-01").await.unwrap();
+        assert_eq!(cierre.periodo.estado, PERIODO_CERRADO);
+        assert_eq!(cierre.periodo.metodo_valuacion, "peps");
+        assert_eq!(cierre.periodo.valor_total, 20.0);
+        assert_eq!(cierre.lineas.len(), 1);
+        assert_eq!(cierre.lineas[0].cantidad, 10.0);
+        let cortes = stock_historico_service::get_cortes(&pool).await.unwrap();
+        assert_eq!(cortes[0].fecha, "2025-01-31");
+        assert!(matches!(cerrar(&pool, "2025-01").await.unwrap_err(), AppError::Conflict(_)));
+
+        // Nada con fecha de enero se registra, modifica ni elimina
+        let error = posting_service::post(&pool, entrada("2025-01-20", 1.0, 2.0)).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+        let cantidad = UpdateMovimiento {
+            cantidad: Some(8.0),
+            ..Default::default()
+        };
+        let error = posting_service::update(&pool, 1, cantidad).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+        let a_enero = UpdateMovimiento {
+            fecha: Some("2025-01-31".to_string()),
+            ..Default::default()
+        };
+        let error = posting_service::update(&pool, 2, a_enero).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+        let error = posting_service::delete(&pool, 1).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+        let factura = FacturaInput {
+            numero: "F-100".to_string(),
+            fecha: "2025-01-15".to_string(),
+            id_proveedor: 1,
+            total: None,
+            estado: None,
+            descuento: None,
+            porcentaje_impuesto: None,
+        };
+        let error = factura_service::create_factura(&pool, factura.clone()).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+
+        // Febrero sigue abierto
+        posting_service::post(&pool, entrada("2025-02-20", 1.0, 3.0)).await.unwrap();
+        let febrero = FacturaInput {
+            fecha: "2025-02-15".to_string(),
+            ..factura
+        };
+        factura_service::create_factura(&pool, febrero).await.unwrap();
+
+        // La valuación guardada no cambia con lo posterior
+        let guardado = periodo_service::get_cierre(&pool, "2025-01").await.unwrap().unwrap();
+        assert_eq!(guardado.lineas.len(), 1);
+        assert_eq!(guardado.lineas[0].valor, 20.0);
+    }
+
+    #[tokio::test]
+    async fn test_reapertura_en_orden_y_auditada() {
+        let pool = setup_test_db().await;
+        cerrar(&pool, "2025-01").await.unwrap();
+        let febrero = cerrar(&pool, "2025-02").await.unwrap();
+        assert_eq!(febrero.periodo.valor_total, 35.0);
+        sesion_service::set_usuario(&pool, Some("mlopez")).await.unwrap();
+
+        // Enero no se reabre mientras febrero siga cerrado
+        let error = periodo_service::reabrir(&pool, reapertura("2025-01")).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+
+        let periodo = periodo_service::reabrir(&pool, reapertura("2025-02")).await.unwrap();
+        assert_eq!(periodo.estado, PERIODO_ABIERTO);
+        assert!(periodo.reabierto_en.is_some());
+        let periodo = periodo_service::reabrir(&pool, reapertura("2025-01")).await.unwrap();
+        assert_eq!(periodo.motivo_reapertura.as_deref(), Some("Factura de enero recibida tarde"));
+        let error = periodo_service::reabrir(&pool, reapertura("2025-01")).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)));
+
+        let historial = auditoria_service::get_by_registro(&pool, "periodo", periodo.id_periodo as i64)
+            .await
+            .unwrap();
+        let cambio = &historial[0];
+        assert_eq!(cambio.operacion, "UPDATE");
+        assert_eq!(cambio.usuario.as_deref(), Some("mlopez"));
+        let despues: serde_json::Value = serde_json::from_str(cambio.despues.as_ref().unwrap()).unwrap();
+        assert_eq!(despues["estado"], PERIODO_ABIERTO);
+
+        // Reabierto admite cambios y el nuevo cierre reemplaza la valuación
+        posting_service::post(&pool, entrada("2025-01-20", 5.0, 4.0)).await.unwrap();
+        let cierre = cerrar(&pool, "2025-01").await.unwrap();
+        assert_eq!(cierre.periodo.id_periodo, periodo.id_periodo);
+        assert_eq!(cierre.periodo.valor_total, 40.0);
+        let guardado = periodo_service::get_cierre(&pool, "2025-01").await.unwrap().unwrap();
+        assert_eq!(guardado.lineas[0].cantidad, 15.0);
+    }
+}
+
+#[cfg(test)]
+mod vencimiento_tests {
+    use crate::models::configuracion::EstrategiaSalida;
+    use crate::models::movimiento::CreateMovimiento;
+    use crate::models::vencimiento::{FiltroVencimiento, RiesgoAlmacen};
+    use crate::services::{configuracion_service, lote_service, movimiento_service, posting_service, vencimiento_service};
+    use sqlx::SqlitePool;
+
+    /// Almacén 1: 10 a 2 que vencen en junio, 10 a 3 que vencen en marzo y 5
+    /// a 1 sin vencimiento. Almacén 2: 4 a 5 que vencen en abril.
+    async fn setup_test_db() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        super::seed_catalogo(&pool).await;
+        for (fecha, cantidad, precio, venc, almacen) in [
+            ("2025-01-01", 10.0, 2.0, Some("2025-06-30"), 1),
+            ("2025-01-05", 10.0, 3.0, Some("2025-03-31"), 1),
+            ("2025-01-10", 5.0, 1.0, None, 1),
+            ("2025-01-10", 4.0, 5.0, Some("2025-04-20"), 2),
+        ] {
+            let data = CreateMovimiento {
+                fecha: fecha.to_string(),
+                tipo: "entrada".to_string(),
+                id_prod_prov: 1,
+                id_presentacion: 1,
+                cantidad,
+                precio_unit: Some(precio),
+                fecha_venc: venc.map(str::to_string),
+                id_almacen: almacen,
+                ..Default::default()
+            };
+            posting_service::post(&pool, data).await.unwrap();
+        }
+        pool
+    }
+
+    fn salida(cantidad: f64) -> CreateMovimiento {
+        CreateMovimiento {
+            fecha: "2025-02-01".to_string(),
+            tipo: "salida".to_string(),
+            id_prod_prov: 1,
+            id_presentacion: 1,
+            cantidad,
+            id_almacen: 1,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lotes_vencidos_y_por_vencer() {
+        let pool = setup_test_db().await;
+
+        let filtro = FiltroVencimiento {
+            dias: 15,
+            fecha: Some("2025-04-10".to_string()),
+            id_almacen: None,
+        };
+        let reporte = vencimiento_service::get_por_vencer(&pool, filtro).await.unwrap();
+        assert_eq!(reporte.lotes.len(), 2);
+        assert_eq!(reporte.lotes[0].fecha_venc, "2025-03-31");
+        assert!(reporte.lotes[0].vencido);
+        assert_eq!(reporte.lotes[0].dias_restantes, -10);
+        assert_eq!(reporte.lotes[0].valor, 30.0);
+        assert!(!reporte.lotes[1].vencido);
+        assert_eq!(reporte.lotes[1].dias_restantes, 10);
+        assert_eq!(
+            reporte.por_almacen,
+            [
+                RiesgoAlmacen {
+                    id_almacen: 1,
+                    almacen: "Almacén 1".to_string(),
+                    lotes_vencidos: 1,
+                    valor_vencido: 30.0,
+                    lotes_por_vencer: 0,
+                    valor_por_vencer: 0.0,
+                },
+                RiesgoAlmacen {
+                    id_almacen: 2,
+                    almacen: "Almacén 2".to_string(),
+                    lotes_vencidos: 0,
+                    valor_vencido: 0.0,
+                    lotes_por_vencer: 1,
+                    valor_por_vencer: 20.0,
+                },
+            ]
+        );
+        assert_eq!(reporte.valor_vencido, 30.0);
+        assert_eq!(reporte.valor_por_vencer, 20.0);
+
+        // Lo que ya salió no está en riesgo
+        posting_service::post(&pool, salida(20.0)).await.unwrap();
+        let filtro = FiltroVencimiento {
+            dias: 365,
+            fecha: Some("2025-04-10".to_string()),
+            id_almacen: Some(1),
+        };
+        let reporte = vencimiento_service::get_por_vencer(&pool, filtro).await.unwrap();
+        assert!(reporte.lotes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fefo_consume_primero_lo_que_vence_antes() {
+        let pool = setup_test_db().await;
+        configuracion_service::set_estrategia_salida(&pool, Some(1), Some(EstrategiaSalida::Fefo))
+            .await
+            .unwrap();
+        assert_eq!(
+            configuracion_service::get_estrategia_salida(&pool, Some(2)).await.unwrap(),
+            EstrategiaSalida::Peps
+        );
+
+        let registrado = posting_service::post(&pool, salida(22.0)).await.unwrap();
+        let movimiento = movimiento_service::get_by_id(&pool, registrado.id_movimiento as i32)
+            .await
+            .unwrap()
+            .unwrap();
+        // Marzo completo, junio completo y 2 del lote sin vencimiento
+        assert_eq!(movimiento.monto_total, Some(30.0 + 20.0 + 2.0));
+
+        let lotes = lote_service::get_all(&pool).await.unwrap();
+        let saldos: Vec<f64> = lotes.iter().map(|l| l.cantidad_actual).collect();
+        assert_eq!(saldos, vec![0.0, 0.0, 3.0, 4.0]);
+
+        // Con PEPS se habría tomado primero el lote de junio
+        configuracion_service::set_estrategia_salida(&pool, Some(1), None).await.unwrap();
+        posting_service::delete(&pool, registrado.id_movimiento as i32).await.unwrap();
+        let registrado = posting_service::post(&pool, salida(12.0)).await.unwrap();
+        let consumos = lote_service::get_consumos_by_movimiento(&pool, registrado.id_movimiento as i32)
+            .await
+            .unwrap();
+        assert_eq!(consumos[0].id_lote, 1);
+        assert_eq!(consumos[0].cantidad, 10.0);
+        assert_eq!(consumos[1].id_lote, 2);
+    }
+}
@@ -0,0 +1,71 @@
+use sqlx::SqlitePool;
+use crate::error::Result;
+use crate::models::movimiento::CreateMovimiento;
+use crate::models::transferencia::{CreateTransferencia, Transferencia, TransferenciaRegistrada, SUBTIPO_TRANSFERENCIA};
+use crate::services::{almacen_service, movimiento_service, posting_service};
+
+/// Prefijo de los números de documento de las transferencias
+const PREFIJO: &str = "TR-";
+
+pub async fn get_by_documento(pool: &SqlitePool, documento: &str) -> Result<Option<Transferencia>> {
+    let movimientos = movimiento_service::get_by_documento(pool, documento).await?;
+    if movimientos.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(Transferencia {
+        documento: documento.to_string(),
+        movimientos,
+    }))
+}
+
+/// Traslada mercadería entre almacenes en una sola transacción. Cada línea
+/// registra una salida del origen y una entrada en el destino con el mismo
+/// número de documento; la entrada recibe los lotes que consumió la salida con
+/// su costo original.
+pub async fn create(pool: &SqlitePool, data: CreateTransferencia) -> Result<TransferenciaRegistrada> {
+    let mut tx = pool.begin().await?;
+
+    almacen_service::verificar_activo(&mut tx, data.id_almacen_origen).await?;
+    almacen_service::verificar_activo(&mut tx, data.id_almacen_destino).await?;
+
+    let documento = movimiento_service::siguiente_documento(&mut tx, PREFIJO).await?;
+    let mut advertencias = Vec::new();
+
+    for linea in &data.lineas {
+        let salida = CreateMovimiento {
+            fecha: data.fecha.clone(),
+            tipo: "salida".to_string(),
+            subtipo: Some(SUBTIPO_TRANSFERENCIA.to_string()),
+            id_prod_prov: linea.id_prod_prov,
+            id_presentacion: linea.id_presentacion,
+            cantidad: linea.cantidad,
+            obs: data.obs.clone(),
+            id_almacen: data.id_almacen_origen,
+            documento: Some(documento.clone()),
+            ..Default::default()
+        };
+        let registrada = posting_service::post_in(&mut tx, &salida).await?;
+        advertencias.extend(registrada.advertencia);
+
+        let entrada = CreateMovimiento {
+            tipo: "entrada".to_string(),
+            id_almacen: data.id_almacen_destino,
+            ..salida
+        };
+        posting_service::post_recepcion_in(&mut tx, registrada.id_movimiento as i32, entrada, 1.0).await?;
+    }
+
+    tx.commit().await?;
+    Ok(TransferenciaRegistrada { documento, advertencias })
+}
+
+/// Anula una transferencia revirtiendo sus movimientos. Falla si ya salió
+/// mercadería de alguno de los lotes recibidos en el destino.
+pub async fn delete(pool: &SqlitePool, documento: &str) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+    let rows = posting_service::delete_documento_in(&mut tx, documento, SUBTIPO_TRANSFERENCIA).await?;
+    tx.commit().await?;
+
+    Ok(rows)
+}
@@ -0,0 +1,111 @@
+use sqlx::SqliteExecutor;
+use crate::error::{AppError, Result};
+use crate::models::valuacion::{FiltroValuacion, LineaValuacion, SubtotalAlmacen, SubtotalCategoria, ValuacionStock};
+use crate::services::movimiento_service;
+
+/// Fecha de corte que incluye todos los movimientos
+const SIN_CORTE: &str = "9999-12-31";
+
+/// Valoriza las existencias a la fecha de corte con el método elegido.
+///
+/// Las cantidades salen de los movimientos con almacén hasta el corte. Los
+/// costos salen de los lotes abiertos hasta esa fecha: para PEPS, el saldo que
+/// les quedaba descontando los consumos posteriores al corte; para el
+/// promedio, todo lo ingresado; y el último costo es el del lote más reciente.
+/// Si en PEPS no queda saldo en lotes se usa el último costo.
+pub async fn get_valuacion<'e, E: SqliteExecutor<'e>>(executor: E, filtro: FiltroValuacion) -> Result<ValuacionStock> {
+    let sql = format!(
+        "WITH existencias AS (
+            SELECT m.id_prod_prov, m.id_presentacion, m.id_almacen, SUM({}) AS cantidad
+            FROM movimiento m
+            WHERE m.id_almacen IS NOT NULL AND m.fecha <= ?1
+            GROUP BY m.id_prod_prov, m.id_presentacion, m.id_almacen
+        ),
+        lotes AS (
+            SELECT l.id_lote, l.id_prod_prov, l.id_presentacion, l.id_almacen, l.costo_unitario,
+                   l.cantidad_inicial, e.fecha,
+                   l.cantidad_inicial - COALESCE((SELECT SUM(c.cantidad) FROM lote_consumo c
+                                                  JOIN movimiento s ON s.id_movimiento = c.id_movimiento
+                                                  WHERE c.id_lote = l.id_lote AND s.fecha <= ?1), 0.0) AS saldo
+            FROM lote l
+            JOIN movimiento e ON e.id_movimiento = l.id_movimiento
+            WHERE e.fecha <= ?1
+        ),
+        costos AS (
+            SELECT id_prod_prov, id_presentacion, id_almacen,
+                   SUM(CASE WHEN saldo > 0 THEN saldo * costo_unitario END)
+                     / SUM(CASE WHEN saldo > 0 THEN saldo END) AS peps,
+                   SUM(cantidad_inicial * costo_unitario) / NULLIF(SUM(cantidad_inicial), 0) AS promedio,
+                   (SELECT u.costo_unitario FROM lotes u
+                    WHERE u.id_prod_prov = lotes.id_prod_prov AND u.id_presentacion = lotes.id_presentacion
+                      AND u.id_almacen = lotes.id_almacen
+                    ORDER BY u.fecha DESC, u.id_lote DESC
+                    LIMIT 1) AS ultimo
+            FROM lotes
+            GROUP BY id_prod_prov, id_presentacion, id_almacen
+        ),
+        valuados AS (
+            SELECT x.*,
+                   CASE ?2 WHEN 'peps' THEN COALESCE(c.peps, c.ultimo)
+                           WHEN 'promedio' THEN c.promedio
+                           ELSE c.ultimo END AS costo
+            FROM existencias x
+            LEFT JOIN costos c ON c.id_prod_prov = x.id_prod_prov AND c.id_presentacion = x.id_presentacion
+                              AND c.id_almacen = x.id_almacen
+            WHERE ?3 IS NULL OR x.id_almacen = ?3
+        )
+        SELECT p.id_producto, p.codigo_interno, p.descripcion, p.categoria,
+               pr.id_presentacion, pr.unidad, a.id_almacen, a.nombre AS almacen,
+               SUM(v.cantidad) AS cantidad,
+               CASE WHEN MAX(v.costo) IS NOT NULL
+                    THEN SUM(v.cantidad * COALESCE(v.costo, 0.0)) / SUM(v.cantidad) END AS costo_unitario,
+               SUM(v.cantidad * COALESCE(v.costo, 0.0)) AS valor
+        FROM valuados v
+        JOIN producto_proveedor pp ON pp.id_prod_prov = v.id_prod_prov
+        JOIN producto p ON p.id_producto = pp.id_producto
+        JOIN presentacion pr ON pr.id_presentacion = v.id_presentacion
+        JOIN almacen a ON a.id_almacen = v.id_almacen
+        GROUP BY p.id_producto, pr.id_presentacion, a.id_almacen
+        HAVING ABS(SUM(v.cantidad)) > 1e-9
+        ORDER BY p.codigo_interno ASC, pr.id_presentacion ASC, a.id_almacen ASC",
+        movimiento_service::CANTIDAD_CON_SIGNO
+    );
+    let lineas = sqlx::query_as::<_, LineaValuacion>(&sql)
+        .bind(filtro.fecha_corte.as_deref().unwrap_or(SIN_CORTE))
+        .bind(filtro.metodo.as_str())
+        .bind(filtro.id_almacen)
+        .fetch_all(executor)
+        .await
+        .map_err(AppError::from)?;
+
+    let mut por_categoria: Vec<SubtotalCategoria> = Vec::new();
+    let mut por_almacen: Vec<SubtotalAlmacen> = Vec::new();
+    for linea in &lineas {
+        match por_categoria.iter_mut().find(|s| s.categoria == linea.categoria) {
+            Some(subtotal) => subtotal.valor += linea.valor,
+            None => por_categoria.push(SubtotalCategoria {
+                categoria: linea.categoria.clone(),
+                valor: linea.valor,
+            }),
+        }
+        match por_almacen.iter_mut().find(|s| s.id_almacen == linea.id_almacen) {
+            Some(subtotal) => subtotal.valor += linea.valor,
+            None => por_almacen.push(SubtotalAlmacen {
+                id_almacen: linea.id_almacen,
+                almacen: linea.almacen.clone(),
+                valor: linea.valor,
+            }),
+        }
+    }
+    por_categoria.sort_by(|a, b| a.categoria.cmp(&b.categoria));
+    por_almacen.sort_by_key(|s| s.id_almacen);
+    let total = lineas.iter().map(|l| l.valor).sum();
+
+    Ok(ValuacionStock {
+        filtro,
+        lineas,
+        por_categoria,
+        por_almacen,
+        total,
+    })
+}
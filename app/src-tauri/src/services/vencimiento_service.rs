@@ -0,0 +1,72 @@
+use sqlx::SqlitePool;
+use crate::error::Result;
+use crate::models::vencimiento::{FiltroVencimiento, LoteVencimiento, ReporteVencimiento, RiesgoAlmacen};
+
+/// Lotes con saldo vencidos o que vencen dentro del plazo del filtro, del
+/// más próximo a vencer al más lejano, con su valor al costo del lote y el
+/// total en riesgo por almacén.
+pub async fn get_por_vencer(pool: &SqlitePool, filtro: FiltroVencimiento) -> Result<ReporteVencimiento> {
+    let fecha: String = sqlx::query_scalar("SELECT COALESCE(?, date('now'))")
+        .bind(&filtro.fecha)
+        .fetch_one(pool)
+        .await?;
+
+    let lotes = sqlx::query_as::<_, LoteVencimiento>(
+        "SELECT l.id_lote, l.id_almacen, a.nombre AS almacen, l.id_prod_prov, p.id_producto,
+                p.codigo_interno, p.descripcion, l.id_presentacion, pr.unidad, l.numero_lote, l.fecha_venc,
+                CAST(julianday(l.fecha_venc) - julianday(?1) AS INTEGER) AS dias_restantes,
+                l.fecha_venc < ?1 AS vencido,
+                l.cantidad_actual AS cantidad, l.costo_unitario,
+                l.cantidad_actual * l.costo_unitario AS valor
+         FROM lote l
+         JOIN producto_proveedor pp ON pp.id_prod_prov = l.id_prod_prov
+         JOIN producto p ON p.id_producto = pp.id_producto
+         JOIN presentacion pr ON pr.id_presentacion = l.id_presentacion
+         JOIN almacen a ON a.id_almacen = l.id_almacen
+         WHERE l.cantidad_actual > 1e-9 AND l.fecha_venc IS NOT NULL
+           AND l.fecha_venc <= date(?1, '+' || ?2 || ' days')
+           AND (?3 IS NULL OR l.id_almacen = ?3)
+         ORDER BY l.fecha_venc ASC, l.id_almacen ASC, l.id_lote ASC"
+    )
+    .bind(&fecha)
+    .bind(filtro.dias)
+    .bind(filtro.id_almacen)
+    .fetch_all(pool)
+    .await?;
+
+    let mut por_almacen: Vec<RiesgoAlmacen> = Vec::new();
+    for lote in &lotes {
+        let i = match por_almacen.iter().position(|r| r.id_almacen == lote.id_almacen) {
+            Some(i) => i,
+            None => {
+                por_almacen.push(RiesgoAlmacen {
+                    id_almacen: lote.id_almacen,
+                    almacen: lote.almacen.clone(),
+                    lotes_vencidos: 0,
+                    valor_vencido: 0.0,
+                    lotes_por_vencer: 0,
+                    valor_por_vencer: 0.0,
+                });
+                por_almacen.len() - 1
+            }
+        };
+        let riesgo = &mut por_almacen[i];
+        if lote.vencido {
+            riesgo.lotes_vencidos += 1;
+            riesgo.valor_vencido += lote.valor;
+        } else {
+            riesgo.lotes_por_vencer += 1;
+            riesgo.valor_por_vencer += lote.valor;
+        }
+    }
+    por_almacen.sort_by_key(|r| r.id_almacen);
+
+    Ok(ReporteVencimiento {
+        fecha,
+        dias: filtro.dias,
+        valor_vencido: por_almacen.iter().map(|r| r.valor_vencido).sum(),
+        valor_por_vencer: por_almacen.iter().map(|r| r.valor_por_vencer).sum(),
+        lotes,
+        por_almacen,
+    })
+}
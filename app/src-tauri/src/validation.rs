@@ -0,0 +1,184 @@
+use crate::error::{AppError, FieldError, Result};
+
+/// Valores permitidos en `estado` de productos, proveedores y sus relaciones
+pub const ESTADOS_REGISTRO: &[&str] = &["Activo", "Inactivo"];
+
+/// Reglas de validación de un DTO. Los comandos llaman a `validate` antes de
+/// pasar los datos al servicio.
+pub trait Validate: Sized {
+    /// Recorta espacios de los campos de texto antes de aplicar las reglas
+    fn normalize(&mut self) {}
+
+    /// Registra en `v` los errores de cada campo
+    fn rules(&self, v: &mut Validator);
+
+    /// Normaliza y valida; devuelve todos los errores de campo juntos
+    fn validate(mut self) -> Result<Self> {
+        self.normalize();
+        let mut v = Validator::default();
+        self.rules(&mut v);
+        v.finish().map(|_| self)
+    }
+}
+
+/// Acumula errores de campo. Solo se conserva el primer error de cada campo.
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        if !self.errors.iter().any(|e| e.field == field) {
+            self.errors.push(FieldError::new(field, message));
+        }
+    }
+
+    /// Campo obligatorio de texto, sin contar espacios
+    pub fn required(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.add(field, "Campo obligatorio");
+        }
+    }
+
+    /// En una actualización, un campo obligatorio puede omitirse pero no vaciarse
+    pub fn not_blank(&mut self, field: &str, value: Option<&str>) {
+        if let Some(value) = value {
+            self.required(field, value);
+        }
+    }
+
+    pub fn max_len(&mut self, field: &str, value: Option<&str>, max: usize) {
+        if value.is_some_and(|v| v.chars().count() > max) {
+            self.add(field, format!("Debe tener como máximo {} caracteres", max));
+        }
+    }
+
+    pub fn positive(&mut self, field: &str, value: Option<f64>) {
+        if value.is_some_and(|v| !v.is_finite() || v <= 0.0) {
+            self.add(field, "Debe ser mayor que cero");
+        }
+    }
+
+    pub fn non_negative(&mut self, field: &str, value: Option<f64>) {
+        if value.is_some_and(|v| !v.is_finite() || v < 0.0) {
+            self.add(field, "No puede ser negativo");
+        }
+    }
+
+    /// Identificador de otro registro
+    pub fn id(&mut self, field: &str, value: Option<i32>) {
+        if value.is_some_and(|v| v <= 0) {
+            self.add(field, "Debe seleccionar un registro válido");
+        }
+    }
+
+    /// Fecha con formato `AAAA-MM-DD` que exista en el calendario
+    pub fn date(&mut self, field: &str, value: Option<&str>) {
+        if value.is_some_and(|v| !is_date(v)) {
+            self.add(field, "Fecha inválida, se espera el formato AAAA-MM-DD");
+        }
+    }
+
+    pub fn one_of(&mut self, field: &str, value: Option<&str>, allowed: &[&str]) {
+        if value.is_some_and(|v| !allowed.contains(&v)) {
+            self.add(field, format!("Valor no permitido, se espera: {}", allowed.join(", ")));
+        }
+    }
+
+    pub fn email(&mut self, field: &str, value: Option<&str>) {
+        if value.is_some_and(|v| !is_email(v)) {
+            self.add(field, "Correo electrónico inválido");
+        }
+    }
+
+    pub fn phone(&mut self, field: &str, value: Option<&str>) {
+        if value.is_some_and(|v| !is_phone(v)) {
+            self.add(field, "Teléfono inválido");
+        }
+    }
+
+    pub fn finish(self) -> Result<()> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(self.errors))
+        }
+    }
+}
+
+/// Recorta un campo obligatorio
+pub fn trim(value: &mut String) {
+    let trimmed = value.trim();
+    if trimmed.len() != value.len() {
+        *value = trimmed.to_string();
+    }
+}
+
+/// Recorta un campo opcional conservando el texto vacío, para que la regla
+/// del campo lo rechace.
+pub fn trim_opt(value: &mut Option<String>) {
+    if let Some(v) = value {
+        trim(v);
+    }
+}
+
+/// Recorta un campo opcional y trata el texto vacío como ausente
+pub fn empty_as_none(value: &mut Option<String>) {
+    trim_opt(value);
+    if value.as_deref() == Some("") {
+        *value = None;
+    }
+}
+
+pub fn is_date(value: &str) -> bool {
+    let partes: Vec<&str> = value.split('-').collect();
+    let [anio, mes, dia] = partes.as_slice() else {
+        return false;
+    };
+    if anio.len() != 4 || mes.len() != 2 || dia.len() != 2 {
+        return false;
+    }
+    // `parse` acepta un signo; las fechas se comparan como texto y solo
+    // pueden tener dígitos
+    if !partes.iter().all(|p| p.bytes().all(|b| b.is_ascii_digit())) {
+        return false;
+    }
+    let (Ok(anio), Ok(mes), Ok(dia)) = (anio.parse::<u32>(), mes.parse::<u32>(), dia.parse::<u32>()) else {
+        return false;
+    };
+
+    let bisiesto = (anio % 4 == 0 && anio % 100 != 0) || anio % 400 == 0;
+    let dias_mes = match mes {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if bisiesto => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    (1..=dias_mes).contains(&dia)
+}
+
+pub fn is_email(value: &str) -> bool {
+    let Some((usuario, dominio)) = value.split_once('@') else {
+        return false;
+    };
+
+    !usuario.is_empty()
+        && !value.chars().any(char::is_whitespace)
+        && !dominio.contains('@')
+        && dominio.split('.').count() >= 2
+        && dominio.split('.').all(|parte| !parte.is_empty())
+}
+
+/// Dígitos con `+` inicial opcional y separadores comunes; entre 7 y 15 dígitos
+pub fn is_phone(value: &str) -> bool {
+    let cuerpo = value.strip_prefix('+').unwrap_or(value);
+    let digitos = cuerpo.chars().filter(char::is_ascii_digit).count();
+
+    cuerpo
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '-' | '(' | ')'))
+        && (7..=15).contains(&digitos)
+}